@@ -1,13 +1,17 @@
 use super::run_with::run_with_output;
+use crate::cli::AuditArgs;
 use crate::core::analyzers::audit::run_audit;
 use crate::core::error::{ErrorCode, Result, UpkeepError};
 use crate::core::output::print_json;
+use crate::core::platform::TargetFilter;
 
-pub async fn run(json: bool) -> Result<()> {
+pub async fn run(json: bool, args: AuditArgs) -> Result<()> {
     run_with_output(
         json,
         async {
-            tokio::task::spawn_blocking(run_audit)
+            let targets = TargetFilter::from_args(&args.target.targets)?;
+            let features = args.feature_selection.selection();
+            tokio::task::spawn_blocking(move || run_audit(&targets, &features))
                 .await
                 .map_err(map_audit_join_error)?
         },
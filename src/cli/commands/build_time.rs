@@ -0,0 +1,59 @@
+use super::run_with::run_with_output;
+use crate::cli::BuildTimeArgs;
+use crate::core::analyzers::build_time::run_build_time;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{print_json, BuildTimeOutput};
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: BuildTimeArgs) -> Result<()> {
+    let fail_on_new = args.fail_on_new;
+    run_with_output(
+        json,
+        async {
+            let targets = TargetFilter::from_args(&args.target.targets)?;
+            let features = args.feature_selection.selection();
+            let include_dev = !args.no_dev;
+            let baseline = args.baseline;
+            tokio::task::spawn_blocking(move || {
+                run_build_time(include_dev, &targets, &features, baseline.as_deref())
+            })
+            .await
+            .map_err(|err| {
+                UpkeepError::message(
+                    ErrorCode::TaskFailed,
+                    format!("build-time task failed: {err}"),
+                )
+            })?
+        },
+        |output| {
+            print_json(output)?;
+            check_new(output, fail_on_new)
+        },
+        |output| {
+            print!("{output}");
+            check_new(output, fail_on_new)
+        },
+    )
+    .await
+}
+
+/// Fails after the report is printed, so CI logs still show the new crates.
+fn check_new(output: &BuildTimeOutput, fail_on_new: bool) -> Result<()> {
+    let new: Vec<&str> = output
+        .crates
+        .iter()
+        .filter(|krate| krate.new)
+        .map(|krate| krate.name.as_str())
+        .collect();
+    if fail_on_new && !new.is_empty() {
+        return Err(UpkeepError::message(
+            ErrorCode::InvalidData,
+            format!(
+                "{} build-time crate(s) not in the baseline: {}",
+                new.len(),
+                new.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
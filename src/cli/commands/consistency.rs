@@ -0,0 +1,26 @@
+use super::run_with::run_with_output;
+use crate::core::analyzers::consistency::run_consistency;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::print_json;
+
+pub async fn run(json: bool) -> Result<()> {
+    run_with_output(
+        json,
+        async {
+            tokio::task::spawn_blocking(run_consistency)
+                .await
+                .map_err(|err| {
+                    UpkeepError::message(
+                        ErrorCode::TaskFailed,
+                        format!("consistency task failed: {err}"),
+                    )
+                })?
+        },
+        print_json,
+        |output| {
+            print!("{output}");
+            Ok(())
+        },
+    )
+    .await
+}
@@ -5,24 +5,48 @@ use semver::Version;
 use std::collections::{HashMap, HashSet};
 
 use super::run_with::run_with_output;
+use crate::cli::TargetArgs;
 use crate::core::analyzers::audit::run_audit;
 use crate::core::analyzers::crates_io::{CratesIoClient, VersionInfo};
+use crate::core::analyzers::git_deps::{commits_behind, ls_remote, staleness, RemoteRefs};
+use crate::core::analyzers::msrv::{
+    format_rust_version, latest_compatible_release, parse_rust_version,
+};
 use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
 use crate::core::output::{
     print_json, AuditSummary, DependencyType, DepsOutput, DepsSecurityOutput, DepsSecurityPackage,
-    DepsSecurityVulnerability, OutdatedPackage, Severity, SkipReason, SkippedDependency,
-    UpdateType,
+    DepsSecurityVulnerability, GitDependency, GitStatus, OutdatedPackage, Severity, SkipReason,
+    SkippedDependency, UpdateType,
 };
-
-pub async fn run(json: bool, include_security: bool) -> Result<()> {
-    run_with_output(json, analyze(include_security), print_json, |output| {
+use crate::core::platform::TargetFilter;
+use crate::core::source::GitSource;
+
+pub async fn run(
+    json: bool,
+    include_security: bool,
+    include_git: bool,
+    target: TargetArgs,
+) -> Result<()> {
+    let analysis = async {
+        let targets = TargetFilter::from_args(&target.targets)?;
+        analyze(include_security, include_git, &targets).await
+    };
+    run_with_output(json, analysis, print_json, |output| {
         println!("{output}");
         Ok(())
     })
     .await
 }
 
-pub async fn analyze(include_security: bool) -> Result<DepsOutput> {
+/// Reports outdated direct dependencies of the workspace members. Dependencies
+/// declared only for platforms outside `targets` are ignored. With
+/// `include_git`, git dependencies are compared against their remotes.
+pub async fn analyze(
+    include_security: bool,
+    include_git: bool,
+    targets: &TargetFilter,
+) -> Result<DepsOutput> {
     let metadata = load_metadata()?;
     // Compute workspace flag before checking for root package to match detect.rs logic:
     // A workspace exists if there's no root package (virtual workspace) OR multiple members
@@ -36,13 +60,15 @@ pub async fn analyze(include_security: bool) -> Result<DepsOutput> {
     let (member_names, skipped_members) = compute_workspace_info(&metadata, &packages_by_id);
 
     let (dependency_names, mut skipped_packages, dependencies) =
-        partition_dependencies(&member_packages);
+        partition_dependencies(&member_packages, targets);
+    let msrv_by_dependency = build_msrv_by_dependency(&member_packages);
     let total_dependencies = member_packages
         .iter()
-        .map(|pkg| pkg.dependencies.len())
-        .sum();
+        .flat_map(|pkg| &pkg.dependencies)
+        .filter(|dep| targets.matches(dep.target.as_ref()))
+        .count();
     let security = if include_security {
-        Some(fetch_security(&dependencies, &resolved_versions).await?)
+        Some(fetch_security(&dependencies, &resolved_versions, targets).await?)
     } else {
         None
     };
@@ -56,10 +82,17 @@ pub async fn analyze(include_security: bool) -> Result<DepsOutput> {
         );
     }
 
+    let git_dependencies = if include_git {
+        check_git_dependencies(&metadata, &member_packages, targets, registry_available).await?
+    } else {
+        Vec::new()
+    };
+
     let (packages, major, minor, patch) = process_dependencies(
         dependencies,
         &resolved_versions,
         &latest_versions,
+        &msrv_by_dependency,
         registry_available,
         &mut skipped_packages,
     )?;
@@ -73,6 +106,7 @@ pub async fn analyze(include_security: bool) -> Result<DepsOutput> {
         packages,
         skipped: skipped_packages.len(),
         skipped_packages,
+        git_dependencies,
         warnings,
         security,
         workspace: is_workspace,
@@ -93,7 +127,7 @@ fn get_resolve(metadata: &Metadata) -> Result<&cargo_metadata::Resolve> {
     })
 }
 
-fn build_packages_map(metadata: &Metadata) -> HashMap<PackageId, &Package> {
+pub(super) fn build_packages_map(metadata: &Metadata) -> HashMap<PackageId, &Package> {
     metadata
         .packages
         .iter()
@@ -101,7 +135,7 @@ fn build_packages_map(metadata: &Metadata) -> HashMap<PackageId, &Package> {
         .collect()
 }
 
-fn workspace_packages<'a>(
+pub(super) fn workspace_packages<'a>(
     metadata: &'a Metadata,
     packages_by_id: &'a HashMap<PackageId, &'a Package>,
 ) -> Result<Vec<&'a Package>> {
@@ -186,8 +220,9 @@ fn compute_workspace_info(
     (member_names, Vec::new())
 }
 
-fn partition_dependencies<'a>(
+pub(super) fn partition_dependencies<'a>(
     members: &'a [&'a Package],
+    targets: &TargetFilter,
 ) -> (
     HashSet<String>,
     Vec<SkippedDependency>,
@@ -201,6 +236,9 @@ fn partition_dependencies<'a>(
 
     for member in members {
         for dep in &member.dependencies {
+            if !targets.matches(dep.target.as_ref()) {
+                continue;
+            }
             let dependency_type = convert_dependency_kind(dep.kind);
 
             let source = dep.source.as_ref().map(ToString::to_string);
@@ -233,7 +271,29 @@ fn partition_dependencies<'a>(
     (dependency_names, skipped_packages, dependencies)
 }
 
-fn convert_dependency_kind(kind: DependencyKind) -> DependencyType {
+/// Maps each dependency name to the lowest `rust-version` declared by the
+/// workspace members that depend on it.
+fn build_msrv_by_dependency(members: &[&Package]) -> HashMap<String, Version> {
+    let mut msrv_by_dependency: HashMap<String, Version> = HashMap::new();
+    for member in members {
+        let Some(msrv) = member.rust_version.as_ref() else {
+            continue;
+        };
+        for dep in &member.dependencies {
+            msrv_by_dependency
+                .entry(dep.name.clone())
+                .and_modify(|existing| {
+                    if msrv < existing {
+                        *existing = msrv.clone();
+                    }
+                })
+                .or_insert_with(|| msrv.clone());
+        }
+    }
+    msrv_by_dependency
+}
+
+pub(super) fn convert_dependency_kind(kind: DependencyKind) -> DependencyType {
     match kind {
         DependencyKind::Development => DependencyType::Dev,
         DependencyKind::Build => DependencyType::Build,
@@ -265,10 +325,167 @@ async fn fetch_latest_versions(
     }
 }
 
+/// Compares each direct git dependency's locked commit with the remote refs,
+/// counts the commits a branch pin is behind, and looks for a crates.io
+/// release under the same name. Remotes are queried concurrently.
+async fn check_git_dependencies(
+    metadata: &Metadata,
+    members: &[&Package],
+    targets: &TargetFilter,
+    registry_available: bool,
+) -> Result<Vec<GitDependency>> {
+    let mut declared = Vec::new();
+    let mut seen = HashSet::new();
+    for member in members {
+        for dep in &member.dependencies {
+            if !targets.matches(dep.target.as_ref()) {
+                continue;
+            }
+            let Some(source) = dep.source.as_ref().map(ToString::to_string) else {
+                continue;
+            };
+            let Some(git) = GitSource::parse(&source) else {
+                continue;
+            };
+            if !seen.insert((dep.name.clone(), source.clone())) {
+                continue;
+            }
+            // The declared source has no commit; the resolved package's does.
+            let commit = metadata
+                .packages
+                .iter()
+                .filter(|package| package.name.as_str() == dep.name)
+                .filter_map(|package| package.source.as_ref())
+                .find(|resolved| resolved.repr.starts_with(&source))
+                .and_then(|resolved| GitSource::parse(&resolved.repr))
+                .and_then(|resolved| resolved.commit);
+            declared.push((
+                dep,
+                convert_dependency_kind(dep.kind),
+                GitSource { commit, ..git },
+            ));
+        }
+    }
+    if declared.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut lookups = tokio::task::JoinSet::new();
+    for url in declared
+        .iter()
+        .map(|(_, _, git)| git.url.clone())
+        .collect::<HashSet<_>>()
+    {
+        lookups.spawn(async move {
+            let refs = ls_remote(&url).await.map_err(|err| err.to_string());
+            (url, refs)
+        });
+    }
+    let remotes: HashMap<String, std::result::Result<RemoteRefs, String>> =
+        join_all(lookups).await?.into_iter().collect();
+
+    let alternatives = if registry_available {
+        fetch_registry_alternatives(&declared).await
+    } else {
+        HashMap::new()
+    };
+
+    let mut git_dependencies = Vec::with_capacity(declared.len());
+    let mut counts = tokio::task::JoinSet::new();
+    for (dep, dependency_type, git) in declared {
+        let (status, latest_commit, latest_tag, pinned_tag, error) = match &remotes[&git.url] {
+            Ok(refs) => {
+                let staleness = staleness(&git, refs);
+                (
+                    staleness.status,
+                    staleness.latest_commit,
+                    staleness.latest_tag,
+                    staleness.pinned_tag,
+                    None,
+                )
+            }
+            Err(err) => (GitStatus::Unknown, None, None, None, Some(err.clone())),
+        };
+        // A branch pin that moved on: count the commits it is missing.
+        if let (GitStatus::Behind, true, Some(pinned)) =
+            (status, git.follows_branch(), git.commit.clone())
+        {
+            let index = git_dependencies.len();
+            let url = git.url.clone();
+            let branch = git.branch().map(ToString::to_string);
+            counts.spawn(async move {
+                let count = commits_behind(&url, branch.as_deref(), &pinned).await;
+                (index, count)
+            });
+        }
+        git_dependencies.push(GitDependency {
+            name: dep.name.clone(),
+            alias: dep.rename.clone(),
+            dependency_type,
+            registry_alternative: alternatives.get(&dep.name).cloned(),
+            url: git.url,
+            reference: git.reference,
+            pinned: git.commit,
+            status,
+            latest_commit,
+            commits_behind: None,
+            latest_tag,
+            pinned_tag,
+            error,
+        });
+    }
+    for (index, count) in join_all(counts).await? {
+        match count {
+            Ok(count) => git_dependencies[index].commits_behind = Some(count),
+            Err(err) => git_dependencies[index].error = Some(err.to_string()),
+        }
+    }
+
+    git_dependencies.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(git_dependencies)
+}
+
+/// Waits for every task in `tasks`, in completion order.
+async fn join_all<T: 'static>(mut tasks: tokio::task::JoinSet<T>) -> Result<Vec<T>> {
+    let mut results = Vec::with_capacity(tasks.len());
+    while let Some(result) = tasks.join_next().await {
+        results.push(result.map_err(|err| {
+            UpkeepError::message(ErrorCode::TaskFailed, format!("git task failed: {err}"))
+        })?);
+    }
+    Ok(results)
+}
+
+/// Looks names up one at a time, since most git crates are not published and
+/// a missing crate fails the whole batch.
+async fn fetch_registry_alternatives(
+    declared: &[(&Dependency, DependencyType, GitSource)],
+) -> HashMap<String, String> {
+    let Ok(crates_io) = CratesIoClient::new() else {
+        return HashMap::new();
+    };
+    let mut alternatives = HashMap::new();
+    for (dep, _, _) in declared {
+        if alternatives.contains_key(&dep.name) {
+            continue;
+        }
+        if let Ok(versions) = crates_io
+            .fetch_latest_versions(std::slice::from_ref(&dep.name), false)
+            .await
+        {
+            if let Some(latest) = versions.get(&dep.name).and_then(|info| info.latest.clone()) {
+                alternatives.insert(dep.name.clone(), latest);
+            }
+        }
+    }
+    alternatives
+}
+
 fn process_dependencies(
     dependencies: Vec<(&Dependency, DependencyType)>,
     resolved_versions: &HashMap<String, Version>,
     latest_versions: &HashMap<String, VersionInfo>,
+    msrv_by_dependency: &HashMap<String, Version>,
     registry_available: bool,
     skipped_packages: &mut Vec<SkippedDependency>,
 ) -> Result<(Vec<OutdatedPackage>, usize, usize, usize)> {
@@ -325,6 +542,13 @@ fn process_dependencies(
             UpdateType::Patch => patch += 1,
         }
 
+        let advice = msrv_advice(
+            latest_versions.get(&dep_name),
+            &latest,
+            &current,
+            msrv_by_dependency.get(&dep_name),
+        );
+
         packages.push(OutdatedPackage {
             name: dep_name,
             alias: dep.rename.clone(),
@@ -333,12 +557,59 @@ fn process_dependencies(
             required: dep.req.to_string(),
             update_type,
             dependency_type,
+            latest_compatible: advice.latest_compatible,
+            latest_rust_version: advice.latest_rust_version,
+            raises_msrv: advice.raises_msrv,
         });
     }
 
     Ok((packages, major, minor, patch))
 }
 
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MsrvAdvice {
+    latest_compatible: Option<String>,
+    latest_rust_version: Option<String>,
+    raises_msrv: bool,
+}
+
+/// Compares the `rust-version` of the latest release against the declared MSRV
+/// and finds the newest release that still fits it and is not older than
+/// `current`.
+fn msrv_advice(
+    info: Option<&VersionInfo>,
+    latest: &str,
+    current: &Version,
+    msrv: Option<&Version>,
+) -> MsrvAdvice {
+    let Some(info) = info else {
+        return MsrvAdvice::default();
+    };
+
+    let latest_rust_version = info
+        .versions
+        .iter()
+        .find(|release| release.num == latest)
+        .and_then(|release| release.rust_version.as_deref())
+        .and_then(parse_rust_version);
+
+    let Some(msrv) = msrv else {
+        return MsrvAdvice {
+            latest_rust_version: latest_rust_version.as_ref().map(format_rust_version),
+            ..MsrvAdvice::default()
+        };
+    };
+
+    MsrvAdvice {
+        latest_compatible: latest_compatible_release(&info.versions, msrv, current)
+            .map(|(release, _)| release.num.clone()),
+        raises_msrv: latest_rust_version
+            .as_ref()
+            .is_some_and(|required| required > msrv),
+        latest_rust_version: latest_rust_version.as_ref().map(format_rust_version),
+    }
+}
+
 fn resolve_current_version(
     dep_key: &str,
     dep_name: &str,
@@ -406,7 +677,7 @@ fn skip_missing_registry_info(
     });
 }
 
-fn classify_update(current: &Version, latest: &Version) -> UpdateType {
+pub(super) fn classify_update(current: &Version, latest: &Version) -> UpdateType {
     if latest.major > current.major {
         UpdateType::Major
     } else if latest.minor > current.minor {
@@ -419,17 +690,20 @@ fn classify_update(current: &Version, latest: &Version) -> UpdateType {
 async fn fetch_security(
     dependencies: &[(&Dependency, DependencyType)],
     resolved_versions: &HashMap<String, Version>,
+    targets: &TargetFilter,
 ) -> Result<DepsSecurityOutput> {
-    let audit_output = tokio::task::spawn_blocking(run_audit)
-        .await
-        .map_err(|err| {
-            let reason = if err.is_panic() {
-                "panicked"
-            } else {
-                "was cancelled"
-            };
-            UpkeepError::message(ErrorCode::TaskFailed, format!("audit task {reason}: {err}"))
-        })??;
+    let targets = targets.clone();
+    let audit_output =
+        tokio::task::spawn_blocking(move || run_audit(&targets, &FeatureSelection::default()))
+            .await
+            .map_err(|err| {
+                let reason = if err.is_panic() {
+                    "panicked"
+                } else {
+                    "was cancelled"
+                };
+                UpkeepError::message(ErrorCode::TaskFailed, format!("audit task {reason}: {err}"))
+            })??;
 
     let mut vulnerabilities_by_package: HashMap<(String, String), Vec<DepsSecurityVulnerability>> =
         HashMap::new();
@@ -490,28 +764,29 @@ async fn fetch_security(
     Ok(DepsSecurityOutput { summary, packages })
 }
 
-fn is_registry_source(source: Option<&String>) -> bool {
+pub(super) fn is_registry_source(source: Option<&String>) -> bool {
     // For Dependency.source:
     // - None means crates.io (default registry)
-    // - Some("registry+...") means another registry
+    // - Some("registry+...") or Some("sparse+...") means another registry
     // - Some("git+...") or Some("path+...") are non-registry sources
     match source {
         None => true, // crates.io
-        Some(s) => s.starts_with("registry+"),
+        Some(s) => s.starts_with("registry+") || s.starts_with("sparse+"),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        classify_update, get_latest_version, is_registry_source, resolve_current_version,
-        run_with_output,
+        build_msrv_by_dependency, classify_update, get_latest_version, is_registry_source,
+        msrv_advice, resolve_current_version, run_with_output, MsrvAdvice,
     };
-    use crate::core::analyzers::crates_io::VersionInfo;
+    use crate::core::analyzers::crates_io::{PublishedVersion, VersionInfo};
     use crate::core::error::{ErrorCode, UpkeepError};
     use crate::core::output::{
         DependencyType, DepsOutput, OutdatedPackage, SkipReason, SkippedDependency, UpdateType,
     };
+    use cargo_metadata::Package;
     use semver::Version;
     use serde_json::Value;
     use std::collections::HashMap;
@@ -565,6 +840,7 @@ mod tests {
                 name: "serde".to_string(),
                 latest: Some("1.0.200".to_string()),
                 latest_stable: None,
+                versions: Vec::new(),
             },
         );
 
@@ -572,13 +848,112 @@ mod tests {
         assert_eq!(latest.as_deref(), Some("1.0.200"));
     }
 
+    fn release(num: &str, rust_version: Option<&str>) -> PublishedVersion {
+        PublishedVersion {
+            num: num.to_string(),
+            rust_version: rust_version.map(str::to_string),
+            yanked: false,
+        }
+    }
+
+    #[test]
+    fn msrv_advice_flags_updates_that_raise_msrv() {
+        let info = VersionInfo {
+            name: "tokio".to_string(),
+            latest: Some("1.40.0".to_string()),
+            latest_stable: Some("1.40.0".to_string()),
+            versions: vec![
+                release("1.40.0", Some("1.80")),
+                release("1.38.0", Some("1.70")),
+                release("1.29.0", Some("1.56")),
+            ],
+        };
+
+        let current = Version::new(1, 29, 0);
+        let advice = msrv_advice(
+            Some(&info),
+            "1.40.0",
+            &current,
+            Some(&Version::new(1, 74, 0)),
+        );
+        assert_eq!(
+            advice,
+            MsrvAdvice {
+                latest_compatible: Some("1.38.0".to_string()),
+                latest_rust_version: Some("1.80".to_string()),
+                raises_msrv: true,
+            }
+        );
+
+        let fits = msrv_advice(
+            Some(&info),
+            "1.40.0",
+            &current,
+            Some(&Version::new(1, 80, 0)),
+        );
+        assert!(!fits.raises_msrv);
+        assert_eq!(fits.latest_compatible.as_deref(), Some("1.40.0"));
+
+        let ahead = msrv_advice(
+            Some(&info),
+            "1.40.0",
+            &Version::new(1, 39, 0),
+            Some(&Version::new(1, 74, 0)),
+        );
+        assert!(ahead.raises_msrv);
+        assert!(ahead.latest_compatible.is_none());
+
+        let undeclared = msrv_advice(Some(&info), "1.40.0", &current, None);
+        assert!(!undeclared.raises_msrv);
+        assert!(undeclared.latest_compatible.is_none());
+        assert_eq!(undeclared.latest_rust_version.as_deref(), Some("1.80"));
+    }
+
+    #[test]
+    fn build_msrv_by_dependency_uses_lowest_member_msrv() {
+        let member = |name: &str, rust_version: Option<&str>| -> Package {
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "version": "0.1.0",
+                "id": format!("{name} 0.1.0"),
+                "source": null,
+                "dependencies": [{
+                    "name": "serde",
+                    "source": "registry+https://github.com/rust-lang/crates.io-index",
+                    "req": "^1",
+                    "kind": null,
+                    "optional": false,
+                    "uses_default_features": true,
+                    "features": [],
+                    "target": null,
+                    "rename": null,
+                    "registry": null,
+                }],
+                "targets": [],
+                "features": {},
+                "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+                "edition": "2021",
+                "rust_version": rust_version,
+            }))
+            .expect("package")
+        };
+        let new = member("new", Some("1.80"));
+        let old = member("old", Some("1.65"));
+        let unset = member("unset", None);
+
+        let msrv = build_msrv_by_dependency(&[&new, &unset, &old]);
+        assert_eq!(msrv.get("serde"), Some(&Version::new(1, 65, 0)));
+    }
+
     #[test]
     fn is_registry_source_handles_registry_and_non_registry() {
         let registry = Some("registry+https://example.com".to_string());
+        let sparse = Some("sparse+https://example.com/index/".to_string());
         let git = Some("git+https://example.com/repo.git".to_string());
 
         assert!(is_registry_source(None));
         assert!(is_registry_source(registry.as_ref()));
+        assert!(is_registry_source(sparse.as_ref()));
         assert!(!is_registry_source(git.as_ref()));
     }
 
@@ -618,6 +993,9 @@ mod tests {
                 required: "^1".to_string(),
                 update_type: UpdateType::Major,
                 dependency_type: DependencyType::Normal,
+                latest_compatible: None,
+                latest_rust_version: None,
+                raises_msrv: false,
             }],
             skipped: 1,
             skipped_packages: vec![SkippedDependency {
@@ -629,6 +1007,7 @@ mod tests {
                 source: None,
                 target: None,
             }],
+            git_dependencies: Vec::new(),
             warnings: vec!["registry unavailable".to_string()],
             security: None,
             workspace: false,
@@ -0,0 +1,315 @@
+use cargo_metadata::{MetadataCommand, Package, PackageId};
+use semver::{Version, VersionReq};
+use std::collections::{HashMap, HashSet};
+
+use super::run_with::run_with_output;
+use crate::cli::DuplicatesArgs;
+use crate::core::analyzers::crates_io::{CratesIoClient, PublishedDependency, VersionInfo};
+use crate::core::analyzers::duplicates::{find_duplicates, DuplicateGroup};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{
+    print_json, DuplicateCrate, DuplicateParent, DuplicateVersion, DuplicatesOutput,
+    UnifySuggestion,
+};
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: DuplicatesArgs) -> Result<()> {
+    run_with_output(json, analyze(&args), print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+async fn analyze(args: &DuplicatesArgs) -> Result<DuplicatesOutput> {
+    let features = args.feature_selection.selection();
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let targets = TargetFilter::from_args(&args.target.targets)?;
+    let graph = ResolveGraph::from_metadata(&metadata, !args.no_dev, &targets)?;
+
+    let roots: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let members: HashSet<&PackageId> = metadata.workspace_members.iter().collect();
+    let groups = find_duplicates(&graph, &roots);
+
+    let mut warnings = Vec::new();
+    let upgradable: Vec<String> = groups
+        .iter()
+        .flat_map(older_parents)
+        .filter(|parent| is_registry(parent))
+        .map(|parent| parent.name.to_string())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let registry = if args.offline || upgradable.is_empty() {
+        None
+    } else {
+        connect(&upgradable, &mut warnings).await
+    };
+
+    let mut duplicates = Vec::new();
+    for group in &groups {
+        let suggestions = suggest(group, &members, registry.as_ref(), &mut warnings).await;
+        duplicates.push(DuplicateCrate {
+            name: group.name.clone(),
+            versions: group
+                .versions
+                .iter()
+                .map(|version| DuplicateVersion {
+                    version: version.package.version.to_string(),
+                    parents: version
+                        .parents
+                        .iter()
+                        .map(|parent| DuplicateParent {
+                            name: parent.parent.name.to_string(),
+                            version: parent.parent.version.to_string(),
+                            req: parent.req.clone(),
+                            workspace_member: members.contains(&parent.parent.id),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            impact: group.impact,
+            suggestions,
+        });
+    }
+
+    Ok(DuplicatesOutput {
+        duplicates,
+        warnings,
+    })
+}
+
+/// A crates.io client with the latest versions of the parents that could be
+/// upgraded already fetched.
+struct Registry {
+    client: CratesIoClient,
+    latest: HashMap<String, VersionInfo>,
+}
+
+async fn connect(names: &[String], warnings: &mut Vec<String>) -> Option<Registry> {
+    let client = match CratesIoClient::new() {
+        Ok(client) => client,
+        Err(err) => {
+            warnings.push(format!("failed to create crates.io client: {err}"));
+            return None;
+        }
+    };
+    match client.fetch_latest_versions(names, false).await {
+        Ok(latest) => Some(Registry { client, latest }),
+        Err(err) => {
+            warnings.push(format!("failed to fetch latest crate versions: {err}"));
+            None
+        }
+    }
+}
+
+/// Parents of every version except the newest, each listed once.
+fn older_parents<'a>(group: &DuplicateGroup<'a>) -> Vec<&'a Package> {
+    let mut seen = HashSet::new();
+    group.versions[..group.versions.len() - 1]
+        .iter()
+        .flat_map(|version| &version.parents)
+        .map(|parent| parent.parent)
+        .filter(|parent| seen.insert(&parent.id))
+        .collect()
+}
+
+fn is_registry(package: &Package) -> bool {
+    package
+        .source
+        .as_ref()
+        .is_some_and(|source| source.is_crates_io())
+}
+
+/// Works out, for each parent holding on to an older version, whether a change
+/// to that parent would let it use the newest version instead.
+async fn suggest(
+    group: &DuplicateGroup<'_>,
+    members: &HashSet<&PackageId>,
+    registry: Option<&Registry>,
+    warnings: &mut Vec<String>,
+) -> Vec<UnifySuggestion> {
+    let newest = &group.newest().version;
+    let mut suggestions = Vec::new();
+    for parent in older_parents(group) {
+        if members.contains(&parent.id) {
+            suggestions.push(UnifySuggestion {
+                parent: parent.name.to_string(),
+                parent_version: parent.version.to_string(),
+                upgrade_to: None,
+                requirement: Some(newest.to_string()),
+                unifies: true,
+            });
+            continue;
+        }
+        let Some(registry) = registry.filter(|_| is_registry(parent)) else {
+            continue;
+        };
+        let Some(latest) = registry
+            .latest
+            .get(parent.name.as_str())
+            .and_then(|info| info.latest.as_deref())
+            .and_then(|latest| Version::parse(latest).ok())
+            .filter(|latest| *latest > parent.version)
+        else {
+            continue;
+        };
+        match registry
+            .client
+            .fetch_dependencies(&parent.name, &latest.to_string())
+            .await
+        {
+            Ok(dependencies) => suggestions.push(upgrade_suggestion(
+                parent,
+                &latest,
+                &dependencies,
+                &group.name,
+                newest,
+            )),
+            Err(err) => warnings.push(format!(
+                "failed to fetch dependencies of {} {latest}: {err}",
+                parent.name
+            )),
+        }
+    }
+    suggestions
+}
+
+/// Checks whether `parent` at release `latest`, whose declared dependencies
+/// are `dependencies`, accepts `newest` of the crate `name`.
+fn upgrade_suggestion(
+    parent: &Package,
+    latest: &Version,
+    dependencies: &[PublishedDependency],
+    name: &str,
+    newest: &Version,
+) -> UnifySuggestion {
+    let requirement = dependencies
+        .iter()
+        .find(|dep| dep.name == name && dep.kind != "dev")
+        .map(|dep| dep.req.clone());
+    let unifies = requirement.as_deref().map_or(true, |req| {
+        VersionReq::parse(req).is_ok_and(|req| req.matches(newest))
+    });
+    UnifySuggestion {
+        parent: parent.name.to_string(),
+        parent_version: parent.version.to_string(),
+        upgrade_to: Some(latest.to_string()),
+        requirement,
+        unifies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parent() -> Package {
+        serde_json::from_value(json!({
+            "name": "old_parent",
+            "version": "1.0.0",
+            "id": "registry+https://github.com/rust-lang/crates.io-index#old_parent@1.0.0",
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": "/tmp/old_parent/Cargo.toml",
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn dependency(name: &str, req: &str, kind: &str) -> PublishedDependency {
+        PublishedDependency {
+            name: name.to_string(),
+            req: req.to_string(),
+            kind: kind.to_string(),
+            optional: false,
+        }
+    }
+
+    #[test]
+    fn upgrade_suggestion_checks_the_new_requirement() {
+        let parent = parent();
+        assert!(is_registry(&parent));
+        let latest = Version::parse("1.2.0").unwrap();
+        let newest = Version::parse("2.0.50").unwrap();
+
+        let unifies = upgrade_suggestion(
+            &parent,
+            &latest,
+            &[dependency("syn", "^2.0", "normal")],
+            "syn",
+            &newest,
+        );
+        assert!(unifies.unifies);
+        assert_eq!(unifies.upgrade_to.as_deref(), Some("1.2.0"));
+        assert_eq!(unifies.requirement.as_deref(), Some("^2.0"));
+
+        let stuck = upgrade_suggestion(
+            &parent,
+            &latest,
+            &[
+                dependency("syn", "^2.0", "dev"),
+                dependency("syn", "^1.0", "normal"),
+            ],
+            "syn",
+            &newest,
+        );
+        assert!(!stuck.unifies);
+        assert_eq!(stuck.requirement.as_deref(), Some("^1.0"));
+
+        let dropped = upgrade_suggestion(&parent, &latest, &[], "syn", &newest);
+        assert!(dropped.unifies);
+        assert!(dropped.requirement.is_none());
+    }
+
+    #[test]
+    fn duplicates_output_renders_parents_and_suggestions() {
+        let output = DuplicatesOutput {
+            duplicates: vec![DuplicateCrate {
+                name: "syn".to_string(),
+                versions: vec![
+                    DuplicateVersion {
+                        version: "1.0.109".to_string(),
+                        parents: vec![DuplicateParent {
+                            name: "old_parent".to_string(),
+                            version: "1.0.0".to_string(),
+                            req: Some("^1".to_string()),
+                            workspace_member: false,
+                        }],
+                    },
+                    DuplicateVersion {
+                        version: "2.0.50".to_string(),
+                        parents: Vec::new(),
+                    },
+                ],
+                impact: 3,
+                suggestions: vec![UnifySuggestion {
+                    parent: "old_parent".to_string(),
+                    parent_version: "1.0.0".to_string(),
+                    upgrade_to: Some("1.2.0".to_string()),
+                    requirement: Some("^2.0".to_string()),
+                    unifies: true,
+                }],
+            }],
+            warnings: Vec::new(),
+        };
+
+        let rendered = output.to_string();
+        assert!(rendered.contains("syn (1.0.109, 2.0.50) - 3 crate(s) duplicated"));
+        assert!(rendered.contains("    - old_parent v1.0.0 requires ^1"));
+        assert!(rendered.contains(
+            "suggestion: upgrade old_parent 1.0.0 -> 1.2.0 (requires syn ^2.0, unifies on 2.0.50)"
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+use cargo_metadata::MetadataCommand;
+
+use super::run_with::run_with_output;
+use crate::cli::FeaturesArgs;
+use crate::core::analyzers::features::{analyze_features, check_powerset, member_powerset};
+use crate::core::analyzers::msrv::first_error_line;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{print_json, FeaturesOutput};
+
+pub async fn run(json: bool, args: FeaturesArgs) -> Result<()> {
+    run_with_output(json, analyze(args), print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+async fn analyze(args: FeaturesArgs) -> Result<FeaturesOutput> {
+    let mut warnings = Vec::new();
+    // Resolved packages are needed to check `dep/feature` entries and
+    // dependency defaults. Cargo refuses to resolve some of the broken entries
+    // this command exists to report, so fall back to the manifests alone.
+    let metadata = match MetadataCommand::new().exec() {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            let message = first_error_line(&err.to_string());
+            let reason = message.rsplit("error: ").next().unwrap_or(&message);
+            warnings.push(format!(
+                "dependencies could not be resolved ({reason}); only workspace members were checked for dependency features"
+            ));
+            MetadataCommand::new().no_deps().exec().map_err(|err| {
+                UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+            })?
+        }
+    };
+    let mut members = analyze_features(&metadata, args.package.as_deref())?;
+    if let Some(package) = &args.package {
+        if members.is_empty() {
+            return Err(UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("{package} is not a workspace member"),
+            ));
+        }
+    }
+
+    if args.powerset {
+        let root = metadata.workspace_root.as_std_path();
+        let combinations: Vec<Vec<Vec<String>>> = members
+            .iter()
+            .map(|member| member_powerset(member, args.depth))
+            .collect();
+        let total: usize = combinations.iter().map(Vec::len).sum();
+        // Each combination is a separate `cargo check`, so say up front how
+        // many will run. Stderr keeps `--json` output parseable.
+        eprintln!(
+            "Checking {total} feature combination{} across {} member{}",
+            if total == 1 { "" } else { "s" },
+            members.len(),
+            if members.len() == 1 { "" } else { "s" }
+        );
+        for (member, combinations) in members.iter_mut().zip(combinations) {
+            member.powerset = check_powerset(root, &member.name, combinations).await;
+        }
+    }
+
+    Ok(FeaturesOutput { members, warnings })
+}
@@ -0,0 +1,287 @@
+use cargo_metadata::{Dependency, MetadataCommand, Package};
+use semver::VersionReq;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+use super::deps::{
+    build_packages_map, convert_dependency_kind, is_registry_source, partition_dependencies,
+    workspace_packages,
+};
+use super::run_with::run_with_output;
+use crate::cli::InheritArgs;
+use crate::core::analyzers::consistency::{compatible_line, minimum_version};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::manifest::{
+    dependency_registries, hoist_dependency, inherited_dependencies, workspace_dependencies,
+    DependencyTable, Hoist,
+};
+use crate::core::output::{print_json, InheritCandidate, InheritDeclaration, InheritOutput};
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: InheritArgs) -> Result<()> {
+    run_with_output(
+        json,
+        async {
+            let apply = args.apply;
+            tokio::task::spawn_blocking(move || analyze(apply))
+                .await
+                .map_err(|err| {
+                    UpkeepError::message(
+                        ErrorCode::TaskFailed,
+                        format!("inherit task failed: {err}"),
+                    )
+                })?
+        },
+        print_json,
+        |output| {
+            print!("{output}");
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Groups the registry dependencies of every member by name and registry and
+/// reports the ones declared by two or more members without
+/// `workspace = true`.
+fn analyze(apply: bool) -> Result<InheritOutput> {
+    // Only declarations matter, so nothing needs resolving or downloading.
+    let metadata = MetadataCommand::new().no_deps().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let packages_by_id = build_packages_map(&metadata);
+    let members = workspace_packages(&metadata, &packages_by_id)?;
+    let root_manifest = metadata.workspace_root.join("Cargo.toml");
+    let in_workspace = workspace_dependencies(root_manifest.as_std_path())?;
+    let targets = TargetFilter::all();
+
+    let mut registries = HashMap::new();
+    let mut declared: BTreeMap<(String, Option<String>), Vec<Declaration>> = BTreeMap::new();
+    for member in &members {
+        let manifest_path = member.manifest_path.as_std_path();
+        let inherited = inherited_dependencies(manifest_path)?;
+        let member_registries = dependency_registries(manifest_path)?;
+        let (_, _, dependencies) = partition_dependencies(std::slice::from_ref(member), &targets);
+        for (dep, _) in dependencies {
+            // Renamed and path dependencies differ per member by design.
+            if dep.rename.is_some() || dep.path.is_some() {
+                continue;
+            }
+            let key = (table_of(dep), dep.name.clone());
+            let registry = member_registries.get(&key).cloned();
+            declared
+                .entry((dep.name.clone(), registry))
+                .or_default()
+                .push((member, dep, inherited.contains(&key)));
+        }
+        registries.insert(member.name.as_str(), member_registries);
+    }
+
+    // Groups worth moving; a name may only be hoisted from one registry.
+    declared.retain(|_, declarations| {
+        let declaring: HashSet<&str> = declarations
+            .iter()
+            .map(|(member, _, _)| member.name.as_str())
+            .collect();
+        declaring.len() >= 2 && declarations.iter().any(|(_, _, inherited)| !inherited)
+    });
+    let mut registries_by_name: HashMap<&str, usize> = HashMap::new();
+    for (name, _) in declared.keys() {
+        *registries_by_name.entry(name).or_default() += 1;
+    }
+
+    let mut candidates = Vec::new();
+    let mut hoists = Vec::new();
+    let mut warnings = Vec::new();
+    let mut conflicting = HashSet::new();
+    for ((name, registry), declarations) in &declared {
+        let pending: Vec<&Declaration> = declarations
+            .iter()
+            .filter(|(_, _, inherited)| !inherited)
+            .collect();
+
+        let existing = in_workspace.get(name).cloned();
+        let requirement = existing.as_ref().map_or_else(
+            || suggested_requirement(pending.iter().map(|(_, dep, _)| &dep.req)),
+            |existing| existing.requirement.clone(),
+        );
+        let default_features: HashSet<bool> = pending
+            .iter()
+            .map(|(_, dep, _)| dep.uses_default_features)
+            .collect();
+        // Members only inherit one requirement when it stays in the
+        // semver-compatible line each of them asked for. Requirements open to
+        // later lines (`*`, `>=1.0`) fit whichever line the others pick.
+        let mut lines: BTreeSet<(u64, u64, u64)> = pending
+            .iter()
+            .filter_map(|(_, dep, _)| compatible_line(&dep.req))
+            .collect();
+        let members_compatible = lines.len() <= 1;
+        let workspace_req = VersionReq::parse(&requirement).ok();
+        lines.extend(workspace_req.as_ref().and_then(compatible_line));
+        if registries_by_name[name.as_str()] > 1 {
+            if conflicting.insert(name.as_str()) {
+                warnings.push(format!(
+                    "{name}: members use different registries, not moved automatically"
+                ));
+            }
+        } else if existing
+            .as_ref()
+            .is_some_and(|existing| &existing.registry != registry)
+        {
+            warnings.push(format!(
+                "{name}: workspace entry uses a different registry, not moved automatically"
+            ));
+        } else if !members_compatible {
+            warnings.push(format!(
+                "{name}: members require incompatible versions, not moved automatically"
+            ));
+        } else if workspace_req.is_none() {
+            warnings.push(format!(
+                "{name}: workspace entry has no version requirement, not moved automatically"
+            ));
+        } else if lines.len() > 1 {
+            warnings.push(format!(
+                "{name}: workspace requirement {requirement} is incompatible with the members', not moved automatically"
+            ));
+        } else if default_features.len() > 1 {
+            warnings.push(format!(
+                "{name}: members disagree on default-features, not moved automatically"
+            ));
+        } else {
+            hoists.push(Hoist {
+                name: name.clone(),
+                requirement: requirement.clone(),
+                registry: registry.clone(),
+                default_features: default_features.contains(&true),
+                declarations: pending_tables(
+                    &members,
+                    &registries,
+                    name,
+                    registry.as_deref(),
+                    &member_names(&pending),
+                ),
+            });
+        }
+
+        candidates.push(InheritCandidate {
+            name: name.clone(),
+            requirement,
+            registry: registry.clone(),
+            in_workspace: existing.is_some(),
+            declarations: declarations
+                .iter()
+                .map(|(member, dep, inherited)| InheritDeclaration {
+                    member: member.name.to_string(),
+                    dependency_type: convert_dependency_kind(dep.kind),
+                    target: dep.target.as_ref().map(ToString::to_string),
+                    requirement: dep.req.to_string(),
+                    features: dep.features.clone(),
+                    default_features: dep.uses_default_features,
+                    inherited: *inherited,
+                })
+                .collect(),
+        });
+    }
+
+    let mut applied = Vec::new();
+    if apply {
+        for hoist in &hoists {
+            hoist_dependency(root_manifest.as_std_path(), hoist)?;
+            applied.push(hoist.name.clone());
+        }
+    }
+
+    Ok(InheritOutput {
+        candidates,
+        applied,
+        warnings,
+    })
+}
+
+/// A member, one of its dependencies, and whether it is declared with
+/// `workspace = true`.
+type Declaration<'a> = (&'a Package, &'a Dependency, bool);
+
+fn table_of(dep: &Dependency) -> DependencyTable {
+    DependencyTable {
+        kind: dep.kind,
+        target: dep.target.as_ref().map(ToString::to_string),
+    }
+}
+
+fn member_names<'a>(pending: &[&Declaration<'a>]) -> HashSet<&'a str> {
+    pending
+        .iter()
+        .map(|(member, _, _)| member.name.as_str())
+        .collect()
+}
+
+/// Every table in the given members that declares `name` from `registry`,
+/// including declarations `partition_dependencies` folded together.
+fn pending_tables(
+    members: &[&Package],
+    registries: &HashMap<&str, HashMap<(DependencyTable, String), String>>,
+    name: &str,
+    registry: Option<&str>,
+    member_names: &HashSet<&str>,
+) -> Vec<(PathBuf, DependencyTable)> {
+    let mut tables = Vec::new();
+    for member in members {
+        if !member_names.contains(member.name.as_str()) {
+            continue;
+        }
+        for dep in &member.dependencies {
+            let source = dep.source.as_ref().map(ToString::to_string);
+            if dep.name != name
+                || dep.rename.is_some()
+                || dep.path.is_some()
+                || !is_registry_source(source.as_ref())
+            {
+                continue;
+            }
+            let table = table_of(dep);
+            let declared_registry = registries
+                .get(member.name.as_str())
+                .and_then(|declared| declared.get(&(table.clone(), dep.name.clone())));
+            if declared_registry.map(String::as_str) != registry {
+                continue;
+            }
+            let entry = (member.manifest_path.clone().into_std_path_buf(), table);
+            if !tables.contains(&entry) {
+                tables.push(entry);
+            }
+        }
+    }
+    tables
+}
+
+/// The requirement with the highest minimum version, so no member ends up
+/// with an older crate than it asked for. `^` is dropped as cargo implies it.
+fn suggested_requirement<'a>(requirements: impl Iterator<Item = &'a VersionReq>) -> String {
+    let highest = requirements
+        .max_by_key(|req| minimum_version(req))
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "*".to_string());
+    match highest.strip_prefix('^') {
+        Some(bare) if !bare.contains(',') => bare.to_string(),
+        _ => highest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggested_requirement_prefers_the_highest_minimum() {
+        let requirements: Vec<VersionReq> = ["^1.0", "1.0.200", "~1.0.150", "*"]
+            .iter()
+            .map(|req| VersionReq::parse(req).unwrap())
+            .collect();
+        assert_eq!(suggested_requirement(requirements.iter()), "1.0.200");
+
+        let bounded = [VersionReq::parse(">=0.3, <0.5").unwrap()];
+        assert_eq!(suggested_requirement(bounded.iter()), ">=0.3, <0.5");
+    }
+}
@@ -0,0 +1,393 @@
+use rustsec::Lockfile;
+use semver::Version;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+use super::deps::classify_update;
+use super::run_with::run_with_output;
+use crate::cli::LockdiffArgs;
+use crate::core::analyzers::audit::{fetch_database, lockfile_vulnerabilities};
+use crate::core::analyzers::consistency::version_line;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{
+    print_json, LockChange, LockDiffOutput, LockDuplicate, LockPackage, LockSource,
+};
+
+pub async fn run(json: bool, args: LockdiffArgs) -> Result<()> {
+    run_with_output(
+        json,
+        async {
+            tokio::task::spawn_blocking(move || analyze(&args))
+                .await
+                .map_err(|err| {
+                    let reason = if err.is_panic() {
+                        "panicked"
+                    } else {
+                        "was cancelled"
+                    };
+                    UpkeepError::message(
+                        ErrorCode::TaskFailed,
+                        format!("lockdiff task {reason}: {err}"),
+                    )
+                })?
+        },
+        print_json,
+        |output| {
+            print!("{output}");
+            Ok(())
+        },
+    )
+    .await
+}
+
+fn analyze(args: &LockdiffArgs) -> Result<LockDiffOutput> {
+    let old = read_lockfile(&args.old)?;
+    let new = read_lockfile(&args.new)?;
+    let mut output = diff_lockfiles(&args.old, &old, &args.new, &new);
+
+    if !args.offline {
+        match fetch_database() {
+            Ok(db) => {
+                let known: HashSet<(String, String)> = lockfile_vulnerabilities(&db, &old)
+                    .into_iter()
+                    .map(|vulnerability| (vulnerability.id, vulnerability.package))
+                    .collect();
+                output.advisories = lockfile_vulnerabilities(&db, &new)
+                    .into_iter()
+                    .filter(|vulnerability| {
+                        !known.contains(&(vulnerability.id.clone(), vulnerability.package.clone()))
+                    })
+                    .collect();
+            }
+            Err(err) => output.warnings.push(err.to_string()),
+        }
+    }
+    Ok(output)
+}
+
+/// Loads a lockfile from a path, or from git when `spec` is not a path: a bare
+/// revision reads `Cargo.lock` in the current directory at that revision and
+/// `rev:path` picks the file.
+fn read_lockfile(spec: &str) -> Result<Lockfile> {
+    let contents = if is_path(spec) {
+        std::fs::read_to_string(spec).map_err(|err| {
+            UpkeepError::context(ErrorCode::Io, format!("failed to read {spec}"), err)
+        })?
+    } else {
+        let object = if spec.contains(':') {
+            spec.to_string()
+        } else {
+            format!("{spec}:./Cargo.lock")
+        };
+        let output = Command::new("git")
+            .args(["show", &object])
+            .output()
+            .map_err(|err| {
+                UpkeepError::context(ErrorCode::ExternalCommand, "failed to run git show", err)
+            })?;
+        if !output.status.success() {
+            return Err(UpkeepError::message(
+                ErrorCode::Config,
+                format!(
+                    "'{spec}' is neither a lockfile nor a git revision: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+        String::from_utf8(output.stdout).map_err(|err| {
+            UpkeepError::context(ErrorCode::Utf8, format!("{object} is not UTF-8"), err)
+        })?
+    };
+    Lockfile::from_str(&contents).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::InvalidData,
+            format!("failed to parse lockfile '{spec}'"),
+            err,
+        )
+    })
+}
+
+/// Whether `spec` names a file rather than a git revision: it exists, or it is
+/// an absolute path such as `/tmp/Cargo.lock` or `C:\x\Cargo.lock`, whose
+/// colon must not be read as `rev:path`.
+fn is_path(spec: &str) -> bool {
+    let path = Path::new(spec);
+    let drive = spec.as_bytes();
+    path.exists()
+        || path.is_absolute()
+        || (drive.len() > 2
+            && drive[0].is_ascii_alphabetic()
+            && drive[1] == b':'
+            && matches!(drive[2], b'\\' | b'/'))
+}
+
+/// Versions of each crate name, with the source of each version.
+fn versions_by_name(lockfile: &Lockfile) -> BTreeMap<String, BTreeMap<Version, Option<String>>> {
+    let mut versions: BTreeMap<String, BTreeMap<Version, Option<String>>> = BTreeMap::new();
+    for package in &lockfile.packages {
+        versions
+            .entry(package.name.to_string())
+            .or_default()
+            .insert(
+                package.version.clone(),
+                package.source.as_ref().map(ToString::to_string),
+            );
+    }
+    versions
+}
+
+fn diff_lockfiles(
+    old_label: &str,
+    old: &Lockfile,
+    new_label: &str,
+    new: &Lockfile,
+) -> LockDiffOutput {
+    let old_versions = versions_by_name(old);
+    let new_versions = versions_by_name(new);
+    let empty = BTreeMap::new();
+
+    let mut output = LockDiffOutput {
+        old: old_label.to_string(),
+        new: new_label.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        upgraded: Vec::new(),
+        downgraded: Vec::new(),
+        advisories: Vec::new(),
+        new_duplicates: Vec::new(),
+        new_sources: new_sources(old, new),
+        warnings: Vec::new(),
+    };
+
+    let names: BTreeSet<&String> = old_versions.keys().chain(new_versions.keys()).collect();
+    for name in names {
+        let before = old_versions.get(name).unwrap_or(&empty);
+        let after = new_versions.get(name).unwrap_or(&empty);
+        let removed: Versions = before
+            .iter()
+            .filter(|(version, _)| !after.contains_key(version))
+            .collect();
+        let added: Versions = after
+            .iter()
+            .filter(|(version, _)| !before.contains_key(version))
+            .collect();
+
+        let (pairs, removed, added) = pair_versions(removed, added);
+        for (from, to) in pairs {
+            let change = |current: &Version, next: &Version| LockChange {
+                name: name.clone(),
+                from: from.to_string(),
+                to: to.to_string(),
+                update_type: classify_update(current, next),
+            };
+            if to > from {
+                output.upgraded.push(change(from, to));
+            } else {
+                output.downgraded.push(change(to, from));
+            }
+        }
+        for (version, source) in removed {
+            output.removed.push(lock_package(name, version, source));
+        }
+        for (version, source) in added {
+            output.added.push(lock_package(name, version, source));
+        }
+
+        if after.len() > 1 && after.len() > before.len() {
+            output.new_duplicates.push(LockDuplicate {
+                name: name.clone(),
+                versions: after.keys().map(ToString::to_string).collect(),
+            });
+        }
+    }
+    output
+}
+
+type Versions<'a> = Vec<(&'a Version, &'a Option<String>)>;
+
+/// Pairs the removed and added versions of one crate into version changes.
+/// A removed version pairs with the added version in the same
+/// semver-compatible line, so `1.0 + 2.0 -> 2.1` reads as an upgrade of 2.0
+/// and a removal of 1.0. When exactly one version is left on each side, the
+/// crate moved to another line and those two are paired as well; anything
+/// else was added or removed outright.
+fn pair_versions<'a>(
+    removed: Versions<'a>,
+    mut added: Versions<'a>,
+) -> (Vec<(&'a Version, &'a Version)>, Versions<'a>, Versions<'a>) {
+    let mut pairs = Vec::new();
+    let mut unpaired = Vec::new();
+    for (from, source) in removed {
+        let line = version_line(from);
+        match added.iter().position(|(to, _)| version_line(to) == line) {
+            Some(index) => pairs.push((from, added.remove(index).0)),
+            None => unpaired.push((from, source)),
+        }
+    }
+    if let ([(from, _)], [(to, _)]) = (unpaired.as_slice(), added.as_slice()) {
+        pairs.push((*from, *to));
+        return (pairs, Vec::new(), Vec::new());
+    }
+    (pairs, unpaired, added)
+}
+
+fn lock_package(name: &str, version: &Version, source: &Option<String>) -> LockPackage {
+    LockPackage {
+        name: name.to_string(),
+        version: version.to_string(),
+        source: source.clone(),
+    }
+}
+
+/// Registries and git repositories used by `new` but not by `old`. Git
+/// sources are compared by repository, ignoring the pinned commit.
+fn new_sources(old: &Lockfile, new: &Lockfile) -> Vec<LockSource> {
+    let known: HashSet<String> = old
+        .packages
+        .iter()
+        .filter_map(|package| package.source.as_ref())
+        .map(|source| source.url().to_string())
+        .collect();
+
+    let mut sources: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for package in &new.packages {
+        let Some(source) = &package.source else {
+            continue;
+        };
+        let url = source.url().to_string();
+        if known.contains(&url) {
+            continue;
+        }
+        let kind = if source.is_git() {
+            "git"
+        } else if source.is_registry() {
+            "registry"
+        } else if source.is_path() {
+            "path"
+        } else {
+            "other"
+        };
+        sources
+            .entry((kind.to_string(), url))
+            .or_default()
+            .push(format!("{} {}", package.name, package.version));
+    }
+    sources
+        .into_iter()
+        .map(|((kind, url), packages)| LockSource {
+            kind,
+            url,
+            packages,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::output::UpdateType;
+
+    const REGISTRY: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+    fn lockfile(packages: &[(&str, &str, Option<&str>)]) -> Lockfile {
+        let mut contents = String::from("version = 3\n");
+        for (name, version, source) in packages {
+            contents.push_str(&format!(
+                "\n[[package]]\nname = \"{name}\"\nversion = \"{version}\"\n"
+            ));
+            if let Some(source) = source {
+                contents.push_str(&format!("source = \"{source}\"\n"));
+            }
+        }
+        Lockfile::from_str(&contents).expect("lockfile")
+    }
+
+    #[test]
+    fn diff_lockfiles_classifies_changes() {
+        let old = lockfile(&[
+            ("app", "0.1.0", None),
+            ("serde", "1.0.100", Some(REGISTRY)),
+            ("syn", "2.0.10", Some(REGISTRY)),
+            ("rand", "0.8.5", Some(REGISTRY)),
+            ("gone", "1.0.0", Some(REGISTRY)),
+        ]);
+        let new = lockfile(&[
+            ("app", "0.1.0", None),
+            ("serde", "1.0.200", Some(REGISTRY)),
+            ("syn", "1.0.109", Some(REGISTRY)),
+            ("syn", "2.0.10", Some(REGISTRY)),
+            ("rand", "0.7.3", Some(REGISTRY)),
+            ("fresh", "0.2.0", Some(REGISTRY)),
+            (
+                "forked",
+                "0.1.0",
+                Some("git+https://github.com/example/forked?branch=main#0123456789abcdef"),
+            ),
+        ]);
+
+        let diff = diff_lockfiles("old", &old, "new", &new);
+
+        let added: Vec<String> = diff
+            .added
+            .iter()
+            .map(|package| format!("{} {}", package.name, package.version))
+            .collect();
+        assert_eq!(added, vec!["forked 0.1.0", "fresh 0.2.0", "syn 1.0.109"]);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name, "gone");
+
+        assert_eq!(diff.upgraded.len(), 1);
+        assert_eq!(diff.upgraded[0].name, "serde");
+        assert!(matches!(diff.upgraded[0].update_type, UpdateType::Patch));
+
+        assert_eq!(diff.downgraded.len(), 1);
+        assert_eq!(diff.downgraded[0].from, "0.8.5");
+        assert_eq!(diff.downgraded[0].to, "0.7.3");
+        assert!(matches!(diff.downgraded[0].update_type, UpdateType::Minor));
+
+        assert_eq!(diff.new_duplicates.len(), 1);
+        assert_eq!(diff.new_duplicates[0].versions, vec!["1.0.109", "2.0.10"]);
+
+        assert_eq!(diff.new_sources.len(), 1);
+        assert_eq!(diff.new_sources[0].kind, "git");
+        assert_eq!(diff.new_sources[0].packages, vec!["forked 0.1.0"]);
+    }
+
+    #[test]
+    fn diff_lockfiles_pairs_versions_within_a_compatible_line() {
+        let old = lockfile(&[
+            ("syn", "1.0.100", Some(REGISTRY)),
+            ("syn", "2.0.10", Some(REGISTRY)),
+        ]);
+        let new = lockfile(&[("syn", "2.0.20", Some(REGISTRY))]);
+
+        let diff = diff_lockfiles("old", &old, "new", &new);
+
+        assert_eq!(diff.upgraded.len(), 1);
+        assert_eq!(diff.upgraded[0].from, "2.0.10");
+        assert_eq!(diff.upgraded[0].to, "2.0.20");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].version, "1.0.100");
+        assert!(diff.added.is_empty());
+    }
+
+    #[test]
+    fn is_path_does_not_mistake_drive_letters_for_revisions() {
+        assert!(is_path("C:\\work\\Cargo.lock"));
+        assert!(is_path("D:/work/Cargo.lock"));
+        assert!(!is_path("main"));
+        assert!(!is_path("main:crates/app/Cargo.lock"));
+        assert_eq!(
+            read_lockfile("C:\\missing\\Cargo.lock").unwrap_err().code(),
+            ErrorCode::Io
+        );
+    }
+
+    #[test]
+    fn read_lockfile_rejects_unknown_revisions() {
+        let err = read_lockfile("definitely-not-a-revision-or-file").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Config);
+    }
+}
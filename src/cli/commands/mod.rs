@@ -1,13 +1,27 @@
 //! Command dispatch and handlers.
 
 mod audit;
+mod build_time;
+mod consistency;
 mod deps;
 mod detect;
+mod duplicates;
+mod features;
+mod inherit;
+mod licenses;
+mod lockdiff;
+mod msrv;
+mod patches;
 mod quality;
 mod run_with;
+mod sbom;
+mod semver;
+mod sources;
 mod tree;
 mod unsafe_code;
 mod unused;
+mod weight;
+mod why;
 
 use crate::core::error::{ErrorCode, Result, UpkeepError};
 
@@ -32,12 +46,30 @@ pub async fn handle(command: UpkeepCommand, json: bool) -> Result<()> {
                 };
                 UpkeepError::message(ErrorCode::TaskFailed, format!("detect {reason}: {err}"))
             })?,
-        UpkeepCommand::Audit => audit::run(json).await,
-        UpkeepCommand::Deps { security } => deps::run(json, security).await,
-        UpkeepCommand::Quality => quality::run(json).await,
-        UpkeepCommand::Unused => unused::run(json).await,
+        UpkeepCommand::Audit(args) => audit::run(json, args).await,
+        UpkeepCommand::Deps {
+            security,
+            git,
+            target,
+        } => deps::run(json, security, git, target).await,
+        UpkeepCommand::Quality(args) => quality::run(json, args).await,
+        UpkeepCommand::Unused(args) => unused::run(json, args).await,
         UpkeepCommand::UnsafeCode => unsafe_code::run(json).await,
         UpkeepCommand::Tree(args) => tree::run(json, args).await,
+        UpkeepCommand::Msrv(args) => msrv::run(json, args).await,
+        UpkeepCommand::Why(args) => why::run(json, args).await,
+        UpkeepCommand::Duplicates(args) => duplicates::run(json, args).await,
+        UpkeepCommand::Weight(args) => weight::run(json, args).await,
+        UpkeepCommand::Lockdiff(args) => lockdiff::run(json, args).await,
+        UpkeepCommand::Semver(args) => semver::run(json, args).await,
+        UpkeepCommand::Licenses(args) => licenses::run(json, args).await,
+        UpkeepCommand::Sbom(args) => sbom::run(json, args).await,
+        UpkeepCommand::Sources(args) => sources::run(json, args).await,
+        UpkeepCommand::Patches(args) => patches::run(json, args).await,
+        UpkeepCommand::Inherit(args) => inherit::run(json, args).await,
+        UpkeepCommand::Consistency => consistency::run(json).await,
+        UpkeepCommand::Features(args) => features::run(json, args).await,
+        UpkeepCommand::BuildTime(args) => build_time::run(json, args).await,
     }
 }
 
@@ -55,13 +87,10 @@ mod tests {
         // Unsafe code requires cargo-geiger to be installed.
         let commands = [
             UpkeepCommand::Detect,
-            UpkeepCommand::Quality,
+            UpkeepCommand::Quality(crate::cli::QualityArgs::default()),
             UpkeepCommand::Tree(crate::cli::TreeArgs {
                 depth: Some(0),
-                duplicates: false,
-                invert: None,
-                features: false,
-                no_dev: false,
+                ..Default::default()
             }),
         ];
 
@@ -0,0 +1,292 @@
+use cargo_metadata::{Metadata, MetadataCommand, Package, PackageId};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use super::run_with::run_with_output;
+use crate::cli::MsrvArgs;
+use crate::core::analyzers::crates_io::{CratesIoClient, VersionInfo};
+use crate::core::analyzers::msrv::{
+    effective_msrv, format_rust_version, parse_rust_version, suggest_downgrade,
+};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::graph::{Edge, ResolveGraph};
+use crate::core::manifest::{
+    set_package_rust_version, set_workspace_rust_version, RustVersionSource,
+};
+use crate::core::output::{print_json, EffectiveMsrvMember, EffectiveMsrvOutput, MsrvDependency};
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: MsrvArgs) -> Result<()> {
+    run_with_output(json, analyze(args), print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+async fn analyze(args: MsrvArgs) -> Result<EffectiveMsrvOutput> {
+    let metadata = MetadataCommand::new().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let graph = ResolveGraph::from_metadata(&metadata, false, &TargetFilter::all())?;
+    let dependents = graph.inverted();
+    let mut warnings = Vec::new();
+
+    let mut members = Vec::new();
+    let mut suggestions: Vec<Suggestion<'_>> = Vec::new();
+    for member_id in &metadata.workspace_members {
+        let package = graph.package(member_id).ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("workspace member {member_id} missing from packages"),
+            )
+        })?;
+
+        let dependencies = graph.reachable_from(member_id);
+        let effective = effective_msrv(&dependencies);
+
+        // A downgrade can only lower the MSRV when a single registry crate forces it.
+        if let (Some(effective_version), [forcing]) =
+            (&effective.effective, effective.forced_by.as_slice())
+        {
+            if forcing
+                .source
+                .as_ref()
+                .is_some_and(|source| source.is_crates_io())
+            {
+                suggestions.push(Suggestion {
+                    member_index: members.len(),
+                    forcing,
+                    requirements: requirements_on(forcing, &dependents, &graph),
+                    effective: effective_version.clone(),
+                    next_highest: effective.next_highest.clone(),
+                });
+            }
+        }
+
+        members.push(EffectiveMsrvMember {
+            name: package.name.to_string(),
+            declared: package.rust_version.as_ref().map(format_rust_version),
+            effective: effective.effective.as_ref().map(format_rust_version),
+            forced_by: effective
+                .forced_by
+                .iter()
+                .map(|dep| MsrvDependency {
+                    name: dep.name.to_string(),
+                    version: dep.version.to_string(),
+                    rust_version: dep
+                        .rust_version
+                        .as_ref()
+                        .map(format_rust_version)
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            downgrade: None,
+            written: None,
+        });
+    }
+
+    let published = fetch_published_versions(&suggestions, &mut warnings).await;
+    for suggestion in &suggestions {
+        let Some(info) = published.get(suggestion.forcing.name.as_str()) else {
+            continue;
+        };
+        members[suggestion.member_index].downgrade = suggest_downgrade(
+            suggestion.forcing,
+            &suggestion.requirements,
+            &info.versions,
+            &suggestion.effective,
+            suggestion.next_highest.as_ref(),
+        );
+    }
+
+    if args.write {
+        write_rust_versions(&metadata, &graph, &mut members, &mut warnings)?;
+    }
+
+    members.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(EffectiveMsrvOutput { members, warnings })
+}
+
+/// A forcing crate whose older releases should be checked for a lower MSRV.
+struct Suggestion<'a> {
+    member_index: usize,
+    forcing: &'a Package,
+    requirements: Vec<VersionReq>,
+    effective: Version,
+    next_highest: Option<Version>,
+}
+
+/// Collects the version requirements that dependents place on `package`.
+fn requirements_on(
+    package: &Package,
+    dependents: &HashMap<PackageId, Vec<Edge>>,
+    graph: &ResolveGraph<'_>,
+) -> Vec<VersionReq> {
+    dependents
+        .get(&package.id)
+        .into_iter()
+        .flatten()
+        .filter_map(|edge| graph.package(&edge.id))
+        .flat_map(|parent| &parent.dependencies)
+        .filter(|dep| dep.name == package.name.as_str() && dep.req.matches(&package.version))
+        .map(|dep| dep.req.clone())
+        .collect()
+}
+
+async fn fetch_published_versions(
+    suggestions: &[Suggestion<'_>],
+    warnings: &mut Vec<String>,
+) -> HashMap<String, VersionInfo> {
+    let mut names: Vec<String> = suggestions
+        .iter()
+        .map(|suggestion| suggestion.forcing.name.to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return HashMap::new();
+    }
+
+    let crates_io = match CratesIoClient::new() {
+        Ok(client) => client,
+        Err(err) => {
+            warnings.push(format!("failed to create crates.io client: {err}"));
+            return HashMap::new();
+        }
+    };
+
+    match crates_io.fetch_latest_versions(&names, false).await {
+        Ok(versions) => versions,
+        Err(err) => {
+            warnings.push(format!(
+                "failed to fetch published versions; downgrade suggestions unavailable: {err}"
+            ));
+            HashMap::new()
+        }
+    }
+}
+
+/// Raises each member's `rust-version` to its effective MSRV.
+///
+/// Declared versions that are already at or above the effective MSRV are left
+/// alone, since the crate's own code may need the newer compiler. Members that
+/// inherit `rust-version` share a single value in `[workspace.package]`, which
+/// is set to the highest effective MSRV among them.
+fn write_rust_versions(
+    metadata: &Metadata,
+    graph: &ResolveGraph<'_>,
+    members: &mut [EffectiveMsrvMember],
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    let manifests: HashMap<&str, PathBuf> = metadata
+        .workspace_members
+        .iter()
+        .filter_map(|id| graph.package(id))
+        .map(|package| {
+            (
+                package.name.as_str(),
+                package.manifest_path.clone().into_std_path_buf(),
+            )
+        })
+        .collect();
+
+    let mut inherited: BTreeMap<Version, Vec<usize>> = BTreeMap::new();
+    for (index, member) in members.iter_mut().enumerate() {
+        let Some(effective) = member.effective.as_deref() else {
+            warnings.push(format!(
+                "{}: no dependency declares a rust-version; nothing to write",
+                member.name
+            ));
+            continue;
+        };
+        let effective_version = to_version(effective)?;
+        if let Some(declared) = member.declared.as_deref() {
+            if to_version(declared)? >= effective_version {
+                continue;
+            }
+        }
+
+        let manifest_path = manifests.get(member.name.as_str()).ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("manifest for {} not found in metadata", member.name),
+            )
+        })?;
+        match set_package_rust_version(manifest_path, effective)? {
+            RustVersionSource::Package => {
+                member.written = Some(manifest_path.display().to_string());
+            }
+            RustVersionSource::Workspace => {
+                inherited.entry(effective_version).or_default().push(index);
+            }
+        }
+    }
+
+    if let Some((version, _)) = inherited.last_key_value() {
+        let root_manifest = metadata
+            .workspace_root
+            .join("Cargo.toml")
+            .into_std_path_buf();
+        set_workspace_rust_version(&root_manifest, &format_rust_version(version))?;
+        for index in inherited.values().flatten() {
+            members[*index].written = Some(root_manifest.display().to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn to_version(value: &str) -> Result<Version> {
+    parse_rust_version(value).ok_or_else(|| {
+        UpkeepError::message(
+            ErrorCode::InvalidData,
+            format!("invalid rust-version: {value}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::output::{
+        EffectiveMsrvMember, EffectiveMsrvOutput, MsrvDependency, MsrvDowngrade,
+    };
+    use serde_json::Value;
+
+    #[test]
+    fn emit_output_json_shape() {
+        let output = EffectiveMsrvOutput {
+            members: vec![EffectiveMsrvMember {
+                name: "app".to_string(),
+                declared: Some("1.70".to_string()),
+                effective: Some("1.81".to_string()),
+                forced_by: vec![MsrvDependency {
+                    name: "home".to_string(),
+                    version: "0.5.11".to_string(),
+                    rust_version: "1.81".to_string(),
+                }],
+                downgrade: Some(MsrvDowngrade {
+                    name: "home".to_string(),
+                    from: "0.5.11".to_string(),
+                    to: "0.5.9".to_string(),
+                    rust_version: "1.70".to_string(),
+                    resulting_msrv: "1.74".to_string(),
+                }),
+                written: None,
+            }],
+            warnings: Vec::new(),
+        };
+
+        let value = serde_json::to_value(&output).expect("serialize");
+        let member = &value["members"][0];
+        assert_eq!(member["effective"], Value::String("1.81".into()));
+        assert_eq!(member["forced_by"][0]["name"], Value::String("home".into()));
+        assert_eq!(member["downgrade"]["to"], Value::String("0.5.9".into()));
+        assert!(member.get("written").is_none());
+
+        let rendered = output.to_string();
+        assert!(rendered.contains("app: effective MSRV 1.81 (declared 1.70)"));
+        assert!(rendered.contains("cargo update -p home@0.5.11 --precise 0.5.9"));
+    }
+}
@@ -0,0 +1,76 @@
+use cargo_metadata::MetadataCommand;
+use rustsec::Lockfile;
+use std::collections::HashSet;
+
+use super::run_with::run_with_output;
+use crate::cli::PatchesArgs;
+use crate::core::analyzers::crates_io::CratesIoClient;
+use crate::core::analyzers::patches::{apply_upstream, inventory, read_patches};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{print_json, PatchesOutput};
+
+pub async fn run(json: bool, args: PatchesArgs) -> Result<()> {
+    run_with_output(json, analyze(&args), print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+async fn analyze(args: &PatchesArgs) -> Result<PatchesOutput> {
+    let metadata = MetadataCommand::new().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    // Patches only take effect in the workspace root manifest.
+    let patches = read_patches(&metadata.workspace_root.join("Cargo.toml"))?;
+
+    let mut warnings = Vec::new();
+    let lockfile_path = metadata.workspace_root.join("Cargo.lock");
+    let unused: Vec<(String, String)> = match Lockfile::load(&lockfile_path) {
+        Ok(lockfile) => lockfile
+            .patch
+            .unused
+            .iter()
+            .map(|dep| (dep.name.to_string(), dep.version.to_string()))
+            .collect(),
+        Err(err) => {
+            warnings.push(format!("failed to load {lockfile_path}: {err}"));
+            Vec::new()
+        }
+    };
+    let mut entries = inventory(&metadata, &patches, &unused);
+
+    let names: Vec<String> = patches
+        .iter()
+        .filter(|patch| patch.targets_crates_io())
+        .map(|patch| patch.name.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if !args.offline && !names.is_empty() {
+        match CratesIoClient::new() {
+            Ok(client) => match client.fetch_latest_versions(&names, false).await {
+                Ok(latest) => {
+                    for (patch, entry) in patches.iter().zip(entries.iter_mut()) {
+                        if !patch.targets_crates_io() {
+                            continue;
+                        }
+                        if let Some(version) = latest
+                            .get(&patch.name)
+                            .and_then(|info| info.latest.as_deref())
+                        {
+                            apply_upstream(entry, version);
+                        }
+                    }
+                }
+                Err(err) => warnings.push(format!("failed to fetch latest crate versions: {err}")),
+            },
+            Err(err) => warnings.push(format!("failed to create crates.io client: {err}")),
+        }
+    }
+
+    Ok(PatchesOutput {
+        patches: entries,
+        warnings,
+    })
+}
@@ -1,47 +1,142 @@
-use cargo_metadata::MetadataCommand;
+use std::future::Future;
 
 use crate::cli::commands::deps;
+use crate::cli::{QualityArgs, QualityMetric};
 use crate::core::analyzers::{
-    audit::run_audit, clippy::run_clippy, unsafe_code::run_unsafe, unused::run_unused,
+    audit::run_audit,
+    clippy::run_clippy,
+    licenses::run_licenses,
+    msrv::{run_msrv, MsrvOptions},
+    semver_checks::{run_semver_checks, SemverOptions},
+    unsafe_code::run_unsafe,
+    unused::run_unused,
 };
 use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
 use crate::core::output::{
-    print_json, AuditOutput, ClippyOutput, DepsOutput, QualityOutput, UnsafeOutput, UnusedOutput,
+    print_json, AuditOutput, ClippyOutput, DepsOutput, LicensesOutput, MetricStatus, MsrvOutput,
+    MsrvState, QualityOutput, SemverOutput, ToolchainCheckStatus, UnsafeOutput, UnusedOutput,
 };
+use crate::core::platform::TargetFilter;
 use crate::core::scorers::quality::{
-    score_quality, ClippySummary, DependencyFreshness, MsrvStatus, QualityInputs, SecuritySummary,
-    UnsafeSummary, UnusedSummary,
+    score_quality, ClippySummary, DependencyFreshness, LicenseSummary, MetricInput, MsrvStatus,
+    QualityInputs, SecuritySummary, SemverSummary, UnavailablePolicy, UnsafeSummary, UnusedSummary,
 };
 
-pub async fn run(json: bool) -> Result<()> {
-    let deps_future = deps::analyze(false);
-    let audit_future = run_blocking("audit", run_audit);
-    let clippy_future = run_clippy();
-    let msrv_future = check_msrv();
-    let unused_future = run_unused();
-    let unsafe_future = run_unsafe();
+pub async fn run(json: bool, args: QualityArgs) -> Result<()> {
+    let selected = |metric| is_selected(&args, metric);
+    if !ALL_METRICS.iter().any(|metric| selected(*metric)) {
+        return Err(UpkeepError::message(
+            ErrorCode::Config,
+            "no quality metrics selected; check --only and --skip",
+        ));
+    }
 
-    let (deps_result, audit_result, clippy_result, msrv_result, unused_result, unsafe_result) = tokio::join!(
-        deps_future,
-        audit_future,
-        clippy_future,
-        msrv_future,
-        unused_future,
-        unsafe_future
+    let all_targets = TargetFilter::all();
+    let deps_future = run_if(
+        selected(QualityMetric::Deps),
+        deps::analyze(false, false, &all_targets),
+    );
+    let audit_future = run_if(
+        selected(QualityMetric::Security),
+        run_blocking("audit", || {
+            run_audit(&TargetFilter::all(), &FeatureSelection::default())
+        }),
+    );
+    let clippy_future = run_if(selected(QualityMetric::Clippy), run_clippy());
+    let msrv_future = run_if(
+        selected(QualityMetric::Msrv),
+        run_msrv(MsrvOptions {
+            verify_toolchain: args.verify_msrv,
+        }),
+    );
+    let unused_future = run_if(selected(QualityMetric::Unused), run_unused(&all_targets));
+    let unsafe_future = run_if(selected(QualityMetric::Unsafe), run_unsafe());
+    let semver_options = SemverOptions::default();
+    let semver_future = run_if(
+        selected(QualityMetric::Semver),
+        run_semver_checks(&semver_options),
+    );
+    let licenses_future = run_if(
+        selected(QualityMetric::Licenses),
+        run_blocking("licenses", || {
+            run_licenses(true, &TargetFilter::all(), &FeatureSelection::default())
+        }),
     );
 
-    let output = build_quality_output(
+    let (
         deps_result,
         audit_result,
         clippy_result,
         msrv_result,
         unused_result,
         unsafe_result,
+        semver_result,
+        licenses_result,
+    ) = tokio::join!(
+        deps_future,
+        audit_future,
+        clippy_future,
+        msrv_future,
+        unused_future,
+        unsafe_future,
+        semver_future,
+        licenses_future
     );
 
+    let output = build_quality_output(
+        QualityResults {
+            deps: deps_result,
+            audit: audit_result,
+            clippy: clippy_result,
+            msrv: msrv_result,
+            unused: unused_result,
+            unsafe_code: unsafe_result,
+            semver: semver_result,
+            licenses: licenses_result,
+        },
+        args.unavailable,
+    )?;
+
     emit_output(json, &output)
 }
 
+const ALL_METRICS: [QualityMetric; 8] = [
+    QualityMetric::Deps,
+    QualityMetric::Security,
+    QualityMetric::Unused,
+    QualityMetric::Unsafe,
+    QualityMetric::Clippy,
+    QualityMetric::Msrv,
+    QualityMetric::Semver,
+    QualityMetric::Licenses,
+];
+
+/// The semver metric needs cargo-semver-checks and a published baseline, and
+/// the license metric a policy, so both only run when requested with their
+/// flag or listed in `--only`.
+fn is_selected(args: &QualityArgs, metric: QualityMetric) -> bool {
+    let included = if args.only.is_empty() {
+        match metric {
+            QualityMetric::Semver => args.semver,
+            QualityMetric::Licenses => args.licenses,
+            _ => true,
+        }
+    } else {
+        args.only.contains(&metric)
+    };
+    included && !args.skip.contains(&metric)
+}
+
+/// Awaits `future` only when the metric is selected, so skipped analyzers never run.
+async fn run_if<T>(selected: bool, future: impl Future<Output = Result<T>>) -> Option<Result<T>> {
+    if selected {
+        Some(future.await)
+    } else {
+        None
+    }
+}
+
 fn emit_output(json: bool, output: &QualityOutput) -> Result<()> {
     if json {
         print_json(output)
@@ -51,118 +146,298 @@ fn emit_output(json: bool, output: &QualityOutput) -> Result<()> {
     }
 }
 
+/// Converts an analyzer result into a metric input.
+///
+/// `None` means the metric was skipped. Failures become unavailable metrics
+/// and also record a recommendation describing why.
+fn to_input<T, U>(
+    result: Option<Result<T>>,
+    label: &str,
+    recommendations: &mut Vec<String>,
+    convert: impl FnOnce(T) -> U,
+) -> MetricInput<U> {
+    match result {
+        None => MetricInput::Skipped,
+        Some(Ok(output)) => MetricInput::Measured(convert(output)),
+        Some(Err(err)) => {
+            recommendations.push(format!("{label} unavailable: {err}"));
+            MetricInput::Unavailable(err.to_string())
+        }
+    }
+}
+
+/// Analyzer results for each metric; `None` for metrics that were not selected.
+#[derive(Default)]
+struct QualityResults {
+    deps: Option<Result<DepsOutput>>,
+    audit: Option<Result<AuditOutput>>,
+    clippy: Option<Result<ClippyOutput>>,
+    msrv: Option<Result<MsrvOutput>>,
+    unused: Option<Result<UnusedOutput>>,
+    unsafe_code: Option<Result<UnsafeOutput>>,
+    semver: Option<Result<SemverOutput>>,
+    licenses: Option<Result<LicensesOutput>>,
+}
+
 fn build_quality_output(
-    deps_result: Result<DepsOutput>,
-    audit_result: Result<AuditOutput>,
-    clippy_result: Result<ClippyOutput>,
-    msrv_result: Result<MsrvStatus>,
-    unused_result: Result<UnusedOutput>,
-    unsafe_result: Result<UnsafeOutput>,
-) -> QualityOutput {
+    results: QualityResults,
+    policy: UnavailablePolicy,
+) -> Result<QualityOutput> {
     let mut extra_recommendations = Vec::new();
 
-    let dependency_freshness = match deps_result {
-        Ok(deps_output) => DependencyFreshness {
-            total: deps_output.total,
-            outdated: deps_output.outdated,
+    let dependency_freshness = to_input(
+        results.deps,
+        "Dependency freshness",
+        &mut extra_recommendations,
+        |output| DependencyFreshness {
+            total: output.total,
+            outdated: output.outdated,
         },
-        Err(err) => {
-            extra_recommendations.push(format!("Dependency freshness unavailable: {err}"));
-            DependencyFreshness {
-                total: 0,
-                outdated: 0,
-            }
-        }
-    };
+    );
 
-    let security = match audit_result {
-        Ok(output) => SecuritySummary {
+    let security = to_input(
+        results.audit,
+        "Security scan",
+        &mut extra_recommendations,
+        |output| SecuritySummary {
             critical: output.summary.critical,
             high: output.summary.high,
             moderate: output.summary.moderate,
             low: output.summary.low,
         },
-        Err(err) => {
-            extra_recommendations.push(format!("Security scan unavailable: {err}"));
-            SecuritySummary {
-                critical: 0,
-                high: 0,
-                moderate: 0,
-                low: 0,
-            }
-        }
-    };
+    );
 
-    let clippy = match clippy_result {
-        Ok(output) => Some(ClippySummary {
+    let clippy = to_input(
+        results.clippy,
+        "Clippy",
+        &mut extra_recommendations,
+        |output| ClippySummary {
             warnings: output.warnings,
             errors: output.errors,
-        }),
-        Err(err) => {
-            extra_recommendations.push(format!("Clippy unavailable: {err}"));
-            None
-        }
-    };
+        },
+    );
 
-    let unused = match unused_result {
-        Ok(output) => Some(UnusedSummary {
+    let unused = to_input(
+        results.unused,
+        "Unused dependencies",
+        &mut extra_recommendations,
+        |output| UnusedSummary {
             unused_count: output.unused.len(),
-        }),
-        Err(err) => {
-            extra_recommendations.push(format!("Unused dependencies unavailable: {err}"));
-            None
-        }
-    };
+        },
+    );
 
-    let unsafe_code = match unsafe_result {
-        Ok(output) => Some(UnsafeSummary {
+    let unsafe_code = to_input(
+        results.unsafe_code,
+        "Unsafe code scan",
+        &mut extra_recommendations,
+        |output| UnsafeSummary {
             total_unsafe: output.summary.total_unsafe,
-        }),
-        Err(err) => {
-            extra_recommendations.push(format!("Unsafe code scan unavailable: {err}"));
-            None
-        }
-    };
+        },
+    );
 
-    let msrv = match msrv_result {
-        Ok(status) => status,
-        Err(err) => {
-            extra_recommendations.push(format!("MSRV check unavailable: {err}"));
-            MsrvStatus::Missing
+    if let Some(Ok(output)) = &results.msrv {
+        extra_recommendations.extend(msrv_recommendations(output));
+    }
+    let msrv = to_input(
+        results.msrv,
+        "MSRV check",
+        &mut extra_recommendations,
+        |output| msrv_status(&output),
+    );
+
+    if let Some(Ok(output)) = &results.semver {
+        extra_recommendations.extend(semver_recommendations(output));
+    }
+    let semver = to_input(
+        results.semver,
+        "Semver check",
+        &mut extra_recommendations,
+        |output| semver_summary(&output),
+    );
+
+    if let Some(Ok(output)) = &results.licenses {
+        extra_recommendations.extend(license_recommendation(output));
+    }
+    let licenses = to_input(
+        results.licenses,
+        "License check",
+        &mut extra_recommendations,
+        |output| LicenseSummary {
+            violations: output.violations.len(),
+        },
+    );
+
+    let mut output = score_quality(
+        &QualityInputs {
+            dependency_freshness,
+            security,
+            unused,
+            unsafe_code,
+            clippy,
+            msrv,
+            semver,
+            licenses,
+        },
+        policy,
+    );
+
+    if policy == UnavailablePolicy::Fail {
+        let unavailable: Vec<String> = output
+            .breakdown
+            .iter()
+            .filter(|metric| metric.status == MetricStatus::Unavailable)
+            .map(|metric| {
+                format!(
+                    "{} ({})",
+                    metric.name,
+                    metric.reason.as_deref().unwrap_or("unknown reason")
+                )
+            })
+            .collect();
+        if !unavailable.is_empty() {
+            return Err(UpkeepError::message(
+                ErrorCode::TaskFailed,
+                format!("quality metrics unavailable: {}", unavailable.join("; ")),
+            ));
         }
-    };
+    }
 
-    let mut output = score_quality(&QualityInputs {
-        dependency_freshness,
-        security,
-        unused,
-        unsafe_code,
-        clippy,
-        msrv,
+    output.recommendations.extend(extra_recommendations);
+
+    Ok(output)
+}
+
+/// Collapses per-member MSRV results into the worst status across the workspace.
+///
+/// A failed toolchain build counts as invalid even when the metadata check passed.
+fn msrv_status(output: &MsrvOutput) -> MsrvStatus {
+    let invalid = output.members.iter().any(|member| {
+        member.state == MsrvState::Invalid
+            || member
+                .toolchain
+                .as_ref()
+                .is_some_and(|check| check.status == ToolchainCheckStatus::Failed)
     });
+    if invalid {
+        MsrvStatus::Invalid
+    } else if output.members.is_empty()
+        || output
+            .members
+            .iter()
+            .any(|member| member.state == MsrvState::Missing)
+    {
+        MsrvStatus::Missing
+    } else {
+        MsrvStatus::Valid
+    }
+}
 
-    output.recommendations.extend(extra_recommendations);
+/// Counts the breaking changes in crates whose version bump does not cover them.
+fn semver_summary(output: &SemverOutput) -> SemverSummary {
+    SemverSummary {
+        unreleased_breaking: output
+            .crates
+            .iter()
+            .filter(|krate| !krate.sufficient)
+            .map(|krate| krate.violations.len())
+            .sum(),
+    }
+}
 
+fn semver_recommendations(output: &SemverOutput) -> Vec<String> {
     output
+        .crates
+        .iter()
+        .filter(|krate| !krate.sufficient)
+        .map(|krate| {
+            let lints: Vec<&str> = krate
+                .violations
+                .iter()
+                .map(|violation| violation.lint.as_str())
+                .collect();
+            format!(
+                "{} {} needs a {} release but bumps {} from {} ({})",
+                krate.name,
+                krate.current,
+                krate.required,
+                krate.bump,
+                krate.baseline.as_deref().unwrap_or("the baseline"),
+                lints.join(", ")
+            )
+        })
+        .collect()
 }
 
-async fn check_msrv() -> Result<MsrvStatus> {
-    let metadata = run_blocking("cargo metadata", || {
-        MetadataCommand::new().exec().map_err(|err| {
-            UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
-        })
-    })
-    .await?;
+const MAX_LISTED_MSRV_DEPENDENCIES: usize = 5;
+const MAX_LISTED_LICENSE_VIOLATIONS: usize = 5;
 
-    // For virtual workspaces (no root package), return Missing for graceful degradation
-    let Some(root) = metadata.root_package() else {
-        return Ok(MsrvStatus::Missing);
-    };
+fn license_recommendation(output: &LicensesOutput) -> Option<String> {
+    if output.violations.is_empty() {
+        return None;
+    }
+    let mut names: Vec<String> = output
+        .violations
+        .iter()
+        .take(MAX_LISTED_LICENSE_VIOLATIONS)
+        .map(|violation| format!("{} ({})", violation.name, violation.status))
+        .collect();
+    let remaining = output
+        .violations
+        .len()
+        .saturating_sub(MAX_LISTED_LICENSE_VIOLATIONS);
+    if remaining > 0 {
+        names.push(format!("and {remaining} more"));
+    }
+    Some(format!(
+        "{} dependencies fail the license check: {}",
+        output.violations.len(),
+        names.join(", ")
+    ))
+}
 
-    match root.rust_version.as_ref() {
-        Some(_) => Ok(MsrvStatus::Valid),
-        None => Ok(MsrvStatus::Missing),
+fn msrv_recommendations(output: &MsrvOutput) -> Vec<String> {
+    let mut recommendations = Vec::new();
+    for member in &output.members {
+        let declared = member.declared.as_deref().unwrap_or("unset");
+        if let Some(highest) = member.incompatible.first() {
+            let mut names: Vec<&str> = member
+                .incompatible
+                .iter()
+                .take(MAX_LISTED_MSRV_DEPENDENCIES)
+                .map(|dep| dep.name.as_str())
+                .collect();
+            let remaining = member
+                .incompatible
+                .len()
+                .saturating_sub(MAX_LISTED_MSRV_DEPENDENCIES);
+            let more = format!("and {remaining} more");
+            if remaining > 0 {
+                names.push(&more);
+            }
+            recommendations.push(format!(
+                "{} declares rust-version {} but dependencies require up to {} ({})",
+                member.name,
+                declared,
+                highest.rust_version,
+                names.join(", ")
+            ));
+        }
+        if let Some(check) = &member.toolchain {
+            let message = check.message.as_deref().unwrap_or("cargo check failed");
+            match check.status {
+                ToolchainCheckStatus::Failed => recommendations.push(format!(
+                    "{} fails to build with toolchain {}: {}",
+                    member.name, check.toolchain, message
+                )),
+                ToolchainCheckStatus::Unavailable => recommendations.push(format!(
+                    "MSRV build check skipped for {}: {}",
+                    member.name, message
+                )),
+                ToolchainCheckStatus::Passed => {}
+            }
+        }
     }
+    recommendations
 }
 
 async fn run_blocking<T, F>(label: &str, func: F) -> Result<T>
@@ -177,15 +452,40 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{build_quality_output, check_msrv, run_blocking, MsrvStatus};
+    use super::{
+        build_quality_output, is_selected, msrv_recommendations, msrv_status, run_blocking,
+        MsrvStatus, QualityResults, UnavailablePolicy,
+    };
+    use crate::cli::{QualityArgs, QualityMetric};
+    use crate::core::analyzers::msrv::{run_msrv, MsrvOptions};
     use crate::core::error::{ErrorCode, UpkeepError};
-    use crate::core::output::{Grade, MetricScore, QualityOutput};
+    use crate::core::output::{
+        Grade, MetricScore, MetricStatus, MsrvDependency, MsrvMember, MsrvOutput, MsrvState,
+        QualityOutput, ReleaseBump, SemverCrate, SemverOutput, SemverViolation, ToolchainCheck,
+        ToolchainCheckStatus,
+    };
     use serde_json::Value;
 
     fn err() -> UpkeepError {
         UpkeepError::message(ErrorCode::TaskFailed, "boom")
     }
 
+    fn msrv_member(state: MsrvState) -> MsrvMember {
+        MsrvMember {
+            name: "app".to_string(),
+            declared: Some("1.70.0".to_string()),
+            state,
+            incompatible: Vec::new(),
+            toolchain: None,
+        }
+    }
+
+    fn valid_msrv() -> MsrvOutput {
+        MsrvOutput {
+            members: vec![msrv_member(MsrvState::Valid)],
+        }
+    }
+
     #[tokio::test]
     async fn run_blocking_returns_ok_value() {
         let value = run_blocking("ok", || Ok(42)).await.unwrap();
@@ -204,24 +504,99 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn check_msrv_returns_valid_when_set() {
-        let status = check_msrv().await.unwrap();
+    async fn run_msrv_checks_declared_rust_version() {
+        let output = run_msrv(MsrvOptions::default()).await.unwrap();
+        let member = output
+            .members
+            .iter()
+            .find(|member| member.name == env!("CARGO_PKG_NAME"))
+            .expect("workspace member");
+        assert!(member.declared.is_some());
+        assert_ne!(member.state, MsrvState::Missing);
+        assert!(member.toolchain.is_none());
+    }
+
+    #[test]
+    fn msrv_status_reports_worst_member() {
+        assert!(matches!(msrv_status(&valid_msrv()), MsrvStatus::Valid));
         assert!(matches!(
-            status,
-            crate::core::scorers::quality::MsrvStatus::Valid
+            msrv_status(&MsrvOutput {
+                members: Vec::new()
+            }),
+            MsrvStatus::Missing
+        ));
+        let mixed = MsrvOutput {
+            members: vec![
+                msrv_member(MsrvState::Missing),
+                msrv_member(MsrvState::Invalid),
+            ],
+        };
+        assert!(matches!(msrv_status(&mixed), MsrvStatus::Invalid));
+
+        let mut failed_build = msrv_member(MsrvState::Valid);
+        failed_build.toolchain = Some(ToolchainCheck {
+            toolchain: "1.70.0".to_string(),
+            status: ToolchainCheckStatus::Failed,
+            message: Some("error[E0658]: unstable".to_string()),
+        });
+        let output = MsrvOutput {
+            members: vec![failed_build],
+        };
+        assert!(matches!(msrv_status(&output), MsrvStatus::Invalid));
+        assert_eq!(
+            msrv_recommendations(&output),
+            vec!["app fails to build with toolchain 1.70.0: error[E0658]: unstable".to_string()]
+        );
+    }
+
+    #[test]
+    fn build_quality_output_names_msrv_raising_dependencies() {
+        let mut member = msrv_member(MsrvState::Invalid);
+        member.incompatible = vec![
+            MsrvDependency {
+                name: "tokio".to_string(),
+                version: "1.40.0".to_string(),
+                rust_version: "1.80.0".to_string(),
+            },
+            MsrvDependency {
+                name: "serde".to_string(),
+                version: "1.0.200".to_string(),
+                rust_version: "1.74.0".to_string(),
+            },
+        ];
+        let output = build_quality_output(
+            QualityResults {
+                msrv: Some(Ok(MsrvOutput {
+                    members: vec![member],
+                })),
+                ..Default::default()
+            },
+            UnavailablePolicy::Exclude,
+        )
+        .unwrap();
+
+        assert!(output.score.abs() < f32::EPSILON);
+        assert!(output.recommendations.contains(
+            &"app declares rust-version 1.70.0 but dependencies require up to 1.80.0 (tokio, serde)"
+                .to_string()
         ));
     }
 
     #[test]
     fn build_quality_output_adds_recommendations_for_failures() {
         let output = build_quality_output(
-            Err(err()),
-            Err(err()),
-            Err(err()),
-            Ok(MsrvStatus::Valid),
-            Err(err()),
-            Err(err()),
-        );
+            QualityResults {
+                deps: Some(Err(err())),
+                audit: Some(Err(err())),
+                clippy: Some(Err(err())),
+                msrv: Some(Ok(valid_msrv())),
+                unused: Some(Err(err())),
+                unsafe_code: Some(Err(err())),
+                ..Default::default()
+            },
+            UnavailablePolicy::Exclude,
+        )
+        .unwrap();
 
         assert_eq!(
             output.recommendations,
@@ -233,6 +608,160 @@ mod tests {
                 "Unsafe code scan unavailable: boom".to_string(),
             ]
         );
+        let unavailable: Vec<&str> = output
+            .breakdown
+            .iter()
+            .filter(|metric| metric.status == MetricStatus::Unavailable)
+            .map(|metric| metric.name.as_str())
+            .collect();
+        assert_eq!(
+            unavailable,
+            vec![
+                "Dependency freshness",
+                "Security",
+                "Unused dependencies",
+                "Unsafe code",
+                "Clippy"
+            ]
+        );
+        assert!(output
+            .breakdown
+            .iter()
+            .filter(|metric| metric.status == MetricStatus::Unavailable)
+            .all(|metric| metric.reason.as_deref() == Some("boom")));
+        // Only MSRV was measured, so it carries the full weight.
+        assert!((output.score - 100.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn build_quality_output_fails_on_unavailable_when_requested() {
+        let err = build_quality_output(
+            QualityResults {
+                deps: Some(Ok(crate::core::output::DepsOutput {
+                    total: 0,
+                    outdated: 0,
+                    major: 0,
+                    minor: 0,
+                    patch: 0,
+                    packages: Vec::new(),
+                    skipped: 0,
+                    skipped_packages: Vec::new(),
+                    git_dependencies: Vec::new(),
+                    warnings: Vec::new(),
+                    security: None,
+                    workspace: false,
+                    members: Vec::new(),
+                    skipped_members: Vec::new(),
+                })),
+                clippy: Some(Err(err())),
+                ..Default::default()
+            },
+            UnavailablePolicy::Fail,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::TaskFailed);
+        assert!(err.to_string().contains("Clippy (boom)"));
+    }
+
+    #[test]
+    fn build_quality_output_marks_skipped_metrics() {
+        let output = build_quality_output(
+            QualityResults {
+                msrv: Some(Ok(valid_msrv())),
+                ..Default::default()
+            },
+            UnavailablePolicy::Fail,
+        )
+        .unwrap();
+
+        assert!(output.recommendations.is_empty());
+        assert!((output.score - 100.0).abs() < f32::EPSILON);
+        let msrv = output
+            .breakdown
+            .iter()
+            .find(|metric| metric.name == "MSRV")
+            .expect("msrv metric");
+        assert_eq!(msrv.status, MetricStatus::Measured);
+        assert!((msrv.weight - 1.0).abs() < f32::EPSILON);
+        let skipped = output
+            .breakdown
+            .iter()
+            .filter(|metric| metric.status == MetricStatus::Skipped)
+            .count();
+        assert_eq!(skipped, 7);
+    }
+
+    #[test]
+    fn is_selected_applies_only_and_skip() {
+        let all = QualityArgs::default();
+        assert!(is_selected(&all, QualityMetric::Clippy));
+
+        let only = QualityArgs {
+            only: vec![QualityMetric::Deps, QualityMetric::Clippy],
+            skip: vec![QualityMetric::Clippy],
+            unavailable: UnavailablePolicy::Exclude,
+            verify_msrv: false,
+            semver: false,
+            licenses: false,
+        };
+        assert!(is_selected(&only, QualityMetric::Deps));
+        assert!(!is_selected(&only, QualityMetric::Clippy));
+        assert!(!is_selected(&only, QualityMetric::Security));
+    }
+
+    #[test]
+    fn is_selected_requires_opting_into_semver() {
+        assert!(!is_selected(&QualityArgs::default(), QualityMetric::Semver));
+
+        let flag = QualityArgs {
+            semver: true,
+            ..Default::default()
+        };
+        assert!(is_selected(&flag, QualityMetric::Semver));
+        assert!(is_selected(&flag, QualityMetric::Deps));
+
+        let only = QualityArgs {
+            only: vec![QualityMetric::Semver],
+            ..Default::default()
+        };
+        assert!(is_selected(&only, QualityMetric::Semver));
+        assert!(!is_selected(&only, QualityMetric::Deps));
+    }
+
+    #[test]
+    fn build_quality_output_names_unreleased_breaking_changes() {
+        let krate = |name: &str, sufficient: bool| SemverCrate {
+            name: name.to_string(),
+            baseline: Some("1.2.0".to_string()),
+            current: "1.2.1".to_string(),
+            bump: ReleaseBump::Patch,
+            required: ReleaseBump::Major,
+            sufficient,
+            violations: vec![SemverViolation {
+                lint: "function_missing".to_string(),
+                summary: "pub fn removed or renamed".to_string(),
+                locations: Vec::new(),
+            }],
+        };
+        let output = build_quality_output(
+            QualityResults {
+                semver: Some(Ok(SemverOutput {
+                    crates: vec![krate("mylib", false), krate("released", true)],
+                    warnings: Vec::new(),
+                })),
+                ..Default::default()
+            },
+            UnavailablePolicy::Exclude,
+        )
+        .unwrap();
+
+        // One uncovered breaking change costs 25 points.
+        assert!((output.score - 75.0).abs() < f32::EPSILON);
+        assert!(output.recommendations.contains(
+            &"mylib 1.2.1 needs a major release but bumps patch from 1.2.0 (function_missing)"
+                .to_string()
+        ));
     }
 
     #[test]
@@ -242,8 +771,10 @@ mod tests {
             grade: Grade::A,
             breakdown: vec![MetricScore {
                 name: "Security".to_string(),
-                score: 90.0,
+                score: Some(90.0),
                 weight: 0.25,
+                status: MetricStatus::Measured,
+                reason: None,
             }],
             recommendations: vec!["Address advisories".to_string()],
         };
@@ -0,0 +1,45 @@
+use crate::cli::{SbomArgs, SbomFormat};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::platform::TargetFilter;
+use crate::core::sbom::{cyclonedx, generate, spdx, SbomOptions};
+
+/// The document is already machine-readable, so `--json` changes nothing.
+pub async fn run(_json: bool, args: SbomArgs) -> Result<()> {
+    // SPDX 2.3 has no place for vulnerability data.
+    if args.vulnerabilities && args.format != SbomFormat::CyclonedxJson {
+        return Err(UpkeepError::message(
+            ErrorCode::Config,
+            "--vulnerabilities is only supported with --format cyclonedx-json",
+        ));
+    }
+    let options = SbomOptions {
+        include_dev: !args.no_dev,
+        targets: TargetFilter::from_args(&args.target.targets)?,
+        features: args.feature_selection.selection(),
+        vulnerabilities: args.vulnerabilities,
+    };
+    let render = match args.format {
+        SbomFormat::CyclonedxJson => cyclonedx::render,
+        SbomFormat::Spdx => spdx::render_json,
+        SbomFormat::SpdxTagValue => spdx::render_tag_value,
+    };
+    let document = tokio::task::spawn_blocking(move || generate(&options, render))
+        .await
+        .map_err(|err| {
+            UpkeepError::message(ErrorCode::TaskFailed, format!("sbom task failed: {err}"))
+        })??;
+
+    match args.output {
+        Some(path) => std::fs::write(&path, format!("{document}\n")).map_err(|err| {
+            UpkeepError::context(
+                ErrorCode::Io,
+                format!("failed to write {}", path.display()),
+                err,
+            )
+        }),
+        None => {
+            println!("{document}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use super::run_with::run_with_output;
+use crate::cli::SemverArgs;
+use crate::core::analyzers::semver_checks::{run_semver_checks, SemverOptions};
+use crate::core::error::Result;
+use crate::core::output::print_json;
+
+pub async fn run(json: bool, args: SemverArgs) -> Result<()> {
+    let options = SemverOptions {
+        package: args.package,
+        baseline_rev: args.baseline_rev,
+    };
+    run_with_output(json, run_semver_checks(&options), print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
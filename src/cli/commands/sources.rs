@@ -0,0 +1,31 @@
+use super::run_with::run_with_output;
+use crate::cli::SourcesArgs;
+use crate::core::analyzers::sources::run_sources;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::print_json;
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: SourcesArgs) -> Result<()> {
+    run_with_output(
+        json,
+        async {
+            let targets = TargetFilter::from_args(&args.target.targets)?;
+            let features = args.feature_selection.selection();
+            let include_dev = !args.no_dev;
+            tokio::task::spawn_blocking(move || run_sources(include_dev, &targets, &features))
+                .await
+                .map_err(|err| {
+                    UpkeepError::message(
+                        ErrorCode::TaskFailed,
+                        format!("sources task failed: {err}"),
+                    )
+                })?
+        },
+        print_json,
+        |output| {
+            print!("{output}");
+            Ok(())
+        },
+    )
+    .await
+}
@@ -1,9 +1,17 @@
-use cargo_metadata::{CargoOpt, DependencyKind, MetadataCommand, Package, PackageId};
-use std::collections::{HashMap, HashSet};
+use cargo_metadata::{CargoOpt, MetadataCommand, Package, PackageId, TargetKind};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use crate::cli::TreeArgs;
+use super::deps::convert_dependency_kind;
+use crate::cli::{SourceKind, TreeArgs, TreeFormat};
 use crate::core::error::{ErrorCode, Result, UpkeepError};
-use crate::core::output::{print_json, TreeNode, TreeOutput, TreeStats};
+use crate::core::graph::{Edge, ResolveGraph};
+use crate::core::output::{
+    print_json, DependencyType, GraphEdge, GraphEdgeKind, GraphNode, GraphNodeKind, GraphOutput,
+    TreeNode, TreeOutput, TreeStats,
+};
+use crate::core::pattern::NamePattern;
+use crate::core::platform::TargetFilter;
+use crate::core::source::GitSource;
 
 /// Maximum recursion depth for tree traversal.
 /// This prevents stack overflow on pathologically deep dependency graphs.
@@ -11,13 +19,6 @@ use crate::core::output::{print_json, TreeNode, TreeOutput, TreeStats};
 /// and deep recursion can exhaust stack space on some platforms.
 const MAX_TREE_DEPTH: usize = 200;
 
-#[derive(Clone)]
-struct Edge {
-    id: PackageId,
-    is_dev: bool,
-    is_build: bool,
-}
-
 /// Context for tree building operations, grouping related parameters
 /// to avoid passing many arguments through recursive calls.
 struct TreeBuildContext<'a> {
@@ -26,11 +27,49 @@ struct TreeBuildContext<'a> {
     features_by_id: &'a HashMap<PackageId, Vec<String>>,
     args: &'a TreeArgs,
     duplicate_names: &'a HashSet<String>,
+    filter: &'a TreeFilter<'a>,
+    /// Packages whose children are listed under `--only-direct`.
+    direct_roots: HashSet<PackageId>,
     expanded: HashSet<PackageId>,
     path: HashSet<PackageId>,
 }
 
+/// Crates hidden by `--prune` and `--exclude-source`, together with their
+/// subtrees. Workspace members are never hidden.
+struct TreeFilter<'a> {
+    prune: Vec<NamePattern>,
+    exclude_source: &'a [SourceKind],
+    members: HashSet<&'a PackageId>,
+}
+
+impl TreeFilter<'_> {
+    fn hides(&self, package: &Package) -> bool {
+        if self.members.contains(&package.id) {
+            return false;
+        }
+        self.prune
+            .iter()
+            .any(|pattern| pattern.matches(&package.name))
+            || self.exclude_source.contains(&source_kind(package))
+    }
+}
+
+fn source_kind(package: &Package) -> SourceKind {
+    match &package.source {
+        None => SourceKind::Path,
+        Some(source) if GitSource::parse(&source.repr).is_some() => SourceKind::Git,
+        Some(_) => SourceKind::Registry,
+    }
+}
+
 pub async fn run(json: bool, args: TreeArgs) -> Result<()> {
+    if json && matches!(args.format, TreeFormat::Dot | TreeFormat::Mermaid) {
+        return Err(UpkeepError::message(
+            ErrorCode::Config,
+            "--format dot and --format mermaid cannot be combined with --json",
+        ));
+    }
+
     let cwd = std::env::current_dir()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|err| {
@@ -38,28 +77,43 @@ pub async fn run(json: bool, args: TreeArgs) -> Result<()> {
             "<unknown>".to_string()
         });
 
-    let metadata = MetadataCommand::new()
-        .features(CargoOpt::AllFeatures)
-        .exec()
-        .map_err(|err| {
-            UpkeepError::context(
-                ErrorCode::Metadata,
-                format!("failed to load cargo metadata in directory: {cwd}"),
-                err,
-            )
-        })?;
-    let resolve = metadata.resolve.as_ref().ok_or_else(|| {
-        UpkeepError::message(ErrorCode::InvalidData, "metadata missing resolve data")
+    // Without feature flags the tree shows everything any build could pull in.
+    let features = args.feature_selection.selection();
+    let mut command = MetadataCommand::new();
+    if features.is_default() {
+        command.features(CargoOpt::AllFeatures);
+    } else {
+        features.apply(&mut command);
+    }
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Metadata,
+            format!("failed to load cargo metadata in directory: {cwd}"),
+            err,
+        )
     })?;
+    let selected_package = features.selected_package(&metadata)?;
+    let roots: Vec<&PackageId> = match selected_package {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let targets = TargetFilter::from_args(&args.target.targets)?;
+    let resolve_graph = ResolveGraph::from_metadata(&metadata, !args.no_dev, &targets)?;
+    let filter = TreeFilter {
+        prune: NamePattern::parse_all(&args.prune)?,
+        exclude_source: &args.exclude_source,
+        members: metadata.workspace_members.iter().collect(),
+    };
 
-    let mut packages_by_id: HashMap<PackageId, &Package> = HashMap::new();
+    // Only count versions that are part of the selected graph, so crates pulled
+    // in for other platforms, other members, or hidden subtrees do not show up
+    // as duplicates.
     let mut versions_by_name: HashMap<String, HashSet<String>> = HashMap::new();
-    for package in &metadata.packages {
+    for package in visible_packages(&resolve_graph, &roots, &filter) {
         versions_by_name
             .entry(package.name.to_string())
             .or_default()
             .insert(package.version.to_string());
-        packages_by_id.insert(package.id.clone(), package);
     }
 
     let duplicate_names: HashSet<String> = versions_by_name
@@ -67,50 +121,23 @@ pub async fn run(json: bool, args: TreeArgs) -> Result<()> {
         .filter_map(|(name, versions)| if versions.len() > 1 { Some(name) } else { None })
         .collect();
 
-    let mut features_by_id = HashMap::new();
-    let mut forward_graph: HashMap<PackageId, Vec<Edge>> = HashMap::new();
-
-    for node in &resolve.nodes {
-        let features: Vec<String> = node.features.iter().map(ToString::to_string).collect();
-        features_by_id.insert(node.id.clone(), features);
-        for dep in &node.deps {
-            if args.no_dev && !includes_non_dev(dep) {
-                continue;
-            }
-
-            let is_dev = dep
-                .dep_kinds
-                .iter()
-                .any(|kind| kind.kind == DependencyKind::Development);
-            let is_build = dep
-                .dep_kinds
-                .iter()
-                .any(|kind| kind.kind == DependencyKind::Build);
-
-            forward_graph
-                .entry(node.id.clone())
-                .or_default()
-                .push(Edge {
-                    id: dep.pkg.clone(),
-                    is_dev,
-                    is_build,
-                });
-        }
-    }
-
     let invert_target = args.invert.as_deref();
+    let inverted;
     let graph = if invert_target.is_some() {
-        invert_graph(&forward_graph)
+        inverted = resolve_graph.inverted();
+        &inverted
     } else {
-        forward_graph
+        &resolve_graph.edges
     };
 
     let mut ctx = TreeBuildContext {
-        graph: &graph,
-        packages_by_id: &packages_by_id,
-        features_by_id: &features_by_id,
+        graph,
+        packages_by_id: &resolve_graph.packages_by_id,
+        features_by_id: &resolve_graph.features_by_id,
         args: &args,
         duplicate_names: &duplicate_names,
+        filter: &filter,
+        direct_roots: metadata.workspace_members.iter().cloned().collect(),
         expanded: HashSet::new(),
         path: HashSet::new(),
     };
@@ -119,7 +146,7 @@ pub async fn run(json: bool, args: TreeArgs) -> Result<()> {
         Some(name) => build_inverted_root(name, &mut ctx)?,
         None => {
             // Handle virtual workspaces by creating a synthetic root node
-            match metadata.root_package() {
+            match selected_package.or(metadata.root_package()) {
                 Some(root_package) => build_node(&root_package.id, 0, false, false, &mut ctx)?
                     .unwrap_or_else(|| TreeNode::empty("root")),
                 None => {
@@ -142,17 +169,221 @@ pub async fn run(json: bool, args: TreeArgs) -> Result<()> {
         }
     };
 
-    let stats = build_stats(&root);
-    let output = TreeOutput { root, stats };
+    match args.format {
+        TreeFormat::Text => {
+            let stats = build_stats(&root);
+            let output = TreeOutput { root, stats };
+            emit_output(json, &args, &output)
+        }
+        TreeFormat::Dot | TreeFormat::Mermaid | TreeFormat::GraphJson => {
+            let members: HashSet<&PackageId> = metadata.workspace_members.iter().collect();
+            let graph = build_graph(&root, invert_target.is_some(), &resolve_graph, &members);
+            match args.format {
+                TreeFormat::Dot => println!("{}", graph.to_dot()),
+                TreeFormat::Mermaid => println!("{}", graph.to_mermaid()),
+                _ => print_json(&graph)?,
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Flattens the rendered tree into nodes and edges for graph exports.
+///
+/// Edges always point from dependent to dependency, including for inverted
+/// trees, and carry the kinds recorded in the resolve graph.
+fn build_graph(
+    root: &TreeNode,
+    inverted: bool,
+    resolve_graph: &ResolveGraph<'_>,
+    members: &HashSet<&PackageId>,
+) -> GraphOutput {
+    let mut tree_nodes: Vec<&TreeNode> = Vec::new();
+    let mut seen_nodes = HashSet::new();
+    let mut pairs: Vec<(&str, &str)> = Vec::new();
+    let mut seen_pairs = HashSet::new();
+    collect_graph(
+        root,
+        inverted,
+        &mut tree_nodes,
+        &mut seen_nodes,
+        &mut pairs,
+        &mut seen_pairs,
+    );
+
+    let mut edges = Vec::new();
+    for (from, to) in pairs {
+        let from_id = PackageId {
+            repr: from.to_string(),
+        };
+        let to_id = PackageId {
+            repr: to.to_string(),
+        };
+        let Some(edge) = resolve_graph
+            .edges
+            .get(&from_id)
+            .and_then(|edges| edges.iter().find(|edge| edge.id == to_id))
+        else {
+            continue;
+        };
+
+        let dependent = resolve_graph.package(&from_id);
+        let dependency = resolve_graph.package(&to_id);
+        let kinds = edge.kinds();
+        edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            features: dependent
+                .zip(dependency)
+                .map(|(dependent, dependency)| edge_features(dependent, dependency, &kinds))
+                .unwrap_or_default(),
+            kinds,
+            renamed_as: dependent
+                .zip(dependency)
+                .and_then(|(dependent, dependency)| renamed_as(dependent, dependency)),
+        });
+    }
 
-    emit_output(json, &args, &output)
+    let packages = tree_nodes
+        .into_iter()
+        .map(|node| {
+            let id = PackageId {
+                repr: node.package_id.clone(),
+            };
+            let package = resolve_graph.package(&id);
+            let incoming: Vec<&GraphEdge> = edges
+                .iter()
+                .filter(|edge| edge.to == node.package_id)
+                .collect();
+            GraphNode {
+                id: node.package_id.clone(),
+                name: node.name.clone(),
+                version: node.version.clone(),
+                source: package
+                    .and_then(|package| package.source.as_ref())
+                    .map(ToString::to_string),
+                license: package.and_then(|package| package.license.clone()),
+                features: resolve_graph
+                    .features_by_id
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_default(),
+                kind: node_kind(package, members.contains(&id), &incoming),
+                duplicate: node.duplicate,
+            }
+        })
+        .collect();
+
+    GraphOutput { packages, edges }
+}
+
+/// Returns the local name `dependent` gives `dependency` when it renames it
+/// with `package = "..."` in its manifest.
+fn renamed_as(dependent: &Package, dependency: &Package) -> Option<String> {
+    dependent
+        .dependencies
+        .iter()
+        .find(|dep| {
+            dep.name == dependency.name.as_str()
+                && dep.rename.is_some()
+                && dep.req.matches(&dependency.version)
+        })
+        .and_then(|dep| dep.rename.clone())
+}
+
+/// Collects the features `dependent` turns on for `dependency` in the
+/// declarations behind `kinds`, including `default` unless it is disabled.
+fn edge_features(
+    dependent: &Package,
+    dependency: &Package,
+    kinds: &[GraphEdgeKind],
+) -> Vec<String> {
+    let mut features = BTreeSet::new();
+    for dep in &dependent.dependencies {
+        let declared_kind = GraphEdgeKind {
+            kind: convert_dependency_kind(dep.kind),
+            target: dep.target.as_ref().map(ToString::to_string),
+        };
+        if dep.name != dependency.name.as_str()
+            || !dep.req.matches(&dependency.version)
+            || !kinds.contains(&declared_kind)
+        {
+            continue;
+        }
+        if dep.uses_default_features {
+            features.insert("default".to_string());
+        }
+        features.extend(dep.features.iter().cloned());
+    }
+    features.into_iter().collect()
+}
+
+fn collect_graph<'a>(
+    node: &'a TreeNode,
+    inverted: bool,
+    nodes: &mut Vec<&'a TreeNode>,
+    seen_nodes: &mut HashSet<&'a str>,
+    pairs: &mut Vec<(&'a str, &'a str)>,
+    seen_pairs: &mut HashSet<(&'a str, &'a str)>,
+) {
+    // Synthetic roots (virtual workspaces, multi-match inversions) have no package id.
+    let is_real = !node.package_id.is_empty();
+    if is_real && seen_nodes.insert(node.package_id.as_str()) {
+        nodes.push(node);
+    }
+
+    for child in &node.dependencies {
+        if is_real {
+            let pair = if inverted {
+                (child.package_id.as_str(), node.package_id.as_str())
+            } else {
+                (node.package_id.as_str(), child.package_id.as_str())
+            };
+            if seen_pairs.insert(pair) {
+                pairs.push(pair);
+            }
+        }
+        collect_graph(child, inverted, nodes, seen_nodes, pairs, seen_pairs);
+    }
+}
+
+/// Classifies a node for coloring. Build and dev only apply when every
+/// incoming edge is of that kind; a crate also used normally is normal.
+fn node_kind(package: Option<&Package>, is_member: bool, incoming: &[&GraphEdge]) -> GraphNodeKind {
+    if is_member {
+        return GraphNodeKind::WorkspaceMember;
+    }
+    let is_proc_macro = package.is_some_and(|package| {
+        package
+            .targets
+            .iter()
+            .any(|target| target.kind.contains(&TargetKind::ProcMacro))
+    });
+    if is_proc_macro {
+        return GraphNodeKind::ProcMacro;
+    }
+    if incoming.is_empty() {
+        return GraphNodeKind::Normal;
+    }
+    let only = |kind: DependencyType| {
+        incoming
+            .iter()
+            .all(|edge| !edge.has_kind(DependencyType::Normal) && edge.has_kind(kind))
+    };
+    if only(DependencyType::Build) {
+        GraphNodeKind::Build
+    } else if only(DependencyType::Dev) {
+        GraphNodeKind::Dev
+    } else {
+        GraphNodeKind::Normal
+    }
 }
 
 fn emit_output(json: bool, args: &TreeArgs, output: &TreeOutput) -> Result<()> {
     if json {
         print_json(output)
     } else {
-        let rendered = render_tree(&output.root, args.features);
+        let rendered = render_tree(&output.root, args.show_features);
         println!("{rendered}");
         println!(
             "\nCrates: {}  Direct deps: {}  Transitive deps: {}  Duplicate crates: {}",
@@ -165,39 +396,51 @@ fn emit_output(json: bool, args: &TreeArgs, output: &TreeOutput) -> Result<()> {
     }
 }
 
-fn includes_non_dev(dep: &cargo_metadata::NodeDep) -> bool {
-    dep.dep_kinds.iter().any(|kind| match kind.kind {
-        DependencyKind::Development => false,
-        DependencyKind::Normal | DependencyKind::Build | DependencyKind::Unknown => true,
-    })
-}
-
-fn invert_graph(graph: &HashMap<PackageId, Vec<Edge>>) -> HashMap<PackageId, Vec<Edge>> {
-    let mut inverted: HashMap<PackageId, Vec<Edge>> = HashMap::new();
-    for (from, children) in graph {
-        for child in children {
-            inverted.entry(child.id.clone()).or_default().push(Edge {
-                id: from.clone(),
-                is_dev: child.is_dev,
-                is_build: child.is_build,
-            });
+/// Collects the packages reachable from `roots` without entering hidden
+/// subtrees.
+fn visible_packages<'a>(
+    graph: &ResolveGraph<'a>,
+    roots: &[&PackageId],
+    filter: &TreeFilter<'_>,
+) -> Vec<&'a Package> {
+    let mut visited: HashSet<&PackageId> = roots.iter().copied().collect();
+    let mut queue: Vec<&PackageId> = roots.to_vec();
+    let mut packages: Vec<&'a Package> = roots.iter().filter_map(|id| graph.package(id)).collect();
+
+    while let Some(id) = queue.pop() {
+        for edge in graph.edges.get(id).into_iter().flatten() {
+            let Some(package) = graph.package(&edge.id) else {
+                continue;
+            };
+            if !filter.hides(package) && visited.insert(&edge.id) {
+                packages.push(package);
+                queue.push(&edge.id);
+            }
         }
     }
-    inverted
+    packages
 }
 
 fn build_inverted_root(name: &str, ctx: &mut TreeBuildContext<'_>) -> Result<TreeNode> {
+    let pattern = NamePattern::parse(name)?;
     let mut matches = Vec::new();
     for (id, package) in ctx.packages_by_id {
-        if package.name == name {
+        if pattern.matches(&package.name) {
             matches.push(id.clone());
         }
     }
+    // Keep the output stable when a pattern matches several crates.
+    matches.sort_by(|left, right| {
+        let left = &ctx.packages_by_id[left];
+        let right = &ctx.packages_by_id[right];
+        (&left.name, &left.version).cmp(&(&right.name, &right.version))
+    });
+    ctx.direct_roots = matches.iter().cloned().collect();
 
     if matches.is_empty() {
         return Err(UpkeepError::message(
             ErrorCode::InvalidData,
-            format!("no package named '{name}' found in metadata"),
+            format!("no package matching '{name}' found in metadata"),
         ));
     }
 
@@ -255,7 +498,7 @@ fn build_node(
         name: package.name.to_string(),
         version: package.version.to_string(),
         package_id: package.id.to_string(),
-        features: if ctx.args.features {
+        features: if ctx.args.show_features {
             ctx.features_by_id.get(id).cloned().unwrap_or_default()
         } else {
             Vec::new()
@@ -274,7 +517,9 @@ fn build_node(
         });
     }
 
-    if !ctx.expanded.insert(id.clone()) {
+    // Under `--only-direct`, only members (or the inverted crates) list children.
+    let leaf = ctx.args.only_direct && !ctx.direct_roots.contains(id);
+    if leaf || !ctx.expanded.insert(id.clone()) {
         return Ok(if ctx.args.duplicates && !duplicate && depth > 0 {
             None
         } else {
@@ -306,6 +551,13 @@ fn build_node(
             if ctx.path.contains(&child.id) {
                 continue;
             }
+            let hidden = ctx
+                .packages_by_id
+                .get(&child.id)
+                .is_some_and(|package| ctx.filter.hides(package));
+            if hidden {
+                continue;
+            }
             if let Some(child_node) =
                 build_node(&child.id, depth + 1, child.is_dev, child.is_build, ctx)?
             {
@@ -1,11 +1,17 @@
 use super::run_with::run_with_output;
+use crate::cli::TargetArgs;
 use crate::core::error::Result;
 
 use crate::core::analyzers::unused::run_unused;
 use crate::core::output::print_json;
+use crate::core::platform::TargetFilter;
 
-pub async fn run(json: bool) -> Result<()> {
-    run_with_output(json, run_unused(), print_json, |output| {
+pub async fn run(json: bool, args: TargetArgs) -> Result<()> {
+    let analysis = async {
+        let targets = TargetFilter::from_args(&args.targets)?;
+        run_unused(&targets).await
+    };
+    run_with_output(json, analysis, print_json, |output| {
         println!("{output}");
         Ok(())
     })
@@ -0,0 +1,44 @@
+use cargo_metadata::{MetadataCommand, PackageId};
+
+use super::run_with::run_with_output;
+use crate::cli::WeightArgs;
+use crate::core::analyzers::weight::{dependency_weights, SourceSizes};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{print_json, WeightMember, WeightOutput};
+use crate::core::platform::TargetFilter;
+
+pub async fn run(json: bool, args: WeightArgs) -> Result<()> {
+    run_with_output(json, async { analyze(&args) }, print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+fn analyze(args: &WeightArgs) -> Result<WeightOutput> {
+    let features = args.feature_selection.selection();
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let targets = TargetFilter::from_args(&args.target.targets)?;
+    let graph = ResolveGraph::from_metadata(&metadata, !args.no_dev, &targets)?;
+
+    let roots: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let mut sizes = SourceSizes::default();
+    let members = roots
+        .iter()
+        .filter_map(|id| graph.package(id))
+        .map(|package| WeightMember {
+            name: package.name.to_string(),
+            dependencies: dependency_weights(&graph, &roots, &package.id, &mut sizes),
+        })
+        .collect();
+
+    Ok(WeightOutput { members })
+}
@@ -0,0 +1,388 @@
+use cargo_metadata::{Metadata, MetadataCommand, Package, PackageId};
+use semver::VersionReq;
+use std::collections::{HashSet, VecDeque};
+
+use super::run_with::run_with_output;
+use crate::cli::WhyArgs;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::graph::{Edge, ResolveGraph};
+use crate::core::output::{print_json, WhyHop, WhyOutput, WhyPath};
+use crate::core::platform::TargetFilter;
+
+/// Upper bound on listed paths; heavily shared crates can have thousands.
+const MAX_PATHS: usize = 100;
+
+pub async fn run(json: bool, args: WhyArgs) -> Result<()> {
+    run_with_output(json, async { analyze(&args) }, print_json, |output| {
+        print!("{output}");
+        Ok(())
+    })
+    .await
+}
+
+fn analyze(args: &WhyArgs) -> Result<WhyOutput> {
+    let features = args.feature_selection.selection();
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let targets = TargetFilter::from_args(&args.target.targets)?;
+    let graph = ResolveGraph::from_metadata(&metadata, true, &targets)?;
+
+    let (name, version) = parse_spec(&args.spec)?;
+    let wanted = matching_packages(&metadata, &name, version.as_ref());
+    if wanted.is_empty() {
+        let message = match version {
+            Some(_) => format!("no package matching '{}' found in metadata", args.spec),
+            None => format!("no package named '{name}' found in metadata"),
+        };
+        return Err(UpkeepError::message(ErrorCode::InvalidData, message));
+    }
+
+    let members: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let (paths, truncated) = find_paths(&graph, &members, &wanted);
+
+    let mut versions: Vec<String> = wanted
+        .iter()
+        .filter_map(|id| graph.package(id))
+        .map(|package| package.version.to_string())
+        .collect();
+    versions.sort();
+    versions.dedup();
+
+    Ok(WhyOutput {
+        crate_name: name,
+        versions,
+        paths: paths
+            .iter()
+            .map(|path| describe_path(&graph, path))
+            .collect(),
+        truncated,
+    })
+}
+
+/// Splits `name` or `name@version`; a partial version such as `1.0` matches
+/// every `1.0.x` release.
+fn parse_spec(spec: &str) -> Result<(String, Option<VersionReq>)> {
+    let Some((name, version)) = spec.split_once('@') else {
+        return Ok((spec.to_string(), None));
+    };
+    let req = VersionReq::parse(&format!("={version}")).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Config,
+            format!("invalid version in '{spec}'"),
+            err,
+        )
+    })?;
+    Ok((name.to_string(), Some(req)))
+}
+
+fn matching_packages(
+    metadata: &Metadata,
+    name: &str,
+    version: Option<&VersionReq>,
+) -> HashSet<PackageId> {
+    metadata
+        .packages
+        .iter()
+        .filter(|package| package.name.as_str() == name)
+        .filter(|package| version.map_or(true, |req| req.matches(&package.version)))
+        .map(|package| package.id.clone())
+        .collect()
+}
+
+/// Enumerates simple paths from `members` to any of `wanted`, depth-first,
+/// stopping after [`MAX_PATHS`]. Returns the paths and whether more exist.
+fn find_paths(
+    graph: &ResolveGraph<'_>,
+    members: &[&PackageId],
+    wanted: &HashSet<PackageId>,
+) -> (Vec<Vec<PackageId>>, bool) {
+    // Only descend into packages that can reach the crate at all.
+    let dependents = graph.inverted();
+    let mut leads_to: HashSet<&PackageId> = wanted.iter().collect();
+    let mut queue: VecDeque<&PackageId> = wanted.iter().collect();
+    while let Some(id) = queue.pop_front() {
+        for edge in dependents.get(id).into_iter().flatten() {
+            if leads_to.insert(&edge.id) {
+                queue.push_back(&edge.id);
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    let mut truncated = false;
+    for member in members {
+        if !leads_to.contains(member) {
+            continue;
+        }
+        let mut path = vec![(*member).clone()];
+        walk(
+            graph,
+            &leads_to,
+            wanted,
+            &mut path,
+            &mut paths,
+            &mut truncated,
+        );
+    }
+    (paths, truncated)
+}
+
+fn walk(
+    graph: &ResolveGraph<'_>,
+    leads_to: &HashSet<&PackageId>,
+    wanted: &HashSet<PackageId>,
+    path: &mut Vec<PackageId>,
+    paths: &mut Vec<Vec<PackageId>>,
+    truncated: &mut bool,
+) {
+    let current = path.last().cloned().expect("path starts at a member");
+    if path.len() > 1 && wanted.contains(&current) {
+        if paths.len() == MAX_PATHS {
+            *truncated = true;
+        } else {
+            paths.push(path.clone());
+        }
+        return;
+    }
+
+    for edge in graph.edges.get(&current).into_iter().flatten() {
+        if *truncated {
+            return;
+        }
+        if !leads_to.contains(&edge.id) || path.contains(&edge.id) {
+            continue;
+        }
+        // Dev-dependencies are only built for the workspace member itself.
+        if path.len() > 1 && !edge.is_normal && !edge.is_build {
+            continue;
+        }
+        path.push(edge.id.clone());
+        walk(graph, leads_to, wanted, path, paths, truncated);
+        path.pop();
+    }
+}
+
+fn describe_path(graph: &ResolveGraph<'_>, path: &[PackageId]) -> WhyPath {
+    let name = |id: &PackageId| {
+        graph
+            .package(id)
+            .map(|package| package.name.to_string())
+            .unwrap_or_else(|| id.repr.clone())
+    };
+
+    let hops = path
+        .windows(2)
+        .filter_map(|pair| {
+            let (from, to) = (&pair[0], &pair[1]);
+            let edge = graph.edges.get(from)?.iter().find(|edge| edge.id == *to)?;
+            Some(describe_hop(graph, from, edge))
+        })
+        .collect();
+
+    WhyPath {
+        member: name(&path[0]),
+        hops,
+    }
+}
+
+fn describe_hop(graph: &ResolveGraph<'_>, from: &PackageId, edge: &Edge) -> WhyHop {
+    let parent = graph.package(from);
+    let child = graph.package(&edge.id);
+    let (optional, enabled_by) = match (parent, child) {
+        (Some(parent), Some(child)) => {
+            let parent_features = graph
+                .features_by_id
+                .get(from)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            enabling_features(parent, parent_features, child)
+        }
+        _ => (false, Vec::new()),
+    };
+
+    WhyHop {
+        from: parent
+            .map(|package| package.name.to_string())
+            .unwrap_or_else(|| from.repr.clone()),
+        to: child
+            .map(|package| package.name.to_string())
+            .unwrap_or_else(|| edge.id.repr.clone()),
+        version: child
+            .map(|package| package.version.to_string())
+            .unwrap_or_default(),
+        kinds: edge.kinds(),
+        optional,
+        enabled_by,
+    }
+}
+
+/// Works out whether `parent` only depends on `child` optionally and, if so,
+/// which of its enabled features turned the dependency on.
+fn enabling_features(
+    parent: &Package,
+    parent_features: &[String],
+    child: &Package,
+) -> (bool, Vec<String>) {
+    let declarations: Vec<_> = parent
+        .dependencies
+        .iter()
+        .filter(|dep| dep.name == child.name.as_str() && dep.req.matches(&child.version))
+        .collect();
+    if declarations.is_empty() || declarations.iter().any(|dep| !dep.optional) {
+        return (false, Vec::new());
+    }
+
+    let mut enabled_by = Vec::new();
+    for dep in declarations {
+        let key = dep.rename.as_deref().unwrap_or(&dep.name);
+        let explicit = format!("dep:{key}");
+        let prefix = format!("{key}/");
+        for feature in parent_features {
+            let enables = parent.features.get(feature).is_some_and(|values| {
+                values
+                    .iter()
+                    .any(|value| *value == explicit || value.starts_with(&prefix))
+            });
+            // Without a `dep:` entry anywhere, cargo creates an implicit feature
+            // named after the dependency.
+            let implicit = feature == key && !parent.features.contains_key(feature);
+            if (enables || implicit) && !enabled_by.contains(feature) {
+                enabled_by.push(feature.clone());
+            }
+        }
+    }
+    enabled_by.sort();
+    (true, enabled_by)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::output::{DependencyType, GraphEdgeKind};
+    use serde_json::json;
+
+    fn package(
+        name: &str,
+        dependencies: serde_json::Value,
+        features: serde_json::Value,
+    ) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": null,
+            "dependencies": dependencies,
+            "targets": [],
+            "features": features,
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn dependency(name: &str, optional: bool, rename: Option<&str>) -> serde_json::Value {
+        json!({
+            "name": name,
+            "source": null,
+            "req": "^1.0",
+            "kind": null,
+            "rename": rename,
+            "optional": optional,
+            "uses_default_features": true,
+            "features": [],
+            "target": null,
+        })
+    }
+
+    #[test]
+    fn parse_spec_accepts_optional_version() {
+        let (name, version) = parse_spec("serde").unwrap();
+        assert_eq!(name, "serde");
+        assert!(version.is_none());
+
+        let (name, version) = parse_spec("serde@1.0").unwrap();
+        assert_eq!(name, "serde");
+        let version = version.unwrap();
+        assert!(version.matches(&"1.0.200".parse().unwrap()));
+        assert!(!version.matches(&"1.1.0".parse().unwrap()));
+
+        assert_eq!(
+            parse_spec("serde@nope").unwrap_err().code(),
+            ErrorCode::Config
+        );
+    }
+
+    #[test]
+    fn enabling_features_reports_explicit_and_implicit_features() {
+        let child = package("rustls", json!([]), json!({}));
+
+        let parent = package(
+            "client",
+            json!([dependency("rustls", true, None)]),
+            json!({ "tls": ["dep:rustls"], "full": ["tls"], "default": [] }),
+        );
+        let enabled = ["full".to_string(), "tls".to_string()];
+        assert_eq!(
+            enabling_features(&parent, &enabled, &child),
+            (true, vec!["tls".to_string()])
+        );
+
+        let implicit = package(
+            "client",
+            json!([dependency("rustls", true, Some("tls_impl"))]),
+            json!({ "default": ["tls_impl"] }),
+        );
+        let enabled = ["default".to_string(), "tls_impl".to_string()];
+        assert_eq!(
+            enabling_features(&implicit, &enabled, &child),
+            (true, vec!["tls_impl".to_string()])
+        );
+
+        let required = package(
+            "client",
+            json!([dependency("rustls", false, None)]),
+            json!({}),
+        );
+        assert_eq!(
+            enabling_features(&required, &[], &child),
+            (false, Vec::new())
+        );
+    }
+
+    #[test]
+    fn why_output_renders_paths() {
+        let output = WhyOutput {
+            crate_name: "rustls".to_string(),
+            versions: vec!["1.0.0".to_string()],
+            paths: vec![WhyPath {
+                member: "app".to_string(),
+                hops: vec![WhyHop {
+                    from: "app".to_string(),
+                    to: "rustls".to_string(),
+                    version: "1.0.0".to_string(),
+                    kinds: vec![GraphEdgeKind {
+                        kind: DependencyType::Normal,
+                        target: None,
+                    }],
+                    optional: true,
+                    enabled_by: vec!["tls".to_string()],
+                }],
+            }],
+            truncated: false,
+        };
+
+        let rendered = output.to_string();
+        assert!(rendered.contains("rustls 1.0.0 is required by 1 path(s):"));
+        assert!(rendered.contains("  -> rustls v1.0.0 (normal, optional, enabled by app/tls)"));
+
+        let value = serde_json::to_value(&output).unwrap();
+        assert_eq!(value["crate"], "rustls");
+        assert_eq!(value["paths"][0]["hops"][0]["enabled_by"][0], "tls");
+    }
+}
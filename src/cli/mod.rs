@@ -3,7 +3,11 @@
 pub mod commands;
 
 use crate::core::error::{ErrorCode, Result, UpkeepError};
-use clap::{Args, Parser, Subcommand};
+use crate::core::features::FeatureSelection;
+use crate::core::scorers::quality::UnavailablePolicy;
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 use tracing_subscriber::EnvFilter;
 
 #[derive(Debug, Parser)]
@@ -31,18 +35,25 @@ pub enum Command {
     #[command(about = "Detect workspace, tooling, and CI")]
     Detect,
     #[command(about = "Report RustSec vulnerabilities")]
-    Audit,
+    Audit(AuditArgs),
     Deps {
         #[arg(
             long,
             help = "Include RustSec advisories for direct workspace deps (requires Cargo.lock)"
         )]
         security: bool,
+        #[arg(
+            long,
+            help = "Compare git dependencies with their remotes (runs git ls-remote and fetch)"
+        )]
+        git: bool,
+        #[command(flatten)]
+        target: TargetArgs,
     },
     #[command(about = "Compute project quality score")]
-    Quality,
+    Quality(QualityArgs),
     #[command(about = "Find unused dependencies")]
-    Unused,
+    Unused(TargetArgs),
     #[command(
         name = "unsafe-code",
         alias = "unsafe",
@@ -51,6 +62,34 @@ pub enum Command {
     UnsafeCode,
     #[command(about = "Render dependency tree with filters")]
     Tree(TreeArgs),
+    #[command(about = "Compute the minimal supported Rust version")]
+    Msrv(MsrvArgs),
+    #[command(about = "Explain why a crate is in the dependency graph")]
+    Why(WhyArgs),
+    #[command(about = "Suggest how to consolidate duplicate crate versions")]
+    Duplicates(DuplicatesArgs),
+    #[command(about = "Show what each direct dependency adds to the build")]
+    Weight(WeightArgs),
+    #[command(about = "Compare two Cargo.lock files or git revisions")]
+    Lockdiff(LockdiffArgs),
+    #[command(about = "Check public API changes against the version bump")]
+    Semver(SemverArgs),
+    #[command(about = "Check dependency licenses against the configured policy")]
+    Licenses(LicensesArgs),
+    #[command(about = "Generate a software bill of materials")]
+    Sbom(SbomArgs),
+    #[command(about = "Check where dependencies come from against the source policy")]
+    Sources(SourcesArgs),
+    #[command(about = "List [patch] and [replace] overrides and whether they are still needed")]
+    Patches(PatchesArgs),
+    #[command(about = "Suggest moving shared dependencies into [workspace.dependencies]")]
+    Inherit(InheritArgs),
+    #[command(about = "Find dependency and package settings that differ between members")]
+    Consistency,
+    #[command(about = "Check feature hygiene and optionally cargo check feature combinations")]
+    Features(FeaturesArgs),
+    #[command(about = "List dependencies that run build scripts or proc-macros")]
+    BuildTime(BuildTimeArgs),
 }
 
 #[derive(Debug, Subcommand)]
@@ -58,18 +97,25 @@ pub enum UpkeepCommand {
     #[command(about = "Detect workspace, tooling, and CI")]
     Detect,
     #[command(about = "Report RustSec vulnerabilities")]
-    Audit,
+    Audit(AuditArgs),
     Deps {
         #[arg(
             long,
             help = "Include RustSec advisories for direct workspace deps (requires Cargo.lock)"
         )]
         security: bool,
+        #[arg(
+            long,
+            help = "Compare git dependencies with their remotes (runs git ls-remote and fetch)"
+        )]
+        git: bool,
+        #[command(flatten)]
+        target: TargetArgs,
     },
     #[command(about = "Compute project quality score")]
-    Quality,
+    Quality(QualityArgs),
     #[command(about = "Find unused dependencies")]
-    Unused,
+    Unused(TargetArgs),
     #[command(
         name = "unsafe-code",
         alias = "unsafe",
@@ -78,20 +124,400 @@ pub enum UpkeepCommand {
     UnsafeCode,
     #[command(about = "Render dependency tree with filters")]
     Tree(TreeArgs),
+    #[command(about = "Compute the minimal supported Rust version")]
+    Msrv(MsrvArgs),
+    #[command(about = "Explain why a crate is in the dependency graph")]
+    Why(WhyArgs),
+    #[command(about = "Suggest how to consolidate duplicate crate versions")]
+    Duplicates(DuplicatesArgs),
+    #[command(about = "Show what each direct dependency adds to the build")]
+    Weight(WeightArgs),
+    #[command(about = "Compare two Cargo.lock files or git revisions")]
+    Lockdiff(LockdiffArgs),
+    #[command(about = "Check public API changes against the version bump")]
+    Semver(SemverArgs),
+    #[command(about = "Check dependency licenses against the configured policy")]
+    Licenses(LicensesArgs),
+    #[command(about = "Generate a software bill of materials")]
+    Sbom(SbomArgs),
+    #[command(about = "Check where dependencies come from against the source policy")]
+    Sources(SourcesArgs),
+    #[command(about = "List [patch] and [replace] overrides and whether they are still needed")]
+    Patches(PatchesArgs),
+    #[command(about = "Suggest moving shared dependencies into [workspace.dependencies]")]
+    Inherit(InheritArgs),
+    #[command(about = "Find dependency and package settings that differ between members")]
+    Consistency,
+    #[command(about = "Check feature hygiene and optionally cargo check feature combinations")]
+    Features(FeaturesArgs),
+    #[command(about = "List dependencies that run build scripts or proc-macros")]
+    BuildTime(BuildTimeArgs),
 }
 
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Default)]
+pub struct QualityArgs {
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Only compute the listed metrics"
+    )]
+    pub only: Vec<QualityMetric>,
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Skip the listed metrics"
+    )]
+    pub skip: Vec<QualityMetric>,
+    #[arg(
+        long,
+        default_value = "exclude",
+        value_parser = unavailable_policy_parser(),
+        help = "How to score metrics whose analyzer failed"
+    )]
+    pub unavailable: UnavailablePolicy,
+    #[arg(
+        long,
+        help = "Build each member under its declared rust-version toolchain (requires rustup)"
+    )]
+    pub verify_msrv: bool,
+    #[arg(
+        long,
+        help = "Also check public API changes against the version bump (requires cargo-semver-checks)"
+    )]
+    pub semver: bool,
+    #[arg(
+        long,
+        help = "Also check dependency licenses against the policy in [workspace.metadata.upkeep]"
+    )]
+    pub licenses: bool,
+}
+
+/// Parses `--unavailable`, keeping the clap binding out of the scorer.
+fn unavailable_policy_parser() -> impl TypedValueParser<Value = UnavailablePolicy> {
+    PossibleValuesParser::new([
+        PossibleValue::new("exclude")
+            .help("Leave the metric out and renormalize the remaining weights"),
+        PossibleValue::new("zero").help("Score the metric as zero with its full weight"),
+        PossibleValue::new("fail").help("Fail the quality run"),
+    ])
+    .map(|value| match value.as_str() {
+        "zero" => UnavailablePolicy::Zero,
+        "fail" => UnavailablePolicy::Fail,
+        _ => UnavailablePolicy::Exclude,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QualityMetric {
+    Deps,
+    Security,
+    Unused,
+    Unsafe,
+    Clippy,
+    Msrv,
+    /// Opt-in: only computed with `--semver` or when listed in `--only`.
+    Semver,
+    /// Opt-in: only computed with `--licenses` or when listed in `--only`.
+    Licenses,
+}
+
+#[derive(Debug, Clone, Args, Default)]
+pub struct TargetArgs {
+    #[arg(
+        long = "target",
+        value_name = "TRIPLE",
+        help = "Only include dependencies built for this target (repeatable; also `host` or `all`)"
+    )]
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Args, Default)]
+pub struct FeatureArgs {
+    #[arg(
+        long,
+        value_name = "FEATURES",
+        help = "Comma or space separated list of features to activate"
+    )]
+    pub features: Vec<String>,
+    #[arg(long, help = "Activate all available features")]
+    pub all_features: bool,
+    #[arg(long, help = "Do not activate the `default` feature")]
+    pub no_default_features: bool,
+    #[arg(
+        short = 'p',
+        long = "package",
+        value_name = "NAME",
+        help = "Workspace member to resolve features for and root the graph at"
+    )]
+    pub package: Option<String>,
+}
+
+impl FeatureArgs {
+    pub fn selection(&self) -> FeatureSelection {
+        FeatureSelection {
+            features: self.features.clone(),
+            all_features: self.all_features,
+            no_default_features: self.no_default_features,
+            package: self.package.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Args, Default)]
 pub struct TreeArgs {
     #[arg(long, help = "Limit recursion depth")]
     pub depth: Option<usize>,
     #[arg(long, help = "Only show duplicate crates")]
     pub duplicates: bool,
-    #[arg(long, help = "Invert tree to show reverse dependencies")]
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Invert tree to show reverse dependencies of crates matching a name, glob, or /regex/"
+    )]
     pub invert: Option<String>,
     #[arg(long, help = "Include enabled features")]
-    pub features: bool,
+    pub show_features: bool,
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Hide crates matching a glob or /regex/ and their subtrees (repeatable)"
+    )]
+    pub prune: Vec<String>,
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SOURCE",
+        help = "Hide crates from this kind of source and their subtrees (repeatable)"
+    )]
+    pub exclude_source: Vec<SourceKind>,
+    #[arg(long, help = "Only show the direct dependencies of workspace members")]
+    pub only_direct: bool,
+    #[arg(long, value_enum, default_value_t, help = "Output format")]
+    pub format: TreeFormat,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct AuditArgs {
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TreeFormat {
+    /// Indented text tree
+    #[default]
+    Text,
+    /// Graphviz DOT graph
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Flat JSON list of packages and edges
+    GraphJson,
+}
+
+/// Where a package comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SourceKind {
+    /// A git repository
+    Git,
+    /// A local path
+    Path,
+    /// A registry such as crates.io
+    Registry,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct WhyArgs {
+    #[arg(value_name = "CRATE", help = "Crate name, optionally as name@version")]
+    pub spec: String,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct DuplicatesArgs {
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[arg(long, help = "Skip crates.io lookups for newer parent releases")]
+    pub offline: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct WeightArgs {
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct LockdiffArgs {
+    #[arg(
+        value_name = "OLD",
+        help = "Lockfile path or git revision (`rev` or `rev:path`) to compare from"
+    )]
+    pub old: String,
+    #[arg(
+        value_name = "NEW",
+        help = "Lockfile path or git revision (`rev` or `rev:path`) to compare to"
+    )]
+    pub new: String,
+    #[arg(long, help = "Skip the RustSec advisory check")]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct LicensesArgs {
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct SourcesArgs {
     #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
     pub no_dev: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct InheritArgs {
+    #[arg(
+        long,
+        help = "Move the suggested dependencies into [workspace.dependencies] and inherit them"
+    )]
+    pub apply: bool,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct PatchesArgs {
+    #[arg(long, help = "Skip crates.io lookups for newer upstream releases")]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct FeaturesArgs {
+    #[arg(
+        long,
+        help = "Run cargo check for every combination of up to --depth features"
+    )]
+    pub powerset: bool,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 2,
+        help = "Largest number of features combined in one powerset check"
+    )]
+    pub depth: usize,
+    #[arg(
+        short = 'p',
+        long = "package",
+        value_name = "NAME",
+        help = "Only check this workspace member"
+    )]
+    pub package: Option<String>,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct BuildTimeArgs {
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Earlier `build-time --json` report to mark new crates against"
+    )]
+    pub baseline: Option<PathBuf>,
+    #[arg(
+        long,
+        requires = "baseline",
+        help = "Exit with an error when a crate is missing from the baseline"
+    )]
+    pub fail_on_new: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct SbomArgs {
+    #[arg(long, value_enum, default_value_t, help = "Document format")]
+    pub format: SbomFormat,
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Write the document to a file instead of stdout"
+    )]
+    pub output: Option<PathBuf>,
+    #[arg(long, help = "Embed RustSec advisories affecting the components")]
+    pub vulnerabilities: bool,
+    #[arg(long = "no-dev", help = "Exclude dev-dependencies")]
+    pub no_dev: bool,
+    #[command(flatten)]
+    pub target: TargetArgs,
+    #[command(flatten)]
+    pub feature_selection: FeatureArgs,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[default]
+    CyclonedxJson,
+    /// SPDX 2.3 JSON
+    #[value(alias = "spdx-json")]
+    Spdx,
+    /// SPDX 2.3 tag-value
+    SpdxTagValue,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct SemverArgs {
+    #[arg(
+        short = 'p',
+        long = "package",
+        value_name = "NAME",
+        help = "Workspace member to check (defaults to every publishable library)"
+    )]
+    pub package: Option<String>,
+    #[arg(
+        long,
+        value_name = "REV",
+        help = "Git revision to compare against instead of the latest crates.io release"
+    )]
+    pub baseline_rev: Option<String>,
+}
+
+#[derive(Debug, Args, Default)]
+pub struct MsrvArgs {
+    #[arg(long, help = "Write the computed MSRV to rust-version in Cargo.toml")]
+    pub write: bool,
 }
 
 pub fn init_logging(verbose: bool, log_level: Option<&str>) -> Result<()> {
@@ -128,9 +554,13 @@ pub fn init_logging(verbose: bool, log_level: Option<&str>) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cli, Command, TreeArgs, UpkeepCommand};
+    use super::{
+        Cli, Command, QualityMetric, SbomFormat, SourceKind, TreeArgs, TreeFormat,
+        UnavailablePolicy, UpkeepCommand,
+    };
     use crate::core::error::ErrorCode;
     use clap::{error::ErrorKind, Parser};
+    use std::path::PathBuf;
 
     #[test]
     fn parses_upkeep_subcommand() {
@@ -160,8 +590,22 @@ mod tests {
             "--duplicates",
             "--invert",
             "serde",
-            "--features",
+            "--show-features",
             "--no-dev",
+            "--format",
+            "mermaid",
+            "--features",
+            "a,b",
+            "--no-default-features",
+            "-p",
+            "app",
+            "--prune",
+            "windows-*",
+            "--prune",
+            "/^syn$/",
+            "--exclude-source",
+            "git",
+            "--only-direct",
         ])
         .unwrap();
 
@@ -169,9 +613,46 @@ mod tests {
             Command::Tree(args) => {
                 assert_eq!(args.depth, Some(2));
                 assert!(args.duplicates);
+                assert_eq!(args.format, TreeFormat::Mermaid);
                 assert_eq!(args.invert.as_deref(), Some("serde"));
-                assert!(args.features);
+                assert!(args.show_features);
                 assert!(args.no_dev);
+                assert_eq!(args.feature_selection.features, vec!["a,b"]);
+                assert!(args.feature_selection.no_default_features);
+                assert!(!args.feature_selection.all_features);
+                assert_eq!(args.feature_selection.package.as_deref(), Some("app"));
+                assert_eq!(args.prune, vec!["windows-*", "/^syn$/"]);
+                assert_eq!(args.exclude_source, vec![SourceKind::Git]);
+                assert!(args.only_direct);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_repeated_target_flags() {
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "deps",
+            "--target",
+            "host",
+            "--target",
+            "x86_64-pc-windows-msvc",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Deps { target, .. } => {
+                assert_eq!(target.targets, vec!["host", "x86_64-pc-windows-msvc"]);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["cargo-upkeep", "upkeep", "audit", "--target", "all"]).unwrap();
+        match cli.command {
+            Command::Upkeep(UpkeepCommand::Audit(args)) => {
+                assert_eq!(args.target.targets, vec!["all"]);
             }
             _ => panic!("unexpected subcommand"),
         }
@@ -189,6 +670,241 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_quality_metric_selection() {
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "quality",
+            "--only",
+            "deps,security",
+            "--skip",
+            "clippy",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Quality(args) => {
+                assert_eq!(
+                    args.only,
+                    vec![QualityMetric::Deps, QualityMetric::Security]
+                );
+                assert_eq!(args.skip, vec![QualityMetric::Clippy]);
+                assert_eq!(args.unavailable, UnavailablePolicy::Exclude);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_quality_unavailable_policy() {
+        let cli =
+            Cli::try_parse_from(["cargo-upkeep", "quality", "--unavailable", "fail"]).unwrap();
+
+        match cli.command {
+            Command::Quality(args) => assert_eq!(args.unavailable, UnavailablePolicy::Fail),
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_semver_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "quality", "--semver"]).unwrap();
+        match cli.command {
+            Command::Quality(args) => assert!(args.semver),
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "semver",
+            "-p",
+            "mylib",
+            "--baseline-rev",
+            "v1.2.0",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Semver(args) => {
+                assert_eq!(args.package.as_deref(), Some("mylib"));
+                assert_eq!(args.baseline_rev.as_deref(), Some("v1.2.0"));
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_licenses_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "quality", "--only", "licenses"]).unwrap();
+        match cli.command {
+            Command::Quality(args) => assert_eq!(args.only, vec![QualityMetric::Licenses]),
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["cargo-upkeep", "licenses", "--no-dev", "-p", "app"]).unwrap();
+        match cli.command {
+            Command::Licenses(args) => {
+                assert!(args.no_dev);
+                assert_eq!(args.feature_selection.package.as_deref(), Some("app"));
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_sources_options() {
+        let cli =
+            Cli::try_parse_from(["cargo-upkeep", "sources", "--no-dev", "-p", "app"]).unwrap();
+        match cli.command {
+            Command::Sources(args) => {
+                assert!(args.no_dev);
+                assert_eq!(args.feature_selection.package.as_deref(), Some("app"));
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_patches_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "patches", "--offline"]).unwrap();
+        match cli.command {
+            Command::Patches(args) => assert!(args.offline),
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_inherit_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "inherit", "--apply"]).unwrap();
+        match cli.command {
+            Command::Inherit(args) => assert!(args.apply),
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_consistency_subcommand() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "upkeep", "consistency"]).unwrap();
+        match cli.command {
+            Command::Upkeep(UpkeepCommand::Consistency) => {}
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_features_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "upkeep", "features"]).unwrap();
+        match cli.command {
+            Command::Upkeep(UpkeepCommand::Features(args)) => {
+                assert!(!args.powerset);
+                assert_eq!(args.depth, 2);
+                assert!(args.package.is_none());
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "features",
+            "--powerset",
+            "--depth",
+            "1",
+            "-p",
+            "core",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Features(args) => {
+                assert!(args.powerset);
+                assert_eq!(args.depth, 1);
+                assert_eq!(args.package.as_deref(), Some("core"));
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+    }
+
+    #[test]
+    fn parses_build_time_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "upkeep", "build-time"]).unwrap();
+        match cli.command {
+            Command::Upkeep(UpkeepCommand::BuildTime(args)) => {
+                assert!(args.baseline.is_none());
+                assert!(!args.fail_on_new);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "build-time",
+            "--baseline",
+            "build-time.json",
+            "--fail-on-new",
+            "--no-dev",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::BuildTime(args) => {
+                assert_eq!(args.baseline, Some(PathBuf::from("build-time.json")));
+                assert!(args.fail_on_new);
+                assert!(args.no_dev);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        assert!(Cli::try_parse_from(["cargo-upkeep", "build-time", "--fail-on-new"]).is_err());
+    }
+
+    #[test]
+    fn parses_sbom_options() {
+        let cli = Cli::try_parse_from(["cargo-upkeep", "sbom"]).unwrap();
+        match cli.command {
+            Command::Sbom(args) => {
+                assert_eq!(args.format, SbomFormat::CyclonedxJson);
+                assert!(args.output.is_none());
+                assert!(!args.vulnerabilities);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "cargo-upkeep",
+            "sbom",
+            "--format",
+            "cyclonedx-json",
+            "-o",
+            "bom.json",
+            "--vulnerabilities",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Sbom(args) => {
+                assert_eq!(args.output, Some(PathBuf::from("bom.json")));
+                assert!(args.vulnerabilities);
+            }
+            _ => panic!("unexpected subcommand"),
+        }
+
+        for (value, format) in [
+            ("spdx", SbomFormat::Spdx),
+            ("spdx-json", SbomFormat::Spdx),
+            ("spdx-tag-value", SbomFormat::SpdxTagValue),
+        ] {
+            let cli = Cli::try_parse_from(["cargo-upkeep", "sbom", "--format", value]).unwrap();
+            match cli.command {
+                Command::Sbom(args) => assert_eq!(args.format, format),
+                _ => panic!("unexpected subcommand"),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_quality_metric() {
+        let err =
+            Cli::try_parse_from(["cargo-upkeep", "quality", "--skip", "coverage"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
     #[test]
     fn parses_global_flags() {
         let cli = Cli::try_parse_from([
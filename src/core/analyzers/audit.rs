@@ -7,12 +7,22 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 
 use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
 use crate::core::output::{AuditOutput, AuditSummary, Severity, Vulnerability};
-
-pub fn run_audit() -> Result<AuditOutput> {
-    let metadata = MetadataCommand::new().exec().map_err(|err| {
+use crate::core::platform::TargetFilter;
+
+/// Audits `Cargo.lock` against the RustSec database. Paths are traced through
+/// dependency edges active on `targets` and resolved with `features`; when
+/// specific targets or a package are selected, vulnerable packages that are not
+/// part of that build are left out.
+pub fn run_audit(targets: &TargetFilter, features: &FeatureSelection) -> Result<AuditOutput> {
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
         UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
     })?;
+    let selected_package = features.selected_package(&metadata)?;
+    let scoped = !targets.is_all() || selected_package.is_some();
 
     let workspace_root = PathBuf::from(&metadata.workspace_root);
     let lockfile_path = workspace_root.join("Cargo.lock");
@@ -24,17 +34,14 @@ pub fn run_audit() -> Result<AuditOutput> {
         )
     })?;
 
-    let db = Database::fetch().map_err(|err| {
-        UpkeepError::context(
-            ErrorCode::Rustsec,
-            "failed to fetch RustSec advisory database",
-            err,
-        )
-    })?;
+    let db = fetch_database()?;
     let settings = Settings::default();
     let report = Report::generate(&db, &lockfile, &settings);
 
-    let graph = DependencyGraph::build(&metadata)?;
+    let mut graph = DependencyGraph::build(&metadata, targets)?;
+    if let Some(package) = selected_package {
+        graph.roots = vec![package.id.clone()];
+    }
 
     let mut vulnerabilities = Vec::new();
     for entry in &report.vulnerabilities.list {
@@ -42,18 +49,21 @@ pub fn run_audit() -> Result<AuditOutput> {
         let package_name = entry.package.name.to_string();
         let package_version = entry.package.version.to_string();
 
-        let path = graph
-            .path_to(
-                &package_name,
-                &package_version,
-                entry
-                    .package
-                    .source
-                    .as_ref()
-                    .map(|s| s.to_string())
-                    .as_deref(),
-            )
-            .unwrap_or_else(|| vec![package_name.clone()]);
+        let path = graph.path_to(
+            &package_name,
+            &package_version,
+            entry
+                .package
+                .source
+                .as_ref()
+                .map(|s| s.to_string())
+                .as_deref(),
+        );
+        let path = match path {
+            Some(path) => path,
+            None if scoped => continue,
+            None => vec![package_name.clone()],
+        };
 
         vulnerabilities.push(Vulnerability {
             id: advisory.id.to_string(),
@@ -73,6 +83,36 @@ pub fn run_audit() -> Result<AuditOutput> {
     })
 }
 
+pub fn fetch_database() -> Result<Database> {
+    Database::fetch().map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Rustsec,
+            "failed to fetch RustSec advisory database",
+            err,
+        )
+    })
+}
+
+/// Lists the advisories affecting the packages in `lockfile`. Without a
+/// resolved graph the path is just the package itself.
+pub fn lockfile_vulnerabilities(db: &Database, lockfile: &Lockfile) -> Vec<Vulnerability> {
+    let report = Report::generate(db, lockfile, &Settings::default());
+    report
+        .vulnerabilities
+        .list
+        .iter()
+        .map(|entry| Vulnerability {
+            id: entry.advisory.id.to_string(),
+            package: entry.package.name.to_string(),
+            package_version: entry.package.version.to_string(),
+            severity: map_severity(entry.advisory.cvss.as_ref().map(|c| c.severity())),
+            title: entry.advisory.title.to_string(),
+            path: vec![entry.package.name.to_string()],
+            fix_available: !entry.versions.patched().is_empty(),
+        })
+        .collect()
+}
+
 fn map_severity(severity: Option<RustsecSeverity>) -> Severity {
     match severity {
         Some(RustsecSeverity::Critical) => Severity::Critical,
@@ -113,7 +153,7 @@ struct DependencyGraph {
 }
 
 impl DependencyGraph {
-    fn build(metadata: &Metadata) -> Result<Self> {
+    fn build(metadata: &Metadata, targets: &TargetFilter) -> Result<Self> {
         let resolve = metadata.resolve.as_ref().ok_or_else(|| {
             UpkeepError::message(ErrorCode::InvalidData, "metadata missing resolve data")
         })?;
@@ -133,7 +173,18 @@ impl DependencyGraph {
 
         let mut adjacency: HashMap<PackageId, Vec<PackageId>> = HashMap::new();
         for node in &resolve.nodes {
-            let deps = node.deps.iter().map(|dep| dep.pkg.clone()).collect();
+            let deps = node
+                .deps
+                .iter()
+                .filter(|dep| {
+                    dep.dep_kinds.is_empty()
+                        || dep
+                            .dep_kinds
+                            .iter()
+                            .any(|kind| targets.matches(kind.target.as_ref()))
+                })
+                .map(|dep| dep.pkg.clone())
+                .collect();
             adjacency.insert(node.id.clone(), deps);
         }
 
@@ -294,7 +345,7 @@ mod tests {
     #[test]
     fn dependency_graph_path_to_returns_none_for_missing_package() {
         let metadata = MetadataCommand::new().exec().expect("metadata");
-        let graph = DependencyGraph::build(&metadata).expect("graph");
+        let graph = DependencyGraph::build(&metadata, &TargetFilter::all()).expect("graph");
 
         let missing = graph.path_to("missing-pkg-that-does-not-exist", "0.0.0", None);
         assert!(
@@ -308,7 +359,7 @@ mod tests {
     #[test]
     fn dependency_graph_path_to_fallback_finds_root_package() {
         let metadata = MetadataCommand::new().exec().expect("metadata");
-        let graph = DependencyGraph::build(&metadata).expect("graph");
+        let graph = DependencyGraph::build(&metadata, &TargetFilter::all()).expect("graph");
 
         let root = metadata
             .root_package()
@@ -329,7 +380,7 @@ mod tests {
     #[test]
     fn dependency_graph_path_to_exact_match_finds_registry_package() {
         let metadata = MetadataCommand::new().exec().expect("metadata");
-        let graph = DependencyGraph::build(&metadata).expect("graph");
+        let graph = DependencyGraph::build(&metadata, &TargetFilter::all()).expect("graph");
 
         let registry_pkg = metadata
             .packages
@@ -0,0 +1,222 @@
+//! Dependencies with a build script or a proc-macro target, which run code
+//! on the machine that builds the workspace.
+
+use cargo_metadata::{MetadataCommand, Package, PackageId, TargetKind};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::core::analyzers::sources::classify;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{BuildTimeCrate, BuildTimeOutput};
+use crate::core::platform::TargetFilter;
+
+/// Lists the build-time crates reachable from the workspace members (or the
+/// `-p` package), compared against `baseline` when one is given.
+pub fn run_build_time(
+    include_dev: bool,
+    targets: &TargetFilter,
+    features: &FeatureSelection,
+    baseline: Option<&Path>,
+) -> Result<BuildTimeOutput> {
+    let known = baseline.map(read_baseline).transpose()?;
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let graph = ResolveGraph::from_metadata(&metadata, include_dev, targets)?;
+
+    let roots: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let members: HashSet<&PackageId> = metadata.workspace_members.iter().collect();
+
+    // Credit each crate to the direct dependencies it is reached through.
+    // Other members are not crossed, so their dependencies are credited to
+    // their own direct dependencies instead.
+    let mut via: HashMap<PackageId, BTreeSet<String>> = HashMap::new();
+    let reachable = graph.reachable_from_roots(&roots, |_, _| false);
+    for member in members.iter().filter(|id| reachable.contains(**id)) {
+        for edge in graph.edges.get(*member).into_iter().flatten() {
+            let Some(direct) = graph.package(&edge.id) else {
+                continue;
+            };
+            if members.contains(&edge.id) {
+                continue;
+            }
+            let pulled_in = graph.reachable_from_roots(&[&edge.id], |_, next| {
+                members.contains(&next.id) || (!next.is_normal && !next.is_build)
+            });
+            for id in pulled_in {
+                via.entry(id).or_default().insert(direct.name.to_string());
+            }
+        }
+    }
+
+    let mut packages: Vec<&Package> = reachable
+        .iter()
+        .filter(|id| !members.contains(id))
+        .filter_map(|id| graph.package(id))
+        .collect();
+    packages.sort_by(|left, right| {
+        left.name
+            .cmp(&right.name)
+            .then_with(|| left.version.cmp(&right.version))
+    });
+
+    Ok(BuildTimeOutput {
+        crates: inventory(&packages, &via, known.as_ref()),
+        baseline: baseline.map(|path| path.display().to_string()),
+    })
+}
+
+pub fn inventory(
+    packages: &[&Package],
+    via: &HashMap<PackageId, BTreeSet<String>>,
+    baseline: Option<&HashSet<String>>,
+) -> Vec<BuildTimeCrate> {
+    packages
+        .iter()
+        .filter_map(|package| {
+            let has = |kind: &TargetKind| {
+                package
+                    .targets
+                    .iter()
+                    .any(|target| target.kind.contains(kind))
+            };
+            let build_script = has(&TargetKind::CustomBuild);
+            let proc_macro = has(&TargetKind::ProcMacro);
+            if !build_script && !proc_macro {
+                return None;
+            }
+            Some(BuildTimeCrate {
+                name: package.name.to_string(),
+                version: package.version.to_string(),
+                source: classify(package).kind,
+                build_script,
+                proc_macro,
+                links: package.links.clone(),
+                via: via
+                    .get(&package.id)
+                    .map(|names| names.iter().cloned().collect())
+                    .unwrap_or_default(),
+                new: baseline.is_some_and(|known| !known.contains(package.name.as_str())),
+            })
+        })
+        .collect()
+}
+
+/// Crate names from an earlier `build-time --json` report. Names rather than
+/// versions are compared, so upgrading a known crate is not reported as new.
+fn read_baseline(path: &Path) -> Result<HashSet<String>> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Io,
+            format!("failed to read baseline {}", path.display()),
+            err,
+        )
+    })?;
+    parse_baseline(&contents).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Json,
+            format!("failed to parse baseline {}", path.display()),
+            err,
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct Baseline {
+    crates: Vec<BaselineCrate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaselineCrate {
+    name: String,
+}
+
+fn parse_baseline(contents: &str) -> serde_json::Result<HashSet<String>> {
+    let baseline: Baseline = serde_json::from_str(contents)?;
+    Ok(baseline
+        .crates
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn package(name: &str, kinds: &[&str], links: Option<&str>) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "dependencies": [],
+            "targets": kinds.iter().map(|kind| json!({
+                "name": name,
+                "kind": [kind],
+                "crate_types": [kind],
+                "src_path": format!("/registry/{name}/src/lib.rs"),
+                "edition": "2021",
+                "doctest": false,
+                "test": false,
+            })).collect::<Vec<_>>(),
+            "features": {},
+            "manifest_path": format!("/registry/{name}/Cargo.toml"),
+            "edition": "2021",
+            "links": links,
+        }))
+        .expect("package")
+    }
+
+    #[test]
+    fn inventory_lists_build_scripts_and_proc_macros() {
+        let sys = package("ring", &["lib", "custom-build"], Some("ring_core"));
+        let derive = package("serde_derive", &["proc-macro"], None);
+        let plain = package("itoa", &["lib"], None);
+        let via = HashMap::from([(derive.id.clone(), BTreeSet::from(["serde".to_string()]))]);
+        let baseline = HashSet::from(["serde_derive".to_string()]);
+
+        let crates = inventory(&[&sys, &derive, &plain], &via, Some(&baseline));
+
+        let summary: Vec<(&str, bool, bool, bool)> = crates
+            .iter()
+            .map(|krate| {
+                (
+                    krate.name.as_str(),
+                    krate.build_script,
+                    krate.proc_macro,
+                    krate.new,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ring", true, false, true),
+                ("serde_derive", false, true, false),
+            ]
+        );
+        assert_eq!(crates[0].links.as_deref(), Some("ring_core"));
+        assert_eq!(crates[1].via, vec!["serde"]);
+    }
+
+    #[test]
+    fn parse_baseline_reads_crate_names_from_a_report() {
+        let names = parse_baseline(
+            r#"{"crates":[{"name":"ring","version":"0.17.8","proc_macro":false}],"baseline":null}"#,
+        )
+        .unwrap();
+        assert_eq!(names, HashSet::from(["ring".to_string()]));
+        assert!(parse_baseline("[]").is_err());
+    }
+}
@@ -0,0 +1,426 @@
+//! Dependency declarations and package settings that differ between
+//! workspace members.
+
+use cargo_metadata::{Dependency, DependencyKind, MetadataCommand, Package};
+use semver::{Op, Version, VersionReq};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::core::analyzers::msrv::{format_rust_version, parse_rust_version};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::manifest::workspace_package_field;
+use crate::core::output::{ConsistencyFinding, ConsistencyKind, ConsistencyOutput, MemberValue};
+use crate::core::source::GitSource;
+
+/// `[workspace.package]` values members are expected to inherit.
+#[derive(Debug, Default)]
+pub struct WorkspaceDefaults {
+    pub edition: Option<String>,
+    pub rust_version: Option<String>,
+}
+
+pub fn run_consistency() -> Result<ConsistencyOutput> {
+    // Only declarations matter, so nothing needs resolving or downloading.
+    let metadata = MetadataCommand::new().no_deps().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let root_manifest = metadata.workspace_root.join("Cargo.toml");
+    let defaults = WorkspaceDefaults {
+        edition: workspace_package_field(root_manifest.as_std_path(), "edition")?,
+        rust_version: workspace_package_field(root_manifest.as_std_path(), "rust-version")?,
+    };
+    Ok(check_consistency(&metadata.workspace_packages(), &defaults))
+}
+
+pub fn check_consistency(members: &[&Package], defaults: &WorkspaceDefaults) -> ConsistencyOutput {
+    let mut declarations: BTreeMap<&str, Vec<(&Package, &Dependency)>> = BTreeMap::new();
+    for member in members {
+        for dep in &member.dependencies {
+            declarations
+                .entry(dep.name.as_str())
+                .or_default()
+                .push((member, dep));
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (name, declared) in &declarations {
+        let declaring: BTreeSet<&str> = declared
+            .iter()
+            .map(|(member, _)| member.name.as_str())
+            .collect();
+        if declaring.len() < 2 {
+            continue;
+        }
+
+        let registry: Vec<&(&Package, &Dependency)> = declared
+            .iter()
+            .filter(|(_, dep)| dep.path.is_none() && !is_git(dep))
+            .collect();
+        let lines: BTreeSet<(u64, u64, u64)> = registry
+            .iter()
+            .filter_map(|(_, dep)| compatible_line(&dep.req))
+            .collect();
+        if lines.len() > 1 {
+            findings.push(finding(
+                ConsistencyKind::IncompatibleRequirements,
+                name,
+                registry.iter().copied(),
+                |dep| dep.req.to_string(),
+            ));
+        }
+
+        let default_features: BTreeSet<bool> = declared
+            .iter()
+            .map(|(_, dep)| dep.uses_default_features)
+            .collect();
+        if default_features.len() > 1 {
+            findings.push(finding(
+                ConsistencyKind::DefaultFeatures,
+                name,
+                declared.iter(),
+                |dep| format!("default-features = {}", dep.uses_default_features),
+            ));
+        }
+
+        let feature_sets: BTreeSet<BTreeSet<&str>> = declared
+            .iter()
+            .map(|(_, dep)| dep.features.iter().map(String::as_str).collect())
+            .collect();
+        if feature_sets.len() > 1 {
+            findings.push(finding(
+                ConsistencyKind::Features,
+                name,
+                declared.iter(),
+                |dep| {
+                    let features: BTreeSet<&str> =
+                        dep.features.iter().map(String::as_str).collect();
+                    if features.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        features.into_iter().collect::<Vec<_>>().join(", ")
+                    }
+                },
+            ));
+        }
+
+        let renames: BTreeSet<Option<&str>> = declared
+            .iter()
+            .map(|(_, dep)| dep.rename.as_deref())
+            .collect();
+        if renames.len() > 1 {
+            findings.push(finding(
+                ConsistencyKind::Rename,
+                name,
+                declared.iter(),
+                |dep| match &dep.rename {
+                    Some(alias) => format!("as {alias}"),
+                    None => "(not renamed)".to_string(),
+                },
+            ));
+        }
+    }
+
+    let editions: Vec<(&Package, Option<String>)> = members
+        .iter()
+        .map(|member| (*member, Some(member.edition.to_string())))
+        .collect();
+    findings.extend(setting_finding(
+        ConsistencyKind::Edition,
+        "edition",
+        defaults.edition.clone(),
+        &editions,
+    ));
+
+    let rust_versions: Vec<(&Package, Option<String>)> = members
+        .iter()
+        .map(|member| {
+            (
+                *member,
+                member.rust_version.as_ref().map(format_rust_version),
+            )
+        })
+        .collect();
+    let default_rust_version = defaults
+        .rust_version
+        .as_deref()
+        .and_then(parse_rust_version)
+        .map(|version| format_rust_version(&version));
+    findings.extend(setting_finding(
+        ConsistencyKind::RustVersion,
+        "rust-version",
+        default_rust_version,
+        &rust_versions,
+    ));
+
+    ConsistencyOutput { findings }
+}
+
+fn finding<'a>(
+    kind: ConsistencyKind,
+    name: &str,
+    declared: impl Iterator<Item = &'a (&'a Package, &'a Dependency)>,
+    describe: impl Fn(&Dependency) -> String,
+) -> ConsistencyFinding {
+    let mut members = Vec::new();
+    for (member, dep) in declared {
+        let member = match dep.kind {
+            DependencyKind::Development => format!("{} (dev)", member.name),
+            DependencyKind::Build => format!("{} (build)", member.name),
+            _ => member.name.to_string(),
+        };
+        let value = describe(dep);
+        if !members
+            .iter()
+            .any(|existing: &MemberValue| existing.member == member && existing.value == value)
+        {
+            members.push(MemberValue { member, value });
+        }
+    }
+    ConsistencyFinding {
+        kind,
+        name: name.to_string(),
+        expected: None,
+        members,
+    }
+}
+
+/// Members whose `setting` differs from the workspace default, or from the
+/// value most members use when the workspace declares none.
+fn setting_finding(
+    kind: ConsistencyKind,
+    setting: &str,
+    default: Option<String>,
+    values: &[(&Package, Option<String>)],
+) -> Option<ConsistencyFinding> {
+    let expected = default.or_else(|| {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values.iter().filter_map(|(_, value)| value.as_deref()) {
+            *counts.entry(value).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by(|left, right| left.1.cmp(&right.1).then_with(|| left.0.cmp(right.0)))
+            .map(|(value, _)| value.to_string())
+    })?;
+    let members: Vec<MemberValue> = values
+        .iter()
+        .filter(|(_, value)| value.as_deref() != Some(expected.as_str()))
+        .map(|(member, value)| MemberValue {
+            member: member.name.to_string(),
+            value: value.clone().unwrap_or_else(|| "(none)".to_string()),
+        })
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+    Some(ConsistencyFinding {
+        kind,
+        name: setting.to_string(),
+        expected: Some(expected),
+        members,
+    })
+}
+
+fn is_git(dep: &Dependency) -> bool {
+    dep.source
+        .as_ref()
+        .is_some_and(|source| GitSource::parse(&source.to_string()).is_some())
+}
+
+/// The lowest version a requirement accepts.
+pub fn minimum_version(req: &VersionReq) -> Option<Version> {
+    req.comparators
+        .iter()
+        .filter(|comparator| {
+            matches!(
+                comparator.op,
+                Op::Exact | Op::GreaterEq | Op::Tilde | Op::Caret | Op::Wildcard
+            )
+        })
+        .map(|comparator| {
+            Version::new(
+                comparator.major,
+                comparator.minor.unwrap_or(0),
+                comparator.patch.unwrap_or(0),
+            )
+        })
+        .max()
+}
+
+/// The semver-compatible release line a requirement is confined to: `1.x`,
+/// `0.3.x` or `0.0.7`. Requirements confined to different lines cannot share
+/// a version. Requirements that also accept later lines, such as `*` or
+/// `>=1.0`, yield none.
+pub fn compatible_line(req: &VersionReq) -> Option<(u64, u64, u64)> {
+    let line = version_line(&minimum_version(req)?);
+    let next = match line {
+        (0, 0, patch) => Version::new(0, 0, patch + 1),
+        (0, minor, _) => Version::new(0, minor + 1, 0),
+        (major, _, _) => Version::new(major + 1, 0, 0),
+    };
+    (!req.matches(&next)).then_some(line)
+}
+
+/// The semver-compatible release line `version` belongs to.
+pub fn version_line(version: &Version) -> (u64, u64, u64) {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn member(
+        name: &str,
+        edition: &str,
+        rust_version: Option<&str>,
+        dependencies: serde_json::Value,
+    ) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "0.1.0",
+            "id": format!("{name} 0.1.0"),
+            "source": null,
+            "dependencies": dependencies,
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/work/{name}/Cargo.toml"),
+            "edition": edition,
+            "rust_version": rust_version,
+        }))
+        .expect("package")
+    }
+
+    fn dependency(
+        name: &str,
+        req: &str,
+        features: &[&str],
+        rename: Option<&str>,
+    ) -> serde_json::Value {
+        json!({
+            "name": name,
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "req": req,
+            "kind": null,
+            "optional": false,
+            "uses_default_features": true,
+            "features": features,
+            "target": null,
+            "rename": rename,
+            "registry": null,
+        })
+    }
+
+    #[test]
+    fn check_consistency_flags_dependency_differences() {
+        let app = member(
+            "app",
+            "2021",
+            Some("1.70"),
+            json!([
+                dependency("rand", "0.7", &[], None),
+                dependency("log", "*", &[], None),
+                dependency("regex", ">=1.0", &[], None),
+                dependency("serde", "1.0", &["derive"], None),
+                dependency("tokio", "1", &[], Some("rt")),
+            ]),
+        );
+        let cli = member(
+            "cli",
+            "2021",
+            Some("1.70"),
+            json!([
+                dependency("rand", "0.8", &[], None),
+                dependency("log", "1", &[], None),
+                dependency("regex", "2", &[], None),
+                dependency("serde", "1.0.150", &["derive"], None),
+                dependency("tokio", "1.30", &[], None),
+            ]),
+        );
+
+        let output = check_consistency(&[&app, &cli], &WorkspaceDefaults::default());
+
+        let kinds: Vec<(ConsistencyKind, &str)> = output
+            .findings
+            .iter()
+            .map(|finding| (finding.kind, finding.name.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ConsistencyKind::IncompatibleRequirements, "rand"),
+                (ConsistencyKind::Rename, "tokio"),
+            ]
+        );
+        let values: Vec<&str> = output.findings[0]
+            .members
+            .iter()
+            .map(|member| member.value.as_str())
+            .collect();
+        assert_eq!(values, vec!["^0.7", "^0.8"]);
+        assert_eq!(output.findings[1].members[0].value, "as rt");
+    }
+
+    #[test]
+    fn compatible_line_ignores_requirements_open_to_later_lines() {
+        let line = |req: &str| compatible_line(&VersionReq::parse(req).unwrap());
+        assert_eq!(line("1.2"), Some((1, 0, 0)));
+        assert_eq!(line("~0.3.1"), Some((0, 3, 0)));
+        assert_eq!(line("=0.0.7"), Some((0, 0, 7)));
+        assert_eq!(line(">=1.0, <2"), Some((1, 0, 0)));
+        assert_eq!(line(">=1.0"), None);
+        assert_eq!(line(">=1.0, <3"), None);
+        assert_eq!(line("*"), None);
+    }
+
+    #[test]
+    fn check_consistency_compares_settings_with_workspace_defaults() {
+        let app = member(
+            "app",
+            "2021",
+            Some("1.70"),
+            json!([dependency("serde", "1", &["derive"], None)]),
+        );
+        let cli = member(
+            "cli",
+            "2018",
+            None,
+            json!([dependency("serde", "1", &[], None)]),
+        );
+        let defaults = WorkspaceDefaults {
+            edition: None,
+            rust_version: Some("1.70.0".to_string()),
+        };
+
+        let output = check_consistency(&[&app, &cli, &app], &defaults);
+
+        let kinds: Vec<(ConsistencyKind, Option<&str>)> = output
+            .findings
+            .iter()
+            .map(|finding| (finding.kind, finding.expected.as_deref()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ConsistencyKind::Features, None),
+                (ConsistencyKind::Edition, Some("2021")),
+                (ConsistencyKind::RustVersion, Some("1.70")),
+            ]
+        );
+        let values = |index: usize| -> Vec<(&str, &str)> {
+            output.findings[index]
+                .members
+                .iter()
+                .map(|member| (member.member.as_str(), member.value.as_str()))
+                .collect()
+        };
+        assert_eq!(values(0), vec![("app", "derive"), ("cli", "(none)")]);
+        assert_eq!(values(1), vec![("cli", "2018")]);
+        assert_eq!(values(2), vec![("cli", "(none)")]);
+    }
+}
@@ -15,6 +15,27 @@ pub struct VersionInfo {
     /// The latest stable version (kept for future prerelease filtering).
     #[allow(dead_code)]
     pub latest_stable: Option<String>,
+    /// Every published version, newest first as returned by crates.io.
+    pub versions: Vec<PublishedVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedVersion {
+    pub num: String,
+    /// The `rust-version` declared by this release, if any.
+    pub rust_version: Option<String>,
+    pub yanked: bool,
+}
+
+/// A dependency declared by a published release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedDependency {
+    /// The crate depended on (the registry name, even when renamed).
+    pub name: String,
+    pub req: String,
+    /// `normal`, `dev`, or `build`.
+    pub kind: String,
+    pub optional: bool,
 }
 
 #[derive(Clone)]
@@ -101,6 +122,60 @@ impl CratesIoClient {
         Ok(results)
     }
 
+    /// Fetches the dependencies declared by `name` at `version`.
+    pub async fn fetch_dependencies(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<Vec<PublishedDependency>> {
+        let _permit = self.limiter.acquire().await.map_err(|err| {
+            UpkeepError::context(
+                ErrorCode::Concurrency,
+                "rate limiter semaphore closed unexpectedly (this is a bug, please report it)",
+                err,
+            )
+        })?;
+        sleep(self.rate_limit_delay).await;
+
+        let url = format!("{}/crates/{name}/{version}/dependencies", self.base_url);
+        let response = self.http.get(&url).send().await.map_err(|err| {
+            UpkeepError::context(
+                ErrorCode::Http,
+                format!("failed to fetch dependencies from {url}"),
+                err,
+            )
+        })?;
+        let payload: CratesIoDependenciesResponse = response
+            .error_for_status()
+            .map_err(|err| {
+                UpkeepError::context(
+                    ErrorCode::Http,
+                    format!("HTTP error fetching dependencies of {name} {version}"),
+                    err,
+                )
+            })?
+            .json()
+            .await
+            .map_err(|err| {
+                UpkeepError::context(
+                    ErrorCode::Json,
+                    format!("failed to parse dependencies of {name} {version}"),
+                    err,
+                )
+            })?;
+
+        Ok(payload
+            .dependencies
+            .into_iter()
+            .map(|dep| PublishedDependency {
+                name: dep.crate_id,
+                req: dep.req,
+                kind: dep.kind.unwrap_or_else(|| "normal".to_string()),
+                optional: dep.optional,
+            })
+            .collect())
+    }
+
     /// Internal helper that fetches from API. Caller must hold the semaphore permit.
     async fn fetch_from_api_inner(
         &self,
@@ -158,6 +233,15 @@ impl CratesIoClient {
             name: name.to_string(),
             latest: selected,
             latest_stable: max_stable_version,
+            versions: payload
+                .versions
+                .into_iter()
+                .map(|version| PublishedVersion {
+                    num: version.num,
+                    rust_version: version.rust_version,
+                    yanked: version.yanked,
+                })
+                .collect(),
         })
     }
 }
@@ -166,6 +250,8 @@ impl CratesIoClient {
 struct CratesIoResponse {
     #[serde(rename = "crate")]
     krate: CratesIoCrate,
+    #[serde(default)]
+    versions: Vec<CratesIoVersion>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -174,6 +260,30 @@ struct CratesIoCrate {
     max_stable_version: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CratesIoVersion {
+    num: String,
+    #[serde(default)]
+    rust_version: Option<String>,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoDependenciesResponse {
+    dependencies: Vec<CratesIoDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoDependency {
+    crate_id: String,
+    req: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    optional: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,6 +395,69 @@ mod tests {
         mock.assert_calls(1);
     }
 
+    #[tokio::test]
+    async fn fetch_latest_versions_parses_published_versions() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/crates/home");
+            then.status(200).json_body(json!({
+                "crate": {
+                    "max_version": "0.5.11",
+                    "max_stable_version": "0.5.11"
+                },
+                "versions": [
+                    { "num": "0.5.11", "rust_version": "1.81", "yanked": false },
+                    { "num": "0.5.10", "rust_version": null, "yanked": true },
+                    { "num": "0.5.9" }
+                ]
+            }));
+        });
+
+        let client = test_client(server.url(""));
+        let result = client
+            .fetch_latest_versions(&["home".to_string()], false)
+            .await
+            .expect("fetch");
+
+        let versions = &result.get("home").expect("home info").versions;
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].rust_version.as_deref(), Some("1.81"));
+        assert!(versions[1].yanked);
+        assert_eq!(versions[2].num, "0.5.9");
+        assert!(!versions[2].yanked);
+        mock.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn fetch_dependencies_parses_release_dependencies() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/crates/darling/0.20.10/dependencies");
+            then.status(200).json_body(json!({
+                "dependencies": [
+                    { "crate_id": "syn", "req": "^2.0.15", "kind": "normal", "optional": false },
+                    { "crate_id": "proc-macro2", "req": "^1", "kind": "dev", "optional": true },
+                    { "crate_id": "quote", "req": "^1" }
+                ]
+            }));
+        });
+
+        let client = test_client(server.url(""));
+        let deps = client
+            .fetch_dependencies("darling", "0.20.10")
+            .await
+            .expect("fetch");
+
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].name, "syn");
+        assert_eq!(deps[0].req, "^2.0.15");
+        assert_eq!(deps[1].kind, "dev");
+        assert!(deps[1].optional);
+        assert_eq!(deps[2].kind, "normal");
+        mock.assert_calls(1);
+    }
+
     #[tokio::test]
     async fn fetch_latest_versions_uses_cache() {
         let server = MockServer::start();
@@ -0,0 +1,244 @@
+//! Crates resolved at more than one version and the parents that keep the
+//! versions apart.
+
+use cargo_metadata::{Package, PackageId};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::core::graph::{Edge, ResolveGraph};
+
+/// A crate name resolved at several versions.
+pub struct DuplicateGroup<'a> {
+    pub name: String,
+    /// Versions in ascending order; the last one is the unification target.
+    pub versions: Vec<VersionUse<'a>>,
+    /// Crates that are only built because of the older versions, including
+    /// the older versions themselves.
+    pub impact: usize,
+}
+
+impl<'a> DuplicateGroup<'a> {
+    pub fn newest(&self) -> &'a Package {
+        self.versions
+            .last()
+            .expect("duplicate groups have at least two versions")
+            .package
+    }
+}
+
+pub struct VersionUse<'a> {
+    pub package: &'a Package,
+    pub parents: Vec<ParentRequirement<'a>>,
+}
+
+pub struct ParentRequirement<'a> {
+    pub parent: &'a Package,
+    /// The version requirement `parent` declares, when it can be matched.
+    pub req: Option<String>,
+}
+
+/// Finds every crate with more than one version reachable from `roots`,
+/// ranked by impact and then name.
+pub fn find_duplicates<'a>(
+    graph: &ResolveGraph<'a>,
+    roots: &[&PackageId],
+) -> Vec<DuplicateGroup<'a>> {
+    let reachable = graph.reachable_from_roots(roots, |_, _| false);
+    let dependents = graph.inverted();
+
+    let mut by_name: BTreeMap<&str, Vec<&'a Package>> = BTreeMap::new();
+    for id in &reachable {
+        if let Some(package) = graph.package(id) {
+            by_name
+                .entry(package.name.as_str())
+                .or_default()
+                .push(package);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup<'a>> = by_name
+        .into_iter()
+        .filter(|(_, packages)| packages.len() > 1)
+        .map(|(name, mut packages)| {
+            packages.sort_by(|left, right| left.version.cmp(&right.version));
+            let older: HashSet<&PackageId> = packages[..packages.len() - 1]
+                .iter()
+                .map(|package| &package.id)
+                .collect();
+            let without_older =
+                graph.reachable_from_roots(roots, |_, edge| older.contains(&edge.id));
+            let impact = reachable.difference(&without_older).count();
+
+            let versions = packages
+                .into_iter()
+                .map(|package| VersionUse {
+                    package,
+                    parents: parents_of(graph, &dependents, &reachable, package),
+                })
+                .collect();
+            DuplicateGroup {
+                name: name.to_string(),
+                versions,
+                impact,
+            }
+        })
+        .collect();
+
+    groups.sort_by(|left, right| {
+        right
+            .impact
+            .cmp(&left.impact)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    groups
+}
+
+fn parents_of<'a>(
+    graph: &ResolveGraph<'a>,
+    dependents: &HashMap<PackageId, Vec<Edge>>,
+    reachable: &HashSet<PackageId>,
+    package: &Package,
+) -> Vec<ParentRequirement<'a>> {
+    let mut parents: Vec<ParentRequirement<'a>> = dependents
+        .get(&package.id)
+        .into_iter()
+        .flatten()
+        .filter(|edge| reachable.contains(&edge.id))
+        .filter_map(|edge| graph.package(&edge.id))
+        .map(|parent| ParentRequirement {
+            parent,
+            req: parent
+                .dependencies
+                .iter()
+                .find(|dep| dep.name == package.name.as_str() && dep.req.matches(&package.version))
+                .map(|dep| dep.req.to_string()),
+        })
+        .collect();
+    parents.sort_by(|left, right| {
+        left.parent
+            .name
+            .cmp(&right.parent.name)
+            .then_with(|| left.parent.version.cmp(&right.parent.version))
+    });
+    parents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::platform::TargetFilter;
+    use cargo_metadata::Metadata;
+    use serde_json::json;
+
+    fn package(name: &str, version: &str, deps: &[(&str, &str)]) -> serde_json::Value {
+        let dependencies: Vec<serde_json::Value> = deps
+            .iter()
+            .map(|(name, req)| {
+                json!({
+                    "name": name,
+                    "source": null,
+                    "req": req,
+                    "kind": null,
+                    "rename": null,
+                    "optional": false,
+                    "uses_default_features": true,
+                    "features": [],
+                    "target": null,
+                })
+            })
+            .collect();
+        json!({
+            "name": name,
+            "version": version,
+            "id": format!("{name} {version}"),
+            "source": null,
+            "dependencies": dependencies,
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}-{version}/Cargo.toml"),
+            "edition": "2021",
+        })
+    }
+
+    fn node(id: &str, deps: &[&str]) -> serde_json::Value {
+        let node_deps: Vec<serde_json::Value> = deps
+            .iter()
+            .map(|dep| {
+                json!({
+                    "name": dep.split(' ').next().unwrap(),
+                    "pkg": dep,
+                    "dep_kinds": [{ "kind": null, "target": null }],
+                })
+            })
+            .collect();
+        json!({ "id": id, "deps": node_deps, "dependencies": deps, "features": [] })
+    }
+
+    /// app -> old_parent -> syn 1 -> unicode 1
+    /// app -> new_parent -> syn 2
+    /// app -> syn 2
+    fn metadata() -> Metadata {
+        serde_json::from_value(json!({
+            "packages": [
+                package("app", "0.1.0", &[("old_parent", "^1"), ("new_parent", "^1"), ("syn", "^2")]),
+                package("old_parent", "1.0.0", &[("syn", "^1")]),
+                package("new_parent", "1.0.0", &[("syn", "^2.0")]),
+                package("syn", "1.0.109", &[("unicode", "^1")]),
+                package("syn", "2.0.50", &[]),
+                package("unicode", "1.0.0", &[]),
+            ],
+            "workspace_members": ["app 0.1.0"],
+            "resolve": {
+                "root": "app 0.1.0",
+                "nodes": [
+                    node("app 0.1.0", &["old_parent 1.0.0", "new_parent 1.0.0", "syn 2.0.50"]),
+                    node("old_parent 1.0.0", &["syn 1.0.109"]),
+                    node("new_parent 1.0.0", &["syn 2.0.50"]),
+                    node("syn 1.0.109", &["unicode 1.0.0"]),
+                    node("syn 2.0.50", &[]),
+                    node("unicode 1.0.0", &[]),
+                ],
+            },
+            "target_directory": "/tmp/target",
+            "version": 1,
+            "workspace_root": "/tmp",
+        }))
+        .expect("metadata")
+    }
+
+    #[test]
+    fn find_duplicates_lists_parents_and_requirements() {
+        let metadata = metadata();
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        let roots: Vec<&PackageId> = metadata.workspace_members.iter().collect();
+
+        let groups = find_duplicates(&graph, &roots);
+
+        assert_eq!(groups.len(), 1);
+        let syn = &groups[0];
+        assert_eq!(syn.name, "syn");
+        assert_eq!(syn.newest().version.to_string(), "2.0.50");
+        let old = &syn.versions[0];
+        assert_eq!(old.package.version.to_string(), "1.0.109");
+        assert_eq!(old.parents.len(), 1);
+        assert_eq!(old.parents[0].parent.name.as_str(), "old_parent");
+        assert_eq!(old.parents[0].req.as_deref(), Some("^1"));
+        let new_parents: Vec<&str> = syn.versions[1]
+            .parents
+            .iter()
+            .map(|parent| parent.parent.name.as_str())
+            .collect();
+        assert_eq!(new_parents, vec!["app", "new_parent"]);
+    }
+
+    #[test]
+    fn impact_counts_crates_only_pulled_in_by_older_versions() {
+        let metadata = metadata();
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        let roots: Vec<&PackageId> = metadata.workspace_members.iter().collect();
+
+        let groups = find_duplicates(&graph, &roots);
+
+        // syn 1.0.109 and unicode disappear once old_parent moves to syn 2.
+        assert_eq!(groups[0].impact, 2);
+    }
+}
@@ -95,12 +95,14 @@ pub fn handle_tool_output(
 
 /// Checks if stderr indicates a missing cargo subcommand.
 ///
-/// This detects patterns like "no such subcommand" or "unknown subcommand"
-/// combined with the tool name.
+/// This detects patterns like "no such subcommand", "no such command" (newer
+/// cargo) or "unknown subcommand" combined with the tool name.
 pub fn is_missing_subcommand(stderr: &str, tool_name: &str) -> bool {
     let lower = stderr.to_lowercase();
     let tool_name_lower = tool_name.to_lowercase();
-    (lower.contains("no such subcommand") || lower.contains("unknown subcommand"))
+    (lower.contains("no such subcommand")
+        || lower.contains("no such command")
+        || lower.contains("unknown subcommand"))
         && lower.contains(&tool_name_lower)
 }
 
@@ -155,6 +157,11 @@ mod tests {
             "error: NO SUCH SUBCOMMAND: `GEIGER`",
             "geiger"
         ));
+        // Wording used by newer cargo releases
+        assert!(is_missing_subcommand(
+            "error: no such command: `semver-checks`",
+            "semver-checks"
+        ));
 
         // Negative cases - should not detect
         assert!(!is_missing_subcommand("some other error", "geiger"));
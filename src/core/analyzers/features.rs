@@ -0,0 +1,515 @@
+//! Feature hygiene for workspace members, and a `cargo check` run over the
+//! feature powerset.
+
+use cargo_metadata::{Dependency, DependencyKind, Metadata, Package, TargetKind};
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::core::analyzers::msrv::first_error_line;
+use crate::core::analyzers::util::CARGO_CHECK_TIMEOUT;
+use crate::core::error::Result;
+use crate::core::manifest::declared_features;
+use crate::core::output::{DefaultFeaturesHint, FeatureCheck, InvalidFeatureEntry, MemberFeatures};
+use crate::core::walk::visit_files;
+
+/// Checks the features of every workspace member, or only of `package`.
+pub fn analyze_features(metadata: &Metadata, package: Option<&str>) -> Result<Vec<MemberFeatures>> {
+    metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|member| package.map_or(true, |name| member.name.as_str() == name))
+        .map(|member| {
+            let referenced = member
+                .manifest_path
+                .parent()
+                .map(|dir| referenced_features(dir.as_std_path()))
+                .unwrap_or_default();
+            let declared = declared_features(member.manifest_path.as_std_path())?;
+            Ok(check_member(member, &declared, &referenced, |dep| {
+                resolved_package(metadata, dep)
+            }))
+        })
+        .collect()
+}
+
+/// Checks `member`, whose manifest declares the `declared` features; the
+/// features in its metadata also include the implicit ones.
+pub fn check_member<'a>(
+    member: &Package,
+    declared: &BTreeMap<String, Vec<String>>,
+    referenced: &HashSet<String>,
+    resolve: impl Fn(&Dependency) -> Option<&'a Package>,
+) -> MemberFeatures {
+    // A crate can be both an optional dependency and a dev-dependency; the
+    // optional declaration is the one features refer to.
+    let mut dependencies: BTreeMap<&str, &Dependency> = BTreeMap::new();
+    for dep in &member.dependencies {
+        let existing = dependencies.entry(dependency_key(dep)).or_insert(dep);
+        if dep.optional {
+            *existing = dep;
+        }
+    }
+
+    let mut invalid = Vec::new();
+    for (feature, entries) in &member.features {
+        for entry in entries {
+            if let Some(reason) = invalid_entry(member, &dependencies, entry, &resolve) {
+                invalid.push(InvalidFeatureEntry {
+                    feature: feature.clone(),
+                    entry: entry.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    let mut effective = HashSet::new();
+    let unreferenced = member
+        .features
+        .keys()
+        .filter(|feature| *feature != "default" && declared.contains_key(*feature))
+        .filter(|feature| !has_effect(member, feature, referenced, &mut effective, &mut Vec::new()))
+        .cloned()
+        .collect();
+
+    // The implicit feature of an optional dependency is not in the manifest,
+    // so any declared feature that turns it on gates it.
+    let ungated_optional = dependencies
+        .iter()
+        .filter(|(_, dep)| dep.optional)
+        .filter(|(key, _)| {
+            !declared
+                .values()
+                .flatten()
+                .any(|entry| enables_dependency(entry, key))
+        })
+        .map(|(key, _)| key.to_string())
+        .collect();
+
+    let is_library = member
+        .targets
+        .iter()
+        .any(|target| target.kind.contains(&TargetKind::Lib));
+    let default_features = if is_library {
+        dependencies
+            .values()
+            .filter(|dep| dep.kind == DependencyKind::Normal && dep.uses_default_features)
+            .filter_map(|dep| {
+                let key = dependency_key(dep);
+                let defaults = resolve(dep)?.features.get("default")?;
+                let forwarded = forwarded_features(member, key);
+                let needed = defaults.iter().any(|feature| {
+                    !dep.features.contains(feature) && !forwarded.contains(feature.as_str())
+                });
+                (!defaults.is_empty() && !needed).then(|| DefaultFeaturesHint {
+                    dependency: key.to_string(),
+                    defaults: defaults.clone(),
+                })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    MemberFeatures {
+        name: member.name.to_string(),
+        features: member
+            .features
+            .keys()
+            .filter(|feature| declared.contains_key(*feature))
+            .cloned()
+            .collect(),
+        unreferenced,
+        ungated_optional,
+        invalid,
+        default_features,
+        powerset: Vec::new(),
+    }
+}
+
+/// The name a dependency goes by in `[features]`.
+fn dependency_key(dep: &Dependency) -> &str {
+    dep.rename.as_deref().unwrap_or(&dep.name)
+}
+
+/// Features of the dependency `key` that `member`'s own features turn on
+/// with `key/feature` or `key?/feature`.
+fn forwarded_features<'a>(member: &'a Package, key: &str) -> HashSet<&'a str> {
+    member
+        .features
+        .values()
+        .flatten()
+        .filter_map(|entry| {
+            let (dep, feature) = entry.split_once('/')?;
+            (dep.strip_suffix('?').unwrap_or(dep) == key).then_some(feature)
+        })
+        .collect()
+}
+
+/// Whether `entry` turns on the dependency `key`.
+fn enables_dependency(entry: &str, key: &str) -> bool {
+    entry == key
+        || entry.strip_prefix("dep:") == Some(key)
+        || entry.split_once('/').is_some_and(|(dep, _)| dep == key)
+}
+
+/// A feature has an effect when code checks it or it turns on a dependency,
+/// directly or through the features it enables.
+fn has_effect(
+    member: &Package,
+    feature: &str,
+    referenced: &HashSet<String>,
+    effective: &mut HashSet<String>,
+    visiting: &mut Vec<String>,
+) -> bool {
+    if effective.contains(feature) || referenced.contains(feature) {
+        return true;
+    }
+    if visiting.iter().any(|seen| seen == feature) {
+        return false;
+    }
+    let Some(entries) = member.features.get(feature) else {
+        // An optional dependency named directly.
+        return member
+            .dependencies
+            .iter()
+            .any(|dep| dep.optional && dependency_key(dep) == feature);
+    };
+    visiting.push(feature.to_string());
+    let result = entries.iter().any(|entry| {
+        entry.starts_with("dep:")
+            || entry.contains('/')
+            || has_effect(member, entry, referenced, effective, visiting)
+    });
+    visiting.pop();
+    if result {
+        effective.insert(feature.to_string());
+    }
+    result
+}
+
+fn invalid_entry<'a>(
+    member: &Package,
+    dependencies: &BTreeMap<&str, &Dependency>,
+    entry: &str,
+    resolve: &impl Fn(&Dependency) -> Option<&'a Package>,
+) -> Option<String> {
+    if let Some(key) = entry.strip_prefix("dep:") {
+        return match dependencies.get(key) {
+            Some(dep) if dep.optional => None,
+            Some(_) => Some(format!("{key} is not an optional dependency")),
+            None => Some(format!("no dependency named {key}")),
+        };
+    }
+    if let Some((key, feature)) = entry.split_once('/') {
+        let (key, weak) = match key.strip_suffix('?') {
+            Some(key) => (key, true),
+            None => (key, false),
+        };
+        let Some(dep) = dependencies.get(key) else {
+            return Some(format!("no dependency named {key}"));
+        };
+        if weak && !dep.optional {
+            return Some(format!("{key}? used but {key} is not optional"));
+        }
+        let package = resolve(dep)?;
+        return (!package.features.contains_key(feature)).then(|| {
+            format!(
+                "{} {} has no feature {feature}",
+                package.name, package.version
+            )
+        });
+    }
+    let exists = member.features.contains_key(entry)
+        || dependencies.get(entry).is_some_and(|dep| dep.optional);
+    (!exists).then(|| format!("no feature or optional dependency named {entry}"))
+}
+
+fn resolved_package<'a>(metadata: &'a Metadata, dep: &Dependency) -> Option<&'a Package> {
+    metadata
+        .packages
+        .iter()
+        .find(|package| package.name.as_str() == dep.name && dep.req.matches(&package.version))
+}
+
+/// Feature names checked in the `.rs` files under `dir`, through
+/// `feature = "..."` in `cfg` attributes and macros or `CARGO_FEATURE_*` in
+/// build scripts. Nested packages and the member's own `target` directory are
+/// skipped.
+pub fn referenced_features(dir: &Path) -> HashSet<String> {
+    let target = dir.join("target");
+    let mut sources = String::new();
+    visit_files(
+        dir,
+        &|path| path != target && !path.join("Cargo.toml").exists(),
+        &mut |path, _| {
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                if let Ok(contents) = fs::read_to_string(path) {
+                    sources.push_str(&contents);
+                    sources.push('\n');
+                }
+            }
+        },
+    );
+    scan_features(&sources)
+}
+
+fn scan_features(source: &str) -> HashSet<String> {
+    let cfg = Regex::new(r#"feature\s*=\s*"([^"]+)""#).expect("valid regex");
+    let env = Regex::new(r"CARGO_FEATURE_([A-Z0-9_]+)").expect("valid regex");
+    let mut features: HashSet<String> = cfg
+        .captures_iter(source)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    // Build scripts see `foo-bar` as `CARGO_FEATURE_FOO_BAR`; keep both
+    // spellings so either feature name matches.
+    for captures in env.captures_iter(source) {
+        let name = captures[1].to_ascii_lowercase();
+        features.insert(name.replace('_', "-"));
+        features.insert(name);
+    }
+    features
+}
+
+/// Every combination of up to `depth` features, smallest first.
+pub fn powerset(features: &[String], depth: usize) -> Vec<Vec<String>> {
+    let mut combinations = vec![Vec::new()];
+    let mut current: Vec<Vec<String>> = vec![Vec::new()];
+    for _ in 0..depth.min(features.len()) {
+        let mut next = Vec::new();
+        for combination in &current {
+            let start = combination
+                .last()
+                .and_then(|last| features.iter().position(|feature| feature == last))
+                .map_or(0, |index| index + 1);
+            for feature in &features[start..] {
+                let mut extended = combination.clone();
+                extended.push(feature.clone());
+                next.push(extended);
+            }
+        }
+        combinations.extend(next.iter().cloned());
+        current = next;
+    }
+    combinations
+}
+
+/// The feature combinations [`check_powerset`] builds `member` with: every
+/// combination of up to `depth` of its features, `default` excluded.
+pub fn member_powerset(member: &MemberFeatures, depth: usize) -> Vec<Vec<String>> {
+    let features: Vec<String> = member
+        .features
+        .iter()
+        .filter(|feature| *feature != "default")
+        .cloned()
+        .collect();
+    powerset(&features, depth)
+}
+
+/// Runs `cargo check --no-default-features` for `member` with each of
+/// `combinations`, one after another. A check that outlasts
+/// [`CARGO_CHECK_TIMEOUT`] is abandoned and counts as failed.
+pub async fn check_powerset(
+    workspace_root: &Path,
+    member: &str,
+    combinations: Vec<Vec<String>>,
+) -> Vec<FeatureCheck> {
+    let mut checks = Vec::new();
+    for combination in combinations {
+        let mut command = Command::new("cargo");
+        command
+            .args(["check", "--quiet", "-p", member, "--no-default-features"])
+            .current_dir(workspace_root)
+            .kill_on_drop(true);
+        if !combination.is_empty() {
+            command.args(["--features", &combination.join(",")]);
+        }
+        let (passed, message) = match timeout(CARGO_CHECK_TIMEOUT, command.output()).await {
+            Ok(Ok(output)) if output.status.success() => (true, None),
+            Ok(Ok(output)) => (
+                false,
+                Some(first_error_line(&String::from_utf8_lossy(&output.stderr))),
+            ),
+            Ok(Err(err)) => (false, Some(format!("failed to execute cargo: {err}"))),
+            Err(_) => (
+                false,
+                Some(format!(
+                    "cargo check timed out after {}s",
+                    CARGO_CHECK_TIMEOUT.as_secs()
+                )),
+            ),
+        };
+        checks.push(FeatureCheck {
+            features: combination,
+            passed,
+            message,
+        });
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dependency(name: &str, optional: bool, default_features: bool) -> serde_json::Value {
+        json!({
+            "name": name,
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "req": "^1",
+            "kind": null,
+            "optional": optional,
+            "uses_default_features": default_features,
+            "features": [],
+            "target": null,
+            "rename": null,
+            "registry": null,
+        })
+    }
+
+    fn package(
+        name: &str,
+        features: serde_json::Value,
+        dependencies: serde_json::Value,
+    ) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": null,
+            "dependencies": dependencies,
+            "targets": [{
+                "name": name,
+                "kind": ["lib"],
+                "crate_types": ["lib"],
+                "src_path": format!("/work/{name}/src/lib.rs"),
+                "edition": "2021",
+                "doctest": true,
+                "test": true,
+            }],
+            "features": features,
+            "manifest_path": format!("/work/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    #[test]
+    fn check_member_reports_feature_problems() {
+        let declared: BTreeMap<String, Vec<String>> = serde_json::from_value(json!({
+            "default": ["std"],
+            "std": ["serde/std"],
+            "unused": [],
+            "full": ["std", "unused"],
+            "json": ["dep:serde_json", "serde/derive", "serde/nope"],
+            "broken": ["missing"],
+            "weak": ["log?/std"],
+            "rand": ["dep:rand"],
+        }))
+        .expect("features");
+        // Metadata adds the implicit `fastrand` feature to the manifest's.
+        let mut features = serde_json::to_value(&declared).expect("features");
+        features["fastrand"] = json!(["dep:fastrand"]);
+        let member = package(
+            "app",
+            features,
+            json!([
+                dependency("serde", false, true),
+                dependency("serde_json", true, true),
+                dependency("log", false, true),
+                dependency("rand", true, true),
+                dependency("fastrand", true, true),
+            ]),
+        );
+        let serde = package(
+            "serde",
+            json!({ "default": ["std"], "std": [], "derive": [] }),
+            json!([]),
+        );
+        let serde_json = package(
+            "serde_json",
+            json!({ "default": ["std"], "std": [] }),
+            json!([]),
+        );
+        let referenced = HashSet::from(["std".to_string()]);
+
+        let report = check_member(&member, &declared, &referenced, |dep| {
+            match dep.name.as_str() {
+                "serde" => Some(&serde),
+                "serde_json" => Some(&serde_json),
+                _ => None,
+            }
+        });
+
+        assert_eq!(report.unreferenced, vec!["broken", "unused"]);
+        assert_eq!(report.ungated_optional, vec!["fastrand"]);
+        let invalid: Vec<(&str, &str)> = report
+            .invalid
+            .iter()
+            .map(|entry| (entry.entry.as_str(), entry.reason.as_str()))
+            .collect();
+        assert_eq!(
+            invalid,
+            vec![
+                ("missing", "no feature or optional dependency named missing"),
+                ("serde/nope", "serde 1.0.0 has no feature nope"),
+                ("log?/std", "log? used but log is not optional"),
+            ]
+        );
+        // serde's `std` is forwarded by the crate's own `std` feature, while
+        // serde_json's default is not enabled any other way.
+        assert_eq!(report.default_features.len(), 1);
+        assert_eq!(report.default_features[0].dependency, "serde");
+        assert!(report.features.contains(&"rand".to_string()));
+        assert!(!report.features.contains(&"fastrand".to_string()));
+    }
+
+    #[test]
+    fn scan_features_reads_cfg_and_build_script_checks() {
+        let features = scan_features(
+            "#[cfg(feature = \"std\")]\nif cfg!(feature=\"fast-io\") {}\nenv::var(\"CARGO_FEATURE_SMALL_VEC\")",
+        );
+        assert!(features.contains("std"));
+        assert!(features.contains("fast-io"));
+        assert!(features.contains("small-vec"));
+        assert!(features.contains("small_vec"));
+    }
+
+    #[test]
+    fn referenced_features_skips_only_the_member_target_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for (path, feature) in [
+            ("src/target/mod.rs", "kept"),
+            ("target/debug/build.rs", "built"),
+            ("nested/Cargo.toml", ""),
+            ("nested/src/lib.rs", "nested"),
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, format!("#[cfg(feature = \"{feature}\")]\n")).unwrap();
+        }
+
+        let features = referenced_features(root);
+        assert!(features.contains("kept"));
+        assert!(!features.contains("built"));
+        assert!(!features.contains("nested"));
+    }
+
+    #[test]
+    fn powerset_combines_up_to_depth() {
+        let features: Vec<String> = ["a", "b", "c"].iter().map(ToString::to_string).collect();
+        let combinations: Vec<String> = powerset(&features, 2)
+            .iter()
+            .map(|combination| combination.join(","))
+            .collect();
+        assert_eq!(combinations, vec!["", "a", "b", "c", "a,b", "a,c", "b,c"]);
+        assert_eq!(powerset(&features, 0).len(), 1);
+        assert_eq!(powerset(&features, 5).len(), 8);
+    }
+}
@@ -0,0 +1,359 @@
+//! Staleness of git dependencies, from the refs `git ls-remote` reports.
+
+use semver::Version;
+use std::collections::HashMap;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::GitStatus;
+use crate::core::source::GitSource;
+
+/// How long a single git command against a remote may run before it is
+/// abandoned, so an unreachable repository cannot hang `deps`.
+pub const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The branches and tags of a remote repository.
+#[derive(Debug, Default)]
+pub struct RemoteRefs {
+    /// Commit of the default branch.
+    pub head: Option<String>,
+    pub branches: HashMap<String, String>,
+    /// Tags and the commits they point at, with annotated tags peeled.
+    pub tags: HashMap<String, String>,
+}
+
+impl RemoteRefs {
+    /// Parses `git ls-remote` output: one `<commit>\t<ref>` per line.
+    pub fn parse(output: &str) -> Self {
+        let mut refs = RemoteRefs::default();
+        let mut peeled = HashMap::new();
+        for line in output.lines() {
+            let Some((commit, name)) = line.split_once('\t') else {
+                continue;
+            };
+            let commit = commit.trim().to_string();
+            if name == "HEAD" {
+                refs.head = Some(commit);
+            } else if let Some(branch) = name.strip_prefix("refs/heads/") {
+                refs.branches.insert(branch.to_string(), commit);
+            } else if let Some(tag) = name.strip_prefix("refs/tags/") {
+                match tag.strip_suffix("^{}") {
+                    Some(tag) => {
+                        peeled.insert(tag.to_string(), commit);
+                    }
+                    None => {
+                        refs.tags.insert(tag.to_string(), commit);
+                    }
+                }
+            }
+        }
+        // An annotated tag's own object is not a commit; use what it points at.
+        refs.tags.extend(peeled);
+        refs
+    }
+
+    /// The tag with the highest version, ignoring tags that carry none.
+    pub fn latest_tag(&self) -> Option<&str> {
+        self.tags
+            .keys()
+            .filter_map(|tag| Some((tag_version(tag)?, tag)))
+            .max()
+            .map(|(_, tag)| tag.as_str())
+    }
+
+    /// A tag pointing at `commit`, preferring the highest version.
+    pub fn tag_for(&self, commit: &str) -> Option<&str> {
+        let mut tags: Vec<&String> = self
+            .tags
+            .iter()
+            .filter(|(_, target)| target.as_str() == commit)
+            .map(|(tag, _)| tag)
+            .collect();
+        tags.sort_by_key(|tag| (tag_version(tag), tag.as_str()));
+        tags.last().map(|tag| tag.as_str())
+    }
+}
+
+/// Reads the version out of tags such as `v1.2.0`, `1.2` or `mycrate-v1.2.0`.
+fn tag_version(tag: &str) -> Option<Version> {
+    let start = tag.find(|ch: char| ch.is_ascii_digit())?;
+    let version = &tag[start..];
+    Version::parse(version).ok().or_else(|| {
+        let parts: Vec<&str> = version.split('.').collect();
+        match parts.as_slice() {
+            [major] => Version::parse(&format!("{major}.0.0")).ok(),
+            [major, minor] => Version::parse(&format!("{major}.{minor}.0")).ok(),
+            _ => None,
+        }
+    })
+}
+
+/// Lists the default branch, branches and tags of `url`.
+pub async fn ls_remote(url: &str) -> Result<RemoteRefs> {
+    let stdout = run_git(
+        &format!("ls-remote {url}"),
+        &[
+            "ls-remote",
+            "--",
+            url,
+            "HEAD",
+            "refs/heads/*",
+            "refs/tags/*",
+        ],
+    )
+    .await?;
+    Ok(RemoteRefs::parse(&stdout))
+}
+
+/// Counts the commits on `branch` of `url` (the default branch when `None`)
+/// that are not reachable from `pinned`.
+///
+/// Only commit objects are fetched, into a scratch repository that is removed
+/// afterwards. Fails when `pinned` is not in the branch's history, for
+/// example after a force push.
+pub async fn commits_behind(url: &str, branch: Option<&str>, pinned: &str) -> Result<usize> {
+    let scratch = tempfile::Builder::new()
+        .prefix("cargo-upkeep-git-")
+        .tempdir()
+        .map_err(|err| {
+            UpkeepError::context(ErrorCode::Io, "failed to create scratch repository", err)
+        })?;
+    let git_dir = scratch.path().to_string_lossy().into_owned();
+    let refspec = branch.map_or_else(
+        || "HEAD".to_string(),
+        |branch| format!("refs/heads/{branch}"),
+    );
+
+    run_git("init", &["init", "--bare", "--quiet", &git_dir]).await?;
+    run_git(
+        &format!("fetch {url}"),
+        &[
+            "--git-dir",
+            &git_dir,
+            "fetch",
+            "--quiet",
+            "--no-tags",
+            "--filter=tree:0",
+            "--",
+            url,
+            &refspec,
+        ],
+    )
+    .await?;
+    let count = run_git(
+        "rev-list",
+        &[
+            "--git-dir",
+            &git_dir,
+            "rev-list",
+            "--count",
+            &format!("{pinned}..FETCH_HEAD"),
+        ],
+    )
+    .await?;
+    count.trim().parse().map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::ExternalCommand,
+            "failed to parse git rev-list output",
+            err,
+        )
+    })
+}
+
+/// Runs git without prompting for credentials, giving up after
+/// [`GIT_TIMEOUT`]. `label` names the operation in errors.
+async fn run_git(label: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true)
+        .output();
+    let output = timeout(GIT_TIMEOUT, output)
+        .await
+        .map_err(|_| {
+            UpkeepError::message(
+                ErrorCode::ExternalCommand,
+                format!("git {label} timed out after {}s", GIT_TIMEOUT.as_secs()),
+            )
+        })?
+        .map_err(|err| {
+            UpkeepError::context(ErrorCode::ExternalCommand, "failed to run git", err)
+        })?;
+    if !output.status.success() {
+        return Err(UpkeepError::message(
+            ErrorCode::ExternalCommand,
+            format!(
+                "git {label} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// How a pinned git dependency compares to its remote.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GitStaleness {
+    pub status: GitStatus,
+    pub latest_commit: Option<String>,
+    pub latest_tag: Option<String>,
+    pub pinned_tag: Option<String>,
+}
+
+/// Branch pins (and the default branch) are behind when the branch moved on;
+/// tag and rev pins are behind when a newer tag exists than the one at the
+/// pinned commit.
+pub fn staleness(source: &GitSource, refs: &RemoteRefs) -> GitStaleness {
+    let latest_commit = match source.branch() {
+        Some(branch) => refs.branches.get(branch).cloned(),
+        None => refs.head.clone(),
+    };
+    let latest_tag = refs.latest_tag().map(ToString::to_string);
+    let pinned_tag = source
+        .commit
+        .as_deref()
+        .and_then(|commit| refs.tag_for(commit))
+        .map(ToString::to_string);
+
+    let status = match (&source.commit, source.follows_branch()) {
+        (None, _) => GitStatus::Unknown,
+        (Some(pinned), true) => match &latest_commit {
+            Some(latest) if latest == pinned => GitStatus::UpToDate,
+            Some(_) => GitStatus::Behind,
+            None => GitStatus::Unknown,
+        },
+        (Some(pinned), false) => match &latest_tag {
+            Some(latest) => {
+                let newer = tag_version(latest) > pinned_tag.as_deref().and_then(tag_version);
+                if refs.tags.get(latest) == Some(pinned) || !newer {
+                    GitStatus::UpToDate
+                } else {
+                    GitStatus::Behind
+                }
+            }
+            None => GitStatus::Unknown,
+        },
+    };
+
+    GitStaleness {
+        status,
+        latest_commit,
+        latest_tag,
+        pinned_tag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    const LS_REMOTE: &str = "\
+1111111111111111111111111111111111111111\tHEAD
+1111111111111111111111111111111111111111\trefs/heads/main
+2222222222222222222222222222222222222222\trefs/heads/next
+3333333333333333333333333333333333333333\trefs/tags/v1.0.0
+4444444444444444444444444444444444444444\trefs/tags/v1.2.0
+5555555555555555555555555555555555555555\trefs/tags/v1.2.0^{}
+6666666666666666666666666666666666666666\trefs/tags/nightly
+";
+
+    fn source(reference: Option<&str>, commit: &str) -> GitSource {
+        GitSource {
+            url: "https://example.com/repo".to_string(),
+            reference: reference.map(ToString::to_string),
+            commit: Some(commit.repeat(40)),
+        }
+    }
+
+    #[test]
+    fn remote_refs_peel_tags_and_order_versions() {
+        let refs = RemoteRefs::parse(LS_REMOTE);
+        assert_eq!(refs.head.as_deref(), Some(&*"1".repeat(40)));
+        assert_eq!(refs.tags["v1.2.0"], "5".repeat(40));
+        assert_eq!(refs.latest_tag(), Some("v1.2.0"));
+        assert_eq!(refs.tag_for(&"3".repeat(40)), Some("v1.0.0"));
+        assert_eq!(tag_version("mycrate-v2.1"), Version::parse("2.1.0").ok());
+    }
+
+    #[test]
+    fn staleness_compares_branches_and_tags() {
+        let refs = RemoteRefs::parse(LS_REMOTE);
+
+        let current = staleness(&source(None, "1"), &refs);
+        assert_eq!(current.status, GitStatus::UpToDate);
+
+        let behind = staleness(&source(Some("branch=next"), "1"), &refs);
+        assert_eq!(behind.status, GitStatus::Behind);
+        assert_eq!(behind.latest_commit, Some("2".repeat(40)));
+
+        let old_tag = staleness(&source(Some("tag=v1.0.0"), "3"), &refs);
+        assert_eq!(old_tag.status, GitStatus::Behind);
+        assert_eq!(old_tag.pinned_tag.as_deref(), Some("v1.0.0"));
+        assert_eq!(old_tag.latest_tag.as_deref(), Some("v1.2.0"));
+
+        let newest = staleness(&source(Some("rev=555"), "5"), &refs);
+        assert_eq!(newest.status, GitStatus::UpToDate);
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .expect("run git");
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn ls_remote_reads_a_local_repository() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo = temp_dir.path();
+        git(repo, &["init", "--quiet", "--initial-branch=main"]);
+        fs::write(repo.join("README"), "one").expect("write");
+        git(repo, &["add", "README"]);
+        git(repo, &["commit", "--quiet", "-m", "one"]);
+        git(repo, &["tag", "-a", "v0.1.0", "-m", "release"]);
+        let tagged = git(repo, &["rev-parse", "HEAD"]);
+
+        let refs = ls_remote(repo.to_str().expect("utf-8 path"))
+            .await
+            .expect("ls-remote");
+
+        assert_eq!(refs.head.as_deref(), Some(tagged.as_str()));
+        assert_eq!(refs.branches["main"], tagged);
+        assert_eq!(refs.tags["v0.1.0"], tagged);
+    }
+
+    #[tokio::test]
+    async fn commits_behind_counts_new_commits_on_the_branch() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let repo = temp_dir.path();
+        git(repo, &["init", "--quiet", "--initial-branch=main"]);
+        fs::write(repo.join("README"), "one").expect("write");
+        git(repo, &["add", "README"]);
+        git(repo, &["commit", "--quiet", "-m", "one"]);
+        let pinned = git(repo, &["rev-parse", "HEAD"]);
+        for message in ["two", "three"] {
+            fs::write(repo.join("README"), message).expect("write");
+            git(repo, &["commit", "--quiet", "-am", message]);
+        }
+        let url = format!("file://{}", repo.display());
+
+        assert_eq!(
+            commits_behind(&url, Some("main"), &pinned)
+                .await
+                .expect("count"),
+            2
+        );
+        assert_eq!(commits_behind(&url, None, &pinned).await.expect("count"), 2);
+        assert!(commits_behind(&url, Some("main"), &"0".repeat(40))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! License compliance of the resolved dependency graph.
+
+use cargo_metadata::{MetadataCommand, Package, PackageId};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::core::config::{LicensePolicy, UpkeepConfig};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{LicenseGroup, LicenseStatus, LicenseViolation, LicensesOutput};
+use crate::core::platform::TargetFilter;
+use crate::core::spdx::{LicenseExpr, LicenseReq};
+
+/// Checks the licenses of every dependency reachable from the workspace
+/// members (or the `-p` package) against the configured policy.
+pub fn run_licenses(
+    include_dev: bool,
+    targets: &TargetFilter,
+    features: &FeatureSelection,
+) -> Result<LicensesOutput> {
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let config = UpkeepConfig::from_metadata(&metadata)?;
+    let graph = ResolveGraph::from_metadata(&metadata, include_dev, targets)?;
+
+    let roots: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let members: HashSet<&PackageId> = metadata.workspace_members.iter().collect();
+    let mut packages: Vec<&Package> = graph
+        .reachable_from_roots(&roots, |_, _| false)
+        .iter()
+        .filter(|id| !members.contains(id))
+        .filter_map(|id| graph.package(id))
+        .collect();
+    packages.sort_by(|left, right| {
+        left.name
+            .cmp(&right.name)
+            .then_with(|| left.version.cmp(&right.version))
+    });
+
+    Ok(check_licenses(&packages, &config.licenses))
+}
+
+pub fn check_licenses(packages: &[&Package], policy: &LicensePolicy) -> LicensesOutput {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut violations = Vec::new();
+    for package in packages {
+        let label = format!("{} {}", package.name, package.version);
+        let (group, violation) = evaluate(package, policy);
+        groups.entry(group).or_default().push(label);
+        violations.extend(violation);
+    }
+
+    let mut groups: Vec<LicenseGroup> = groups
+        .into_iter()
+        .map(|(license, crates)| LicenseGroup { license, crates })
+        .collect();
+    groups.sort_by_key(|group| Reverse(group.crates.len()));
+
+    LicensesOutput {
+        policy: policy.is_configured(),
+        groups,
+        violations,
+    }
+}
+
+/// Returns the group a package belongs to and, when it breaks the policy, why.
+fn evaluate(package: &Package, policy: &LicensePolicy) -> (String, Option<LicenseViolation>) {
+    let violation = |status, reason: String| LicenseViolation {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        license: package.license.clone(),
+        status,
+        reason,
+    };
+    let exceptions: Vec<&str> = policy
+        .exceptions
+        .iter()
+        .filter(|exception| exception.name == package.name.as_str())
+        .flat_map(|exception| exception.allow.iter().map(String::as_str))
+        .collect();
+
+    let Some(license) = package.license.as_deref() else {
+        return match &package.license_file {
+            // A license file cannot be checked against an allow list; an
+            // exception for the crate records that it was reviewed.
+            Some(file) => {
+                let unchecked = !policy.allow.is_empty() && exceptions.is_empty();
+                (
+                    "custom (license-file)".to_string(),
+                    unchecked.then(|| {
+                        violation(
+                            LicenseStatus::Custom,
+                            format!("license-file {file} needs review"),
+                        )
+                    }),
+                )
+            }
+            None => (
+                "unlicensed".to_string(),
+                Some(violation(
+                    LicenseStatus::Unlicensed,
+                    "no license or license-file".to_string(),
+                )),
+            ),
+        };
+    };
+
+    let expression = match LicenseExpr::parse(license) {
+        Ok(expression) => expression,
+        Err(err) => {
+            return (
+                license.to_string(),
+                Some(violation(LicenseStatus::Invalid, err.to_string())),
+            )
+        }
+    };
+    let accepts = |license: &LicenseReq| {
+        listed(&exceptions, license)
+            || (!listed(&policy.deny, license)
+                && (policy.allow.is_empty() || listed(&policy.allow, license)))
+    };
+    if expression.is_satisfied_by(&accepts) {
+        return (expression.to_string(), None);
+    }
+
+    let mut reasons = Vec::new();
+    for license in expression.licenses() {
+        if accepts(license) {
+            continue;
+        }
+        let reason = if listed(&policy.deny, license) {
+            format!("{license} is denied")
+        } else {
+            format!("{license} is not allowed")
+        };
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+    (
+        expression.to_string(),
+        Some(violation(LicenseStatus::Denied, reasons.join(", "))),
+    )
+}
+
+/// Whether a policy entry names `license`, either exactly (`GPL-2.0+`,
+/// `Apache-2.0 WITH LLVM-exception`) or by its bare identifier.
+fn listed(entries: &[impl AsRef<str>], license: &LicenseReq) -> bool {
+    let full = license.to_string();
+    entries.iter().any(|entry| {
+        let entry = entry.as_ref();
+        entry.eq_ignore_ascii_case(&license.id) || entry.eq_ignore_ascii_case(&full)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::LicenseException;
+    use serde_json::json;
+
+    fn package(name: &str, license: Option<&str>, license_file: Option<&str>) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "license": license,
+            "license_file": license_file,
+            "source": null,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn check_licenses_groups_crates_and_applies_policy() {
+        let packages = [
+            package("serde", Some("MIT OR Apache-2.0"), None),
+            package("serde_json", Some("MIT/Apache-2.0"), None),
+            package("copyleft", Some("GPL-3.0-only"), None),
+            package("weak", Some("MIT AND MPL-2.0"), None),
+            package("ring", None, Some("LICENSE")),
+            package("bare", None, None),
+            package("broken", Some("MIT OR"), None),
+        ];
+        let refs: Vec<&Package> = packages.iter().collect();
+        let policy = LicensePolicy {
+            allow: strings(&["MIT", "Apache-2.0"]),
+            deny: strings(&["GPL-3.0-only"]),
+            exceptions: Vec::new(),
+        };
+
+        let output = check_licenses(&refs, &policy);
+
+        assert!(output.policy);
+        assert_eq!(output.groups[0].license, "MIT OR Apache-2.0");
+        assert_eq!(
+            output.groups[0].crates,
+            vec!["serde 1.0.0", "serde_json 1.0.0"]
+        );
+
+        let statuses: Vec<(&str, LicenseStatus)> = output
+            .violations
+            .iter()
+            .map(|violation| (violation.name.as_str(), violation.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("copyleft", LicenseStatus::Denied),
+                ("weak", LicenseStatus::Denied),
+                ("ring", LicenseStatus::Custom),
+                ("bare", LicenseStatus::Unlicensed),
+                ("broken", LicenseStatus::Invalid),
+            ]
+        );
+        assert_eq!(output.violations[0].reason, "GPL-3.0-only is denied");
+        assert_eq!(output.violations[1].reason, "MPL-2.0 is not allowed");
+    }
+
+    #[test]
+    fn exceptions_accept_licenses_for_one_crate() {
+        let packages = [
+            package("ring", None, Some("LICENSE")),
+            package("webpki", Some("ISC"), None),
+            package("other", Some("ISC"), None),
+        ];
+        let refs: Vec<&Package> = packages.iter().collect();
+        let policy = LicensePolicy {
+            allow: strings(&["MIT"]),
+            deny: Vec::new(),
+            exceptions: vec![
+                LicenseException {
+                    name: "ring".to_string(),
+                    allow: strings(&["OpenSSL"]),
+                },
+                LicenseException {
+                    name: "webpki".to_string(),
+                    allow: strings(&["ISC"]),
+                },
+            ],
+        };
+
+        let output = check_licenses(&refs, &policy);
+
+        assert_eq!(output.violations.len(), 1);
+        assert_eq!(output.violations[0].name, "other");
+    }
+
+    #[test]
+    fn check_licenses_without_policy_only_flags_missing_and_invalid() {
+        let packages = [
+            package("copyleft", Some("GPL-3.0-only"), None),
+            package("ring", None, Some("LICENSE")),
+            package("bare", None, None),
+        ];
+        let refs: Vec<&Package> = packages.iter().collect();
+
+        let output = check_licenses(&refs, &LicensePolicy::default());
+
+        assert!(!output.policy);
+        assert_eq!(output.violations.len(), 1);
+        assert_eq!(output.violations[0].status, LicenseStatus::Unlicensed);
+    }
+}
@@ -1,9 +1,20 @@
 //! Analyzer implementations for project inspection.
 
 pub mod audit;
+pub mod build_time;
 pub mod clippy;
+pub mod consistency;
 pub mod crates_io;
+pub mod duplicates;
 pub mod external_tool;
+pub mod features;
+pub mod git_deps;
+pub mod licenses;
+pub mod msrv;
+pub mod patches;
+pub mod semver_checks;
+pub mod sources;
 pub mod unsafe_code;
 pub mod unused;
 pub mod util;
+pub mod weight;
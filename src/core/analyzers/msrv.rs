@@ -0,0 +1,588 @@
+//! MSRV verification against the resolved dependency graph.
+//!
+//! A declared `rust-version` is only meaningful if every dependency that is
+//! compiled for the crate supports it. This analyzer compares each workspace
+//! member's declared MSRV with the `rust-version` of everything it pulls in
+//! through normal and build dependencies, and can optionally confirm the result
+//! by running `cargo check` under the declared toolchain via rustup.
+//!
+//! It also computes the effective MSRV, the highest `rust-version` across the
+//! dependency graph, and which downgrade of the crate forcing it would lower it.
+//!
+//! Releases that declare no `rust-version` are handled according to the
+//! question being asked. When picking an upgrade that fits an MSRV
+//! ([`latest_compatible_release`]) they count as compatible, matching cargo's
+//! MSRV-aware resolver so the advice agrees with what `cargo update` picks.
+//! When suggesting a downgrade that lowers the effective MSRV
+//! ([`suggest_downgrade`]) they are skipped, because the suggestion has to name
+//! the `rust-version` it leads to and an undeclared release gives none.
+
+use cargo_metadata::{Metadata, MetadataCommand, Package};
+use semver::{Version, VersionReq};
+use std::io;
+use std::path::Path;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::core::analyzers::crates_io::PublishedVersion;
+use crate::core::analyzers::util::CARGO_CHECK_TIMEOUT;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{
+    MsrvDependency, MsrvDowngrade, MsrvMember, MsrvOutput, MsrvState, ToolchainCheck,
+    ToolchainCheckStatus,
+};
+use crate::core::platform::TargetFilter;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsrvOptions {
+    /// Run `cargo check` under the declared toolchain when rustup has it installed.
+    pub verify_toolchain: bool,
+}
+
+pub async fn run_msrv(options: MsrvOptions) -> Result<MsrvOutput> {
+    let metadata = MetadataCommand::new().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+
+    let mut members = check_members(&metadata)?;
+
+    if options.verify_toolchain {
+        let workspace_root = metadata.workspace_root.as_std_path();
+        for member in &mut members {
+            if let Some(declared) = member.declared.clone() {
+                member.toolchain =
+                    Some(verify_with_toolchain(workspace_root, &member.name, &declared).await);
+            }
+        }
+    }
+
+    Ok(MsrvOutput { members })
+}
+
+/// Checks every workspace member's declared MSRV against its dependencies.
+pub fn check_members(metadata: &Metadata) -> Result<Vec<MsrvMember>> {
+    let graph = ResolveGraph::from_metadata(metadata, false, &TargetFilter::all())?;
+
+    let mut members = Vec::new();
+    for member_id in &metadata.workspace_members {
+        let package = graph.package(member_id).ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("workspace member {member_id} missing from packages"),
+            )
+        })?;
+
+        members.push(evaluate_member(package, &graph.reachable_from(member_id)));
+    }
+
+    members.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(members)
+}
+
+fn evaluate_member(package: &Package, dependencies: &[&Package]) -> MsrvMember {
+    let declared = package.rust_version.clone();
+
+    let mut incompatible: Vec<MsrvDependency> = match &declared {
+        Some(declared) => dependencies
+            .iter()
+            .filter_map(|dep| {
+                let required = dep.rust_version.as_ref()?;
+                (required > declared).then(|| MsrvDependency {
+                    name: dep.name.to_string(),
+                    version: dep.version.to_string(),
+                    rust_version: required.to_string(),
+                })
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    incompatible.sort_by(|left, right| {
+        compare_rust_versions(&right.rust_version, &left.rust_version)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+
+    let state = match &declared {
+        None => MsrvState::Missing,
+        Some(_) if incompatible.is_empty() => MsrvState::Valid,
+        Some(_) => MsrvState::Invalid,
+    };
+
+    MsrvMember {
+        name: package.name.to_string(),
+        declared: declared.map(|version| version.to_string()),
+        state,
+        incompatible,
+        toolchain: None,
+    }
+}
+
+fn compare_rust_versions(left: &str, right: &str) -> std::cmp::Ordering {
+    match (Version::parse(left), Version::parse(right)) {
+        (Ok(left), Ok(right)) => left.cmp(&right),
+        _ => left.cmp(right),
+    }
+}
+
+/// The highest `rust-version` found among a member's dependencies.
+pub struct EffectiveMsrv<'a> {
+    pub effective: Option<Version>,
+    /// Dependencies whose `rust-version` equals the effective MSRV.
+    pub forced_by: Vec<&'a Package>,
+    /// The highest `rust-version` among the remaining dependencies.
+    pub next_highest: Option<Version>,
+}
+
+pub fn effective_msrv<'a>(dependencies: &[&'a Package]) -> EffectiveMsrv<'a> {
+    let effective = dependencies
+        .iter()
+        .filter_map(|dep| dep.rust_version.clone())
+        .max();
+
+    let mut forced_by: Vec<&Package> = match &effective {
+        Some(effective) => dependencies
+            .iter()
+            .copied()
+            .filter(|dep| dep.rust_version.as_ref() == Some(effective))
+            .collect(),
+        None => Vec::new(),
+    };
+    forced_by.sort_by(|left, right| {
+        left.name
+            .cmp(&right.name)
+            .then_with(|| left.version.cmp(&right.version))
+    });
+
+    let next_highest = dependencies
+        .iter()
+        .filter(|dep| !forced_by.iter().any(|forced| forced.name == dep.name))
+        .filter_map(|dep| dep.rust_version.clone())
+        .max();
+
+    EffectiveMsrv {
+        effective,
+        forced_by,
+        next_highest,
+    }
+}
+
+/// Picks the newest release of `forcing` that still satisfies `requirements`
+/// and declares a lower `rust-version` than `effective`.
+pub fn suggest_downgrade(
+    forcing: &Package,
+    requirements: &[VersionReq],
+    published: &[PublishedVersion],
+    effective: &Version,
+    next_highest: Option<&Version>,
+) -> Option<MsrvDowngrade> {
+    let (version, rust_version) = stable_releases(published)
+        .filter_map(|(release, version)| Some((version, declared_rust_version(release)?)))
+        .filter(|(version, rust_version)| {
+            version < &forcing.version
+                && rust_version < effective
+                && requirements.iter().all(|req| req.matches(version))
+        })
+        .max_by(|left, right| left.0.cmp(&right.0))?;
+
+    let resulting = match next_highest {
+        Some(next) if next > &rust_version => next.clone(),
+        _ => rust_version.clone(),
+    };
+
+    Some(MsrvDowngrade {
+        name: forcing.name.to_string(),
+        from: forcing.version.to_string(),
+        to: version.to_string(),
+        rust_version: format_rust_version(&rust_version),
+        resulting_msrv: format_rust_version(&resulting),
+    })
+}
+
+/// Returns the newest release whose `rust-version` is at most `msrv`, unless
+/// it is older than `current`, the version already in use.
+pub fn latest_compatible_release<'a>(
+    published: &'a [PublishedVersion],
+    msrv: &Version,
+    current: &Version,
+) -> Option<(&'a PublishedVersion, Version)> {
+    stable_releases(published)
+        .filter(|(release, _)| {
+            release.rust_version.is_none()
+                || declared_rust_version(release).is_some_and(|required| &required <= msrv)
+        })
+        .max_by(|left, right| left.1.cmp(&right.1))
+        .filter(|(_, version)| version >= current)
+}
+
+/// Stable, non-yanked releases with their parsed version.
+fn stable_releases(
+    published: &[PublishedVersion],
+) -> impl Iterator<Item = (&PublishedVersion, Version)> {
+    published
+        .iter()
+        .filter(|release| !release.yanked)
+        .filter_map(|release| {
+            let version = Version::parse(&release.num).ok()?;
+            version.pre.is_empty().then_some((release, version))
+        })
+}
+
+/// The `rust-version` a release declares, if it declares a valid one.
+fn declared_rust_version(release: &PublishedVersion) -> Option<Version> {
+    parse_rust_version(release.rust_version.as_deref()?)
+}
+
+/// Parses a `rust-version` value such as `1.70` or `1.70.1`.
+pub fn parse_rust_version(value: &str) -> Option<Version> {
+    let mut parts = value.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Version::new(major, minor, patch))
+}
+
+/// Formats a version the way `rust-version` is usually written, dropping a
+/// zero patch component (`1.70.0` becomes `1.70`).
+pub fn format_rust_version(version: &Version) -> String {
+    if version.patch == 0 {
+        format!("{}.{}", version.major, version.minor)
+    } else {
+        format!("{}.{}.{}", version.major, version.minor, version.patch)
+    }
+}
+
+async fn verify_with_toolchain(
+    workspace_root: &Path,
+    member: &str,
+    toolchain: &str,
+) -> ToolchainCheck {
+    let installed = match installed_toolchains().await {
+        Ok(installed) => installed,
+        Err(err) => {
+            return ToolchainCheck {
+                toolchain: toolchain.to_string(),
+                status: ToolchainCheckStatus::Unavailable,
+                message: Some(err.to_string()),
+            };
+        }
+    };
+
+    if !is_toolchain_installed(&installed, toolchain) {
+        return ToolchainCheck {
+            toolchain: toolchain.to_string(),
+            status: ToolchainCheckStatus::Unavailable,
+            message: Some(format!(
+                "toolchain {toolchain} is not installed; install with `rustup toolchain install {toolchain}`"
+            )),
+        };
+    }
+
+    let output = Command::new("rustup")
+        .args(["run", toolchain, "cargo", "check", "--quiet", "-p", member])
+        .current_dir(workspace_root)
+        .kill_on_drop(true)
+        .output();
+    let Ok(output) = timeout(CARGO_CHECK_TIMEOUT, output).await else {
+        return ToolchainCheck {
+            toolchain: toolchain.to_string(),
+            status: ToolchainCheckStatus::Unavailable,
+            message: Some(format!(
+                "cargo check timed out after {}s",
+                CARGO_CHECK_TIMEOUT.as_secs()
+            )),
+        };
+    };
+
+    match output {
+        Ok(output) if output.status.success() => ToolchainCheck {
+            toolchain: toolchain.to_string(),
+            status: ToolchainCheckStatus::Passed,
+            message: None,
+        },
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            ToolchainCheck {
+                toolchain: toolchain.to_string(),
+                status: ToolchainCheckStatus::Failed,
+                message: Some(first_error_line(&stderr)),
+            }
+        }
+        Err(err) => ToolchainCheck {
+            toolchain: toolchain.to_string(),
+            status: ToolchainCheckStatus::Unavailable,
+            message: Some(format!("failed to execute rustup: {err}")),
+        },
+    }
+}
+
+async fn installed_toolchains() -> Result<Vec<String>> {
+    let output = Command::new("rustup")
+        .args(["toolchain", "list"])
+        .output()
+        .await
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => UpkeepError::message(
+                ErrorCode::MissingTool,
+                "rustup is not installed or not on PATH",
+            ),
+            _ => UpkeepError::context(
+                ErrorCode::ExternalCommand,
+                "failed to execute rustup toolchain list",
+                err,
+            ),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(UpkeepError::message(
+            ErrorCode::ExternalCommand,
+            format!("rustup toolchain list failed: {}", stderr.trim()),
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_toolchain_list(&stdout))
+}
+
+/// Extracts toolchain names from `rustup toolchain list` output.
+///
+/// Lines look like `1.70.0-x86_64-unknown-linux-gnu (default)`; only the
+/// name before the first space is kept.
+fn parse_toolchain_list(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns true if a toolchain for `version` (e.g. `1.70.0`) is installed.
+///
+/// rustup names toolchains either by full version (`1.70.0-<host>`) or by
+/// minor channel (`1.70-<host>`), so both spellings are accepted.
+fn is_toolchain_installed(installed: &[String], version: &str) -> bool {
+    let short = version.strip_suffix(".0").unwrap_or(version);
+    installed.iter().any(|name| {
+        [version, short].iter().any(|candidate| {
+            name == candidate
+                || name
+                    .strip_prefix(candidate)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        })
+    })
+}
+
+pub fn first_error_line(stderr: &str) -> String {
+    stderr
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("error"))
+        .or_else(|| stderr.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or("cargo check failed with no stderr output")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, rust_version: Option<&str>) -> Package {
+        versioned_package(name, "1.0.0", rust_version)
+    }
+
+    fn versioned_package(name: &str, version: &str, rust_version: Option<&str>) -> Package {
+        let value = serde_json::json!({
+            "name": name,
+            "version": version,
+            "id": format!("{name} {version}"),
+            "source": null,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+            "rust_version": rust_version,
+        });
+        serde_json::from_value(value).expect("package")
+    }
+
+    #[test]
+    fn evaluate_member_reports_missing_without_declaration() {
+        let member = package("app", None);
+        let dep = package("dep", Some("1.80"));
+        let result = evaluate_member(&member, &[&dep]);
+        assert_eq!(result.state, MsrvState::Missing);
+        assert!(result.incompatible.is_empty());
+    }
+
+    #[test]
+    fn evaluate_member_flags_dependencies_above_declared() {
+        let member = package("app", Some("1.70"));
+        let older = package("older", Some("1.60"));
+        let newer = package("newer", Some("1.74"));
+        let newest = package("newest", Some("1.80"));
+        let unknown = package("unknown", None);
+
+        let result = evaluate_member(&member, &[&older, &newer, &unknown, &newest]);
+        assert_eq!(result.state, MsrvState::Invalid);
+        assert_eq!(result.declared.as_deref(), Some("1.70.0"));
+        let names: Vec<&str> = result
+            .incompatible
+            .iter()
+            .map(|dep| dep.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["newest", "newer"]);
+    }
+
+    #[test]
+    fn evaluate_member_is_valid_when_dependencies_fit() {
+        let member = package("app", Some("1.74"));
+        let dep = package("dep", Some("1.74"));
+        let result = evaluate_member(&member, &[&dep]);
+        assert_eq!(result.state, MsrvState::Valid);
+    }
+
+    #[test]
+    fn toolchain_detection_accepts_full_and_minor_names() {
+        let installed = parse_toolchain_list(
+            "stable-x86_64-unknown-linux-gnu (default)\n1.70-x86_64-unknown-linux-gnu\n1.74.1-x86_64-unknown-linux-gnu\n",
+        );
+        assert!(is_toolchain_installed(&installed, "1.70.0"));
+        assert!(is_toolchain_installed(&installed, "1.74.1"));
+        assert!(!is_toolchain_installed(&installed, "1.74.0"));
+        assert!(!is_toolchain_installed(&installed, "1.7.0"));
+    }
+
+    #[test]
+    fn first_error_line_prefers_error_lines() {
+        let stderr = "   Compiling foo v0.1.0\nerror[E0658]: use of unstable library feature\n";
+        assert_eq!(
+            first_error_line(stderr),
+            "error[E0658]: use of unstable library feature"
+        );
+        assert_eq!(
+            first_error_line(""),
+            "cargo check failed with no stderr output"
+        );
+    }
+
+    fn release(num: &str, rust_version: Option<&str>, yanked: bool) -> PublishedVersion {
+        PublishedVersion {
+            num: num.to_string(),
+            rust_version: rust_version.map(str::to_string),
+            yanked,
+        }
+    }
+
+    #[test]
+    fn effective_msrv_finds_forcing_crate_and_runner_up() {
+        let older = package("older", Some("1.60"));
+        let home = package("home", Some("1.81"));
+        let mid = package("mid", Some("1.74"));
+        let unknown = package("unknown", None);
+
+        let result = effective_msrv(&[&older, &home, &unknown, &mid]);
+        assert_eq!(result.effective, Some(Version::new(1, 81, 0)));
+        let forced: Vec<&str> = result
+            .forced_by
+            .iter()
+            .map(|pkg| pkg.name.as_str())
+            .collect();
+        assert_eq!(forced, vec!["home"]);
+        assert_eq!(result.next_highest, Some(Version::new(1, 74, 0)));
+
+        let none = effective_msrv(&[&unknown]);
+        assert!(none.effective.is_none());
+        assert!(none.forced_by.is_empty());
+    }
+
+    #[test]
+    fn suggest_downgrade_picks_newest_compatible_release() {
+        let home = versioned_package("home", "0.5.11", Some("1.81"));
+        let published = vec![
+            release("0.5.11", Some("1.81"), false),
+            release("0.5.10", Some("1.70"), true),
+            release("0.5.9", Some("1.70"), false),
+            release("0.5.8", None, false),
+            release("0.4.0", Some("1.40"), false),
+        ];
+        let requirements = vec![VersionReq::parse("^0.5").unwrap()];
+
+        let downgrade = suggest_downgrade(
+            &home,
+            &requirements,
+            &published,
+            &Version::new(1, 81, 0),
+            Some(&Version::new(1, 74, 0)),
+        )
+        .expect("downgrade");
+
+        assert_eq!(downgrade.to, "0.5.9");
+        assert_eq!(downgrade.rust_version, "1.70");
+        assert_eq!(downgrade.resulting_msrv, "1.74");
+    }
+
+    #[test]
+    fn suggest_downgrade_returns_none_without_candidates() {
+        let home = versioned_package("home", "0.5.11", Some("1.81"));
+        let published = vec![release("0.5.11", Some("1.81"), false)];
+        assert!(suggest_downgrade(&home, &[], &published, &Version::new(1, 81, 0), None).is_none());
+    }
+
+    #[test]
+    fn latest_compatible_release_respects_msrv() {
+        let published = vec![
+            release("2.0.0-rc.1", None, false),
+            release("1.4.0", Some("1.80"), false),
+            release("1.3.1", Some("1.70"), true),
+            release("1.3.0", Some("1.70"), false),
+            release("1.2.0", None, false),
+        ];
+
+        let current = Version::new(1, 2, 0);
+        let (release, version) =
+            latest_compatible_release(&published, &Version::new(1, 74, 0), &current)
+                .expect("release");
+        assert_eq!(release.num, "1.3.0");
+        assert_eq!(version, Version::new(1, 3, 0));
+
+        // 1.2.0 declares no rust-version and counts as compatible.
+        let (release, _) = latest_compatible_release(&published, &Version::new(1, 60, 0), &current)
+            .expect("release");
+        assert_eq!(release.num, "1.2.0");
+        // Never older than the version already in use.
+        assert!(latest_compatible_release(
+            &published,
+            &Version::new(1, 74, 0),
+            &Version::new(1, 3, 5)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn latest_compatible_release_treats_undeclared_as_compatible() {
+        let published = vec![
+            release("0.9.0", None, false),
+            release("0.8.0", Some("1.60"), false),
+        ];
+
+        let (release, _) =
+            latest_compatible_release(&published, &Version::new(1, 56, 0), &Version::new(0, 8, 0))
+                .expect("release");
+        assert_eq!(release.num, "0.9.0");
+    }
+
+    #[test]
+    fn rust_version_round_trips() {
+        assert_eq!(parse_rust_version("1.70"), Some(Version::new(1, 70, 0)));
+        assert_eq!(parse_rust_version("1.70.1"), Some(Version::new(1, 70, 1)));
+        assert_eq!(parse_rust_version("1"), Some(Version::new(1, 0, 0)));
+        assert!(parse_rust_version("1.x").is_none());
+        assert!(parse_rust_version("1.2.3.4").is_none());
+        assert_eq!(format_rust_version(&Version::new(1, 70, 0)), "1.70");
+        assert_eq!(format_rust_version(&Version::new(1, 70, 1)), "1.70.1");
+    }
+}
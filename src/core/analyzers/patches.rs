@@ -0,0 +1,412 @@
+//! `[patch]` and `[replace]` entries in the workspace root manifest, matched
+//! against the resolved packages they override.
+
+use cargo_metadata::camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use cargo_metadata::{Metadata, Package};
+use semver::{Version, VersionReq};
+use toml_edit::{DocumentMut, Item};
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{PatchEntry, PatchKind};
+use crate::core::source::GitSource;
+
+const CRATES_IO: &str = "crates-io";
+const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// One entry of a `[patch.<target>]` or `[replace]` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestPatch {
+    pub kind: PatchKind,
+    pub target: String,
+    /// The crate being overridden; `package = "..."` renames are resolved.
+    pub name: String,
+    pub source: PatchSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchSource {
+    /// A local directory, relative paths resolved against the manifest.
+    Path(Utf8PathBuf),
+    Git {
+        url: String,
+        /// `branch=...`, `tag=...` or `rev=...`.
+        reference: Option<String>,
+    },
+    /// Another version from a registry.
+    Version(String),
+}
+
+impl PatchSource {
+    fn describe(&self) -> String {
+        match self {
+            PatchSource::Path(path) => format!("path {path}"),
+            PatchSource::Git {
+                url,
+                reference: Some(reference),
+            } => format!("git {url} ({reference})"),
+            PatchSource::Git {
+                url,
+                reference: None,
+            } => format!("git {url}"),
+            PatchSource::Version(req) => format!("version {req}"),
+        }
+    }
+
+    fn matches(&self, package: &Package) -> bool {
+        match (self, &package.source) {
+            (PatchSource::Path(path), None) => package
+                .manifest_path
+                .parent()
+                .is_some_and(|dir| normalize(dir) == *path),
+            (PatchSource::Git { url, .. }, Some(source)) => {
+                GitSource::parse(&source.repr).is_some_and(|git| git.url == *url)
+            }
+            (PatchSource::Version(req), Some(source)) => {
+                GitSource::parse(&source.repr).is_none()
+                    && VersionReq::parse(req).is_ok_and(|req| req.matches(&package.version))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ManifestPatch {
+    /// Whether the overridden crate is published on crates.io.
+    pub fn targets_crates_io(&self) -> bool {
+        match self.kind {
+            PatchKind::Patch => {
+                self.target == CRATES_IO || self.target.trim_end_matches('/') == CRATES_IO_INDEX
+            }
+            // Specs without a source URL refer to crates.io.
+            PatchKind::Replace => {
+                !self.target.contains('#') || self.target.starts_with(CRATES_IO_INDEX)
+            }
+        }
+    }
+}
+
+/// Reads the `[patch]` and `[replace]` tables of the manifest at
+/// `manifest_path`. Relative paths are resolved against its directory.
+pub fn read_patches(manifest_path: &Utf8Path) -> Result<Vec<ManifestPatch>> {
+    let contents = std::fs::read_to_string(manifest_path).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Io,
+            format!("failed to read {manifest_path}"),
+            err,
+        )
+    })?;
+    let root = manifest_path.parent().unwrap_or(Utf8Path::new("."));
+    parse_patches(&contents, root).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::InvalidData,
+            format!("failed to parse {manifest_path}"),
+            err,
+        )
+    })
+}
+
+fn parse_patches(
+    contents: &str,
+    root: &Utf8Path,
+) -> std::result::Result<Vec<ManifestPatch>, toml_edit::TomlError> {
+    let document = contents.parse::<DocumentMut>()?;
+    let mut patches = Vec::new();
+
+    let targets = document.get("patch").and_then(Item::as_table_like);
+    for (target, table) in targets.into_iter().flat_map(|table| table.iter()) {
+        let Some(table) = table.as_table_like() else {
+            continue;
+        };
+        for (key, entry) in table.iter() {
+            if let Some((name, source)) = parse_entry(key, entry, root) {
+                patches.push(ManifestPatch {
+                    kind: PatchKind::Patch,
+                    target: target.to_string(),
+                    name,
+                    source,
+                });
+            }
+        }
+    }
+
+    let replace = document.get("replace").and_then(Item::as_table_like);
+    for (spec, entry) in replace.into_iter().flat_map(|table| table.iter()) {
+        // `name:version`, optionally prefixed by `<source url>#`.
+        let package = spec.rsplit('#').next().unwrap_or(spec);
+        let name = package.split(':').next().unwrap_or(package);
+        if let Some((_, source)) = parse_entry(name, entry, root) {
+            patches.push(ManifestPatch {
+                kind: PatchKind::Replace,
+                target: spec.to_string(),
+                name: name.to_string(),
+                source,
+            });
+        }
+    }
+
+    Ok(patches)
+}
+
+fn parse_entry(key: &str, entry: &Item, root: &Utf8Path) -> Option<(String, PatchSource)> {
+    if let Some(req) = entry.as_str() {
+        return Some((key.to_string(), PatchSource::Version(req.to_string())));
+    }
+    let entry = entry.as_table_like()?;
+    let field = |name: &str| entry.get(name).and_then(Item::as_str);
+    let name = field("package").unwrap_or(key).to_string();
+    let source = if let Some(path) = field("path") {
+        PatchSource::Path(normalize(&root.join(path)))
+    } else if let Some(url) = field("git") {
+        let reference = ["branch", "tag", "rev"]
+            .into_iter()
+            .find_map(|kind| field(kind).map(|value| format!("{kind}={value}")));
+        PatchSource::Git {
+            url: url.to_string(),
+            reference,
+        }
+    } else {
+        PatchSource::Version(field("version")?.to_string())
+    };
+    Some((name, source))
+}
+
+/// Drops `.` and `..` components the way cargo does, without touching the
+/// filesystem.
+fn normalize(path: &Utf8Path) -> Utf8PathBuf {
+    let mut normalized = Utf8PathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8Component::CurDir => {}
+            Utf8Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Matches each patch to the resolved package it provides and the packages
+/// depending on it. `unused` holds the `[[patch.unused]]` entries of
+/// Cargo.lock as `(name, version)`.
+pub fn inventory(
+    metadata: &Metadata,
+    patches: &[ManifestPatch],
+    unused: &[(String, String)],
+) -> Vec<PatchEntry> {
+    patches
+        .iter()
+        .map(|patch| {
+            let resolved = metadata.packages.iter().find(|package| {
+                package.name.as_str() == patch.name && patch.source.matches(package)
+            });
+            let version = match resolved {
+                Some(package) => Some(package.version.to_string()),
+                None => unused
+                    .iter()
+                    .find(|(name, _)| *name == patch.name)
+                    .map(|(_, version)| version.clone()),
+            };
+            let mut affects: Vec<String> = resolved
+                .and_then(|package| {
+                    let nodes = &metadata.resolve.as_ref()?.nodes;
+                    Some(
+                        nodes
+                            .iter()
+                            .filter(|node| node.deps.iter().any(|dep| dep.pkg == package.id))
+                            .filter_map(|node| {
+                                metadata.packages.iter().find(|parent| parent.id == node.id)
+                            })
+                            .map(|parent| format!("{} {}", parent.name, parent.version))
+                            .collect(),
+                    )
+                })
+                .unwrap_or_default();
+            affects.sort();
+            PatchEntry {
+                kind: patch.kind,
+                target: patch.target.clone(),
+                name: patch.name.clone(),
+                source: patch.source.describe(),
+                version,
+                used: resolved.is_some(),
+                affects,
+                upstream: None,
+                superseded: false,
+            }
+        })
+        .collect()
+}
+
+/// Records the newest crates.io release and whether it supersedes the patched
+/// version. A release with the same version is not enough, since the patch
+/// usually carries changes that were never published under that number.
+pub fn apply_upstream(entry: &mut PatchEntry, latest: &str) {
+    let covered = entry
+        .version
+        .as_deref()
+        .and_then(|version| Version::parse(version).ok())
+        .zip(Version::parse(latest).ok())
+        .is_some_and(|(patched, latest)| latest > patched);
+    entry.upstream = Some(latest.to_string());
+    entry.superseded = covered;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MANIFEST: &str = r#"
+[workspace]
+members = ["app"]
+
+[patch.crates-io]
+local = { path = "../forks/local" }
+forked = { git = "https://github.com/me/forked", branch = "fix" }
+renamed = { git = "https://github.com/me/other", package = "real-name" }
+
+[patch."https://github.com/org/repo"]
+internal = { path = "vendor/internal" }
+
+[replace]
+"old:0.1.0" = { path = "vendor/old" }
+"#;
+
+    #[test]
+    fn parse_patches_reads_patch_and_replace_tables() {
+        let patches = parse_patches(MANIFEST, Utf8Path::new("/work/repo")).expect("parse");
+
+        let summary: Vec<(PatchKind, &str, &str, String)> = patches
+            .iter()
+            .map(|patch| {
+                (
+                    patch.kind,
+                    patch.target.as_str(),
+                    patch.name.as_str(),
+                    patch.source.describe(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    PatchKind::Patch,
+                    "crates-io",
+                    "local",
+                    "path /work/forks/local".to_string()
+                ),
+                (
+                    PatchKind::Patch,
+                    "crates-io",
+                    "forked",
+                    "git https://github.com/me/forked (branch=fix)".to_string()
+                ),
+                (
+                    PatchKind::Patch,
+                    "crates-io",
+                    "real-name",
+                    "git https://github.com/me/other".to_string()
+                ),
+                (
+                    PatchKind::Patch,
+                    "https://github.com/org/repo",
+                    "internal",
+                    "path /work/repo/vendor/internal".to_string()
+                ),
+                (
+                    PatchKind::Replace,
+                    "old:0.1.0",
+                    "old",
+                    "path /work/repo/vendor/old".to_string()
+                ),
+            ]
+        );
+        assert!(patches[0].targets_crates_io());
+        assert!(!patches[3].targets_crates_io());
+        assert!(patches[4].targets_crates_io());
+    }
+
+    fn package(name: &str, version: &str, source: Option<&str>, dir: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "version": version,
+            "id": format!("{name} {version}"),
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("{dir}/Cargo.toml"),
+            "edition": "2021",
+        })
+    }
+
+    #[test]
+    fn inventory_matches_resolved_and_unused_patches() {
+        let metadata: Metadata = serde_json::from_value(json!({
+            "packages": [
+                package("app", "0.1.0", None, "/work/repo/app"),
+                package("local", "1.2.0", None, "/work/forks/local"),
+                package(
+                    "forked",
+                    "0.3.0",
+                    Some("git+https://github.com/me/forked?branch=fix#abc123"),
+                    "/git/forked"
+                ),
+            ],
+            "workspace_members": ["app 0.1.0"],
+            "workspace_default_members": ["app 0.1.0"],
+            "resolve": {
+                "nodes": [
+                    {
+                        "id": "app 0.1.0",
+                        "deps": [
+                            { "name": "local", "pkg": "local 1.2.0", "dep_kinds": [] },
+                            { "name": "forked", "pkg": "forked 0.3.0", "dep_kinds": [] }
+                        ],
+                        "dependencies": ["local 1.2.0", "forked 0.3.0"],
+                        "features": []
+                    },
+                    { "id": "local 1.2.0", "deps": [], "dependencies": [], "features": [] },
+                    { "id": "forked 0.3.0", "deps": [], "dependencies": [], "features": [] }
+                ],
+                "root": "app 0.1.0"
+            },
+            "workspace_root": "/work/repo",
+            "target_directory": "/work/repo/target",
+            "version": 1,
+        }))
+        .expect("metadata");
+        let patches = parse_patches(MANIFEST, Utf8Path::new("/work/repo")).expect("parse");
+        let unused = vec![("real-name".to_string(), "2.0.0".to_string())];
+
+        let mut entries = inventory(&metadata, &patches, &unused);
+
+        let used: Vec<(&str, bool, Option<&str>)> = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.used, entry.version.as_deref()))
+            .collect();
+        assert_eq!(
+            used,
+            vec![
+                ("local", true, Some("1.2.0")),
+                ("forked", true, Some("0.3.0")),
+                ("real-name", false, Some("2.0.0")),
+                ("internal", false, None),
+                ("old", false, None),
+            ]
+        );
+        assert_eq!(entries[0].affects, vec!["app 0.1.0"]);
+
+        // A fork keeps the version it was taken from, so an equal release
+        // does not supersede it.
+        apply_upstream(&mut entries[0], "1.2.0");
+        assert!(!entries[0].superseded);
+        apply_upstream(&mut entries[0], "1.2.1");
+        assert!(entries[0].superseded);
+        apply_upstream(&mut entries[1], "0.2.9");
+        assert!(!entries[1].superseded);
+        assert_eq!(entries[1].upstream.as_deref(), Some("0.2.9"));
+    }
+}
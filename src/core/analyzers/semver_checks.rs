@@ -0,0 +1,357 @@
+//! Public API compatibility of workspace libraries via cargo-semver-checks.
+//!
+//! Each publishable library is compared against its last release on crates.io,
+//! or a git revision, and the breaking changes found are weighed against the
+//! release level implied by the version in Cargo.toml.
+
+use cargo_metadata::{MetadataCommand, Package, TargetKind};
+use semver::Version;
+use std::path::Path;
+
+use crate::core::analyzers::external_tool::{
+    handle_tool_output, is_missing_subcommand, run_cargo_tool, ExternalToolConfig,
+};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{ReleaseBump, SemverCrate, SemverOutput, SemverViolation};
+
+const SEMVER_CHECKS_CONFIG: ExternalToolConfig<'static> = ExternalToolConfig {
+    tool_name: "semver-checks",
+    install_hint: "cargo install cargo-semver-checks --locked",
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct SemverOptions {
+    /// Workspace member to check; every publishable library when unset.
+    pub package: Option<String>,
+    /// Git revision to compare against instead of the latest crates.io release.
+    pub baseline_rev: Option<String>,
+}
+
+pub async fn run_semver_checks(options: &SemverOptions) -> Result<SemverOutput> {
+    let metadata = MetadataCommand::new().no_deps().exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let workspace_root = metadata.workspace_root.as_std_path();
+
+    let names: Vec<String> = match &options.package {
+        Some(name) => vec![name.clone()],
+        None => metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|package| is_publishable_library(package))
+            .map(|package| package.name.to_string())
+            .collect(),
+    };
+    if names.is_empty() {
+        return Err(UpkeepError::message(
+            ErrorCode::Config,
+            "no publishable library crates to check",
+        ));
+    }
+
+    let mut crates = Vec::new();
+    let mut warnings = Vec::new();
+    for name in &names {
+        match check_package(workspace_root, name, options.baseline_rev.as_deref()).await {
+            Ok(krate) => crates.push(krate),
+            // A missing tool fails every package the same way; a single
+            // requested package has nothing else to report.
+            Err(err) if err.code() == ErrorCode::MissingTool || options.package.is_some() => {
+                return Err(err);
+            }
+            Err(err) => warnings.push(format!("{name}: {err}")),
+        }
+    }
+    if crates.is_empty() {
+        return Err(UpkeepError::message(
+            ErrorCode::ExternalCommand,
+            format!("cargo semver-checks failed: {}", warnings.join("; ")),
+        ));
+    }
+
+    Ok(SemverOutput { crates, warnings })
+}
+
+/// Libraries that can be published; binaries have no public API to check.
+fn is_publishable_library(package: &Package) -> bool {
+    let publishable = package
+        .publish
+        .as_ref()
+        .map_or(true, |registries| !registries.is_empty());
+    publishable
+        && package
+            .targets
+            .iter()
+            .any(|target| target.kind.contains(&TargetKind::Lib))
+}
+
+async fn check_package(
+    workspace_root: &Path,
+    name: &str,
+    baseline_rev: Option<&str>,
+) -> Result<SemverCrate> {
+    let mut args = vec![
+        "semver-checks",
+        "check-release",
+        "--color",
+        "never",
+        "-p",
+        name,
+    ];
+    if let Some(rev) = baseline_rev {
+        args.extend(["--baseline-rev", rev]);
+    }
+    let output = run_cargo_tool(&args, workspace_root, &SEMVER_CHECKS_CONFIG).await?;
+    interpret_output(name, output)
+}
+
+/// Turns a check-release run for one package into a report. The tool exits
+/// non-zero when lints fail, so only a failure without any lint output is
+/// treated as an error.
+fn interpret_output(name: &str, output: std::process::Output) -> Result<SemverCrate> {
+    // Progress and the summary go to stderr, lint failures to stdout.
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stderr),
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let report = parse_report(&text);
+    if !output.status.success() && report.violations.is_empty() {
+        handle_tool_output(
+            std::process::Output {
+                stdout: Vec::new(),
+                ..output
+            },
+            &SEMVER_CHECKS_CONFIG,
+            |stderr| is_missing_subcommand(stderr, SEMVER_CHECKS_CONFIG.tool_name),
+        )?;
+    }
+    Ok(report.into_crate(name))
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    baseline: Option<String>,
+    current: Option<String>,
+    required: Option<ReleaseBump>,
+    violations: Vec<SemverViolation>,
+}
+
+impl Report {
+    fn into_crate(self, name: &str) -> SemverCrate {
+        let parsed = |version: &Option<String>| {
+            version
+                .as_deref()
+                .and_then(|version| Version::parse(version).ok())
+        };
+        let bump = match (parsed(&self.baseline), parsed(&self.current)) {
+            (Some(baseline), Some(current)) => release_bump(&baseline, &current),
+            _ => ReleaseBump::None,
+        };
+        let required = match self.required {
+            Some(required) => required,
+            // Every lint is at least minor-level; without a summary assume the worst.
+            None if !self.violations.is_empty() => ReleaseBump::Major,
+            None => ReleaseBump::None,
+        };
+        SemverCrate {
+            name: name.to_string(),
+            baseline: self.baseline,
+            current: self.current.unwrap_or_default(),
+            bump,
+            required,
+            sufficient: bump >= required,
+            violations: self.violations,
+        }
+    }
+}
+
+/// Parses the text report of `cargo semver-checks check-release` for a
+/// single crate:
+///
+/// ```text
+///     Checking foo v1.2.0 -> v1.3.0 (minor change)
+/// --- failure function_missing: pub fn removed or renamed ---
+/// Failed in:
+///   function foo::bar, previously in file src/lib.rs:10
+///      Summary semver requires new major version: 1 major and 0 minor checks failed
+/// ```
+fn parse_report(text: &str) -> Report {
+    let mut report = Report::default();
+    let mut in_failure = false;
+    let mut collecting = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            collecting = false;
+        } else if let Some(rest) = trimmed.strip_prefix("Checking ") {
+            let parts: Vec<&str> = rest.split_whitespace().collect();
+            if let [_, baseline, "->", current, ..] = parts.as_slice() {
+                report.baseline = Some(baseline.trim_start_matches('v').to_string());
+                report.current = Some(current.trim_start_matches('v').to_string());
+            }
+        } else if let Some(header) = trimmed.strip_prefix("--- ") {
+            collecting = false;
+            in_failure = false;
+            let Some(failure) = header
+                .strip_prefix("failure ")
+                .map(|failure| failure.trim_end_matches('-').trim_end())
+            else {
+                continue;
+            };
+            let (lint, summary) = failure.split_once(": ").unwrap_or((failure, ""));
+            report.violations.push(SemverViolation {
+                lint: lint.to_string(),
+                summary: summary.to_string(),
+                locations: Vec::new(),
+            });
+            in_failure = true;
+        } else if trimmed == "Failed in:" {
+            collecting = in_failure;
+        } else if collecting {
+            if let Some(violation) = report.violations.last_mut() {
+                violation.locations.push(trimmed.to_string());
+            }
+        } else if let Some(summary) = trimmed.strip_prefix("Summary ") {
+            if summary.contains("new major version") {
+                report.required = Some(ReleaseBump::Major);
+            } else if summary.contains("new minor version") {
+                report.required = Some(ReleaseBump::Minor);
+            } else if summary.contains("no semver update required") {
+                report.required = Some(ReleaseBump::None);
+            }
+        }
+    }
+    report
+}
+
+/// The release level of moving from `baseline` to `current`. Below 1.0 the
+/// leftmost non-zero component is the breaking one, as Cargo treats it.
+pub fn release_bump(baseline: &Version, current: &Version) -> ReleaseBump {
+    if current <= baseline {
+        return ReleaseBump::None;
+    }
+    let components = [
+        (baseline.major, current.major),
+        (baseline.minor, current.minor),
+        (baseline.patch, current.patch),
+    ];
+    // Skip leading zero components shared by both versions, so 0.1 -> 0.2 is
+    // a major bump and 0.1.0 -> 0.1.1 a minor one.
+    let leading_zeros = components
+        .iter()
+        .take(2)
+        .take_while(|(from, to)| *from == 0 && *to == 0)
+        .count();
+    let levels = [ReleaseBump::Major, ReleaseBump::Minor, ReleaseBump::Patch];
+    components[leading_zeros..]
+        .iter()
+        .zip(levels)
+        .find(|((from, to), _)| from != to)
+        .map_or(ReleaseBump::Patch, |(_, level)| level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> std::process::ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+        std::process::ExitStatus::from_raw(code << 8)
+    }
+
+    #[cfg(windows)]
+    fn exit_status(code: i32) -> std::process::ExitStatus {
+        use std::os::windows::process::ExitStatusExt;
+        std::process::ExitStatus::from_raw(code as u32)
+    }
+
+    fn output_with(code: i32, stdout: &str, stderr: &str) -> std::process::Output {
+        std::process::Output {
+            status: exit_status(code),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    const FAILED_STDOUT: &str = "\
+--- failure function_missing: pub fn removed or renamed ---
+
+Description:
+A publicly-visible function cannot be imported by its prior path.
+        ref: https://doc.rust-lang.org/cargo/reference/semver.html#item-remove
+
+Failed in:
+  function mylib::parse, previously in file /src/lib.rs:10
+  function mylib::render, previously in file /src/lib.rs:20
+
+--- warning must_use_added: #[must_use] added ---
+
+Failed in:
+  function mylib::build in /src/lib.rs:30
+";
+
+    const FAILED_STDERR: &str = "\
+     Parsing mylib v0.3.1 (current)
+    Checking mylib v0.3.0 -> v0.3.1 (minor change)
+     Checked [   0.012s] 120 checks: 119 pass, 1 fail, 1 warn, 0 skip
+     Summary semver requires new major version: 1 major and 0 minor checks failed
+";
+
+    #[test]
+    fn interpret_output_reports_failed_lints_against_the_bump() {
+        let krate =
+            interpret_output("mylib", output_with(1, FAILED_STDOUT, FAILED_STDERR)).unwrap();
+
+        assert_eq!(krate.baseline.as_deref(), Some("0.3.0"));
+        assert_eq!(krate.current, "0.3.1");
+        assert_eq!(krate.bump, ReleaseBump::Minor);
+        assert_eq!(krate.required, ReleaseBump::Major);
+        assert!(!krate.sufficient);
+        assert_eq!(krate.violations.len(), 1);
+        assert_eq!(krate.violations[0].lint, "function_missing");
+        assert_eq!(krate.violations[0].summary, "pub fn removed or renamed");
+        assert_eq!(krate.violations[0].locations.len(), 2);
+    }
+
+    #[test]
+    fn interpret_output_accepts_clean_runs() {
+        let stderr = "    Checking mylib v1.0.0 -> v1.0.1 (patch change)\n     \
+                      Summary no semver update required\n";
+        let krate = interpret_output("mylib", output_with(0, "", stderr)).unwrap();
+        assert_eq!(krate.bump, ReleaseBump::Patch);
+        assert_eq!(krate.required, ReleaseBump::None);
+        assert!(krate.sufficient);
+        assert!(krate.violations.is_empty());
+    }
+
+    #[test]
+    fn interpret_output_detects_missing_tool() {
+        let output = output_with(101, "", "error: no such command: `semver-checks`");
+        let err = interpret_output("mylib", output).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::MissingTool);
+        assert!(err
+            .to_string()
+            .contains("cargo install cargo-semver-checks"));
+
+        let output = output_with(1, "", "error: mylib not found in registry");
+        let err = interpret_output("mylib", output).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ExternalCommand);
+    }
+
+    #[test]
+    fn release_bump_follows_cargo_compatibility() {
+        let bump = |from: &str, to: &str| {
+            release_bump(&Version::parse(from).unwrap(), &Version::parse(to).unwrap())
+        };
+        assert_eq!(bump("1.2.3", "2.0.0"), ReleaseBump::Major);
+        assert_eq!(bump("1.2.3", "1.3.0"), ReleaseBump::Minor);
+        assert_eq!(bump("1.2.3", "1.2.4"), ReleaseBump::Patch);
+        assert_eq!(bump("1.2.3", "1.2.3"), ReleaseBump::None);
+        assert_eq!(bump("0.1.7", "0.2.0"), ReleaseBump::Major);
+        assert_eq!(bump("0.1.7", "0.1.8"), ReleaseBump::Minor);
+        assert_eq!(bump("0.0.1", "0.0.2"), ReleaseBump::Major);
+        assert_eq!(bump("1.0.0-rc.1", "1.0.0"), ReleaseBump::Patch);
+    }
+}
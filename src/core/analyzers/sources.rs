@@ -0,0 +1,325 @@
+//! Where each dependency in the resolved graph comes from, checked against
+//! the configured source policy.
+
+use cargo_metadata::camino::Utf8Path;
+use cargo_metadata::{MetadataCommand, Package, PackageId};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::core::config::{SourcePolicy, UpkeepConfig};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
+use crate::core::graph::ResolveGraph;
+use crate::core::output::{DependencySource, SourcePackage, SourceViolation, SourcesOutput};
+use crate::core::platform::TargetFilter;
+use crate::core::source::GitSource;
+
+/// Classifies every dependency reachable from the workspace members (or the
+/// `-p` package) and checks it against `[workspace.metadata.upkeep.sources]`.
+pub fn run_sources(
+    include_dev: bool,
+    targets: &TargetFilter,
+    features: &FeatureSelection,
+) -> Result<SourcesOutput> {
+    let mut command = MetadataCommand::new();
+    features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let config = UpkeepConfig::from_metadata(&metadata)?;
+    let graph = ResolveGraph::from_metadata(&metadata, include_dev, targets)?;
+
+    let roots: Vec<&PackageId> = match features.selected_package(&metadata)? {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let members: HashSet<&PackageId> = metadata.workspace_members.iter().collect();
+    let reachable = graph.reachable_from_roots(&roots, |_, _| false);
+
+    // Credit each crate to the members that reach it without going through
+    // another member, so a crate is blamed on the manifest that added it.
+    let mut introduced_by: HashMap<PackageId, BTreeSet<String>> = HashMap::new();
+    for member in members.iter().filter(|id| reachable.contains(**id)) {
+        let Some(package) = graph.package(member) else {
+            continue;
+        };
+        for id in graph.reachable_from_roots(&[*member], |_, edge| members.contains(&edge.id)) {
+            introduced_by
+                .entry(id)
+                .or_default()
+                .insert(package.name.to_string());
+        }
+    }
+
+    let mut packages: Vec<&Package> = reachable
+        .iter()
+        .filter(|id| !members.contains(id))
+        .filter_map(|id| graph.package(id))
+        .collect();
+    packages.sort_by(|left, right| {
+        left.name
+            .cmp(&right.name)
+            .then_with(|| left.version.cmp(&right.version))
+    });
+
+    Ok(check_sources(
+        &packages,
+        &introduced_by,
+        &metadata.workspace_root,
+        &config.sources,
+    ))
+}
+
+pub fn check_sources(
+    packages: &[&Package],
+    introduced_by: &HashMap<PackageId, BTreeSet<String>>,
+    workspace_root: &Utf8Path,
+    policy: &SourcePolicy,
+) -> SourcesOutput {
+    let mut sources = Vec::new();
+    let mut violations = Vec::new();
+    for package in packages {
+        let source = classify(package);
+        let reasons = evaluate(&source, workspace_root, policy);
+        if !reasons.is_empty() {
+            violations.push(SourceViolation {
+                name: source.name.clone(),
+                version: source.version.clone(),
+                kind: source.kind,
+                location: source.location.clone(),
+                reason: reasons.join(", "),
+                introduced_by: introduced_by
+                    .get(&package.id)
+                    .map(|members| members.iter().cloned().collect())
+                    .unwrap_or_default(),
+            });
+        }
+        sources.push(source);
+    }
+    SourcesOutput {
+        packages: sources,
+        violations,
+    }
+}
+
+pub fn classify(package: &Package) -> SourcePackage {
+    let (kind, location, reference) = match &package.source {
+        None => (
+            DependencySource::Path,
+            package.manifest_path.parent().map(ToString::to_string),
+            None,
+        ),
+        Some(source) if source.is_crates_io() => (DependencySource::CratesIo, None, None),
+        Some(source) => match GitSource::parse(&source.repr) {
+            Some(git) => (DependencySource::Git, Some(git.url), git.reference),
+            None => (
+                DependencySource::Registry,
+                Some(registry_url(&source.repr).to_string()),
+                None,
+            ),
+        },
+    };
+    SourcePackage {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        kind,
+        location,
+        reference,
+    }
+}
+
+fn evaluate(
+    source: &SourcePackage,
+    workspace_root: &Utf8Path,
+    policy: &SourcePolicy,
+) -> Vec<String> {
+    let location = source.location.as_deref().unwrap_or_default();
+    let mut reasons = Vec::new();
+    match source.kind {
+        DependencySource::CratesIo => {}
+        DependencySource::Registry => {
+            let allowed = policy
+                .registries
+                .iter()
+                .any(|registry| same_url(registry, location));
+            if !allowed {
+                reasons.push(format!("registry {location} is not allowed"));
+            }
+        }
+        DependencySource::Git => {
+            let allowed = policy.git.is_empty()
+                || policy
+                    .git
+                    .iter()
+                    .any(|prefix| under_prefix(location, prefix));
+            if !allowed {
+                reasons.push(format!("git repository {location} is not allowed"));
+            }
+            if !policy.allow_branches {
+                match source.reference.as_deref() {
+                    None => reasons.push("follows the default branch".to_string()),
+                    Some(reference) if reference.starts_with("branch=") => reasons.push(format!(
+                        "follows branch {}",
+                        reference.trim_start_matches("branch=")
+                    )),
+                    Some(_) => {}
+                }
+            }
+        }
+        DependencySource::Path => {
+            let inside = Utf8Path::new(location).starts_with(workspace_root);
+            if !inside && !policy.allow_external_paths {
+                reasons.push(format!("path {location} is outside the workspace"));
+            }
+        }
+    }
+    reasons
+}
+
+/// The index URL of a registry source, which cargo writes as
+/// `registry+<url>` or `sparse+<url>`.
+fn registry_url(source: &str) -> &str {
+    source.strip_prefix("registry+").unwrap_or(source)
+}
+
+/// Compares registry URLs, ignoring the protocol marker and trailing slashes.
+fn same_url(left: &str, right: &str) -> bool {
+    let normalize = |url: &'_ str| {
+        let url = registry_url(url);
+        url.strip_prefix("sparse+")
+            .unwrap_or(url)
+            .trim_end_matches('/')
+            .to_ascii_lowercase()
+    };
+    normalize(left) == normalize(right)
+}
+
+/// Whether `url` is `prefix` or below it, so `https://github.com/org` does not
+/// match `https://github.com/org-fork`.
+fn under_prefix(url: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    url.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn package(name: &str, source: Option<&str>, manifest_dir: &str) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("{manifest_dir}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn reasons(output: &SourcesOutput) -> Vec<(&str, &str)> {
+        output
+            .violations
+            .iter()
+            .map(|violation| (violation.name.as_str(), violation.reason.as_str()))
+            .collect()
+    }
+
+    fn packages() -> Vec<Package> {
+        vec![
+            package(
+                "serde",
+                Some("registry+https://github.com/rust-lang/crates.io-index"),
+                "/registry/serde",
+            ),
+            package(
+                "internal",
+                Some("sparse+https://registry.example.com/index/"),
+                "/registry/internal",
+            ),
+            package(
+                "pinned",
+                Some("git+https://github.com/my-org/pinned?tag=v1.0.0#abc123"),
+                "/git/pinned",
+            ),
+            package(
+                "floating",
+                Some("git+https://github.com/other/floating?branch=main#def456"),
+                "/git/floating",
+            ),
+            package("vendored", None, "/work/repo/vendor/vendored"),
+            package("sibling", None, "/work/sibling"),
+        ]
+    }
+
+    #[test]
+    fn check_sources_flags_branches_external_paths_and_unknown_registries() {
+        let packages = packages();
+        let refs: Vec<&Package> = packages.iter().collect();
+
+        let mut introduced_by = HashMap::new();
+        introduced_by.insert(
+            packages[3].id.clone(),
+            BTreeSet::from(["app".to_string(), "cli".to_string()]),
+        );
+
+        let output = check_sources(
+            &refs,
+            &introduced_by,
+            Utf8Path::new("/work/repo"),
+            &SourcePolicy::default(),
+        );
+
+        let kinds: Vec<DependencySource> = output.packages.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DependencySource::CratesIo,
+                DependencySource::Registry,
+                DependencySource::Git,
+                DependencySource::Git,
+                DependencySource::Path,
+                DependencySource::Path,
+            ]
+        );
+        assert_eq!(output.packages[2].reference.as_deref(), Some("tag=v1.0.0"));
+        assert_eq!(
+            reasons(&output),
+            vec![
+                (
+                    "internal",
+                    "registry sparse+https://registry.example.com/index/ is not allowed"
+                ),
+                ("floating", "follows branch main"),
+                ("sibling", "path /work/sibling is outside the workspace"),
+            ]
+        );
+        assert_eq!(output.violations[1].introduced_by, vec!["app", "cli"]);
+    }
+
+    #[test]
+    fn check_sources_applies_allowlists() {
+        let packages = packages();
+        let refs: Vec<&Package> = packages.iter().collect();
+        let policy = SourcePolicy {
+            registries: vec!["https://registry.example.com/index".to_string()],
+            git: vec!["https://github.com/my-org/".to_string()],
+            allow_branches: true,
+            allow_external_paths: true,
+        };
+
+        let output = check_sources(&refs, &HashMap::new(), Utf8Path::new("/work/repo"), &policy);
+
+        assert_eq!(
+            reasons(&output),
+            vec![(
+                "floating",
+                "git repository https://github.com/other/floating is not allowed"
+            )]
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use cargo_metadata::MetadataCommand;
+use cargo_metadata::{Metadata, MetadataCommand};
 use serde_json::Value;
+use std::collections::HashSet;
 use std::future::Future;
 use std::path::Path;
 use std::path::PathBuf;
@@ -11,6 +12,7 @@ use crate::core::analyzers::external_tool::{
 use crate::core::analyzers::util::describe_json_schema;
 use crate::core::error::{ErrorCode, Result, UpkeepError};
 use crate::core::output::{Confidence, DependencyType, UnusedDep, UnusedOutput};
+use crate::core::platform::TargetFilter;
 
 const MACHETE_CONFIG: ExternalToolConfig<'static> = ExternalToolConfig {
     tool_name: "machete",
@@ -19,7 +21,9 @@ const MACHETE_CONFIG: ExternalToolConfig<'static> = ExternalToolConfig {
 const MACHETE_ARGS: [&str; 1] = ["machete"];
 const MACHETE_JSON_ARGS: [&str; 2] = ["machete", "--json"];
 
-pub async fn run_unused() -> Result<UnusedOutput> {
+/// Runs cargo-machete over the workspace. Dependencies that are only declared
+/// for platforms outside `targets` are left out of the report.
+pub async fn run_unused(targets: &TargetFilter) -> Result<UnusedOutput> {
     let metadata = MetadataCommand::new().exec().map_err(|err| {
         UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
     })?;
@@ -33,7 +37,11 @@ pub async fn run_unused() -> Result<UnusedOutput> {
             err,
         )
     })?;
-    let (unused, possibly_unused) = parse_machete_output(&stdout)?;
+    let (mut unused, possibly_unused) = parse_machete_output(&stdout)?;
+    if !targets.is_all() {
+        let inactive = inactive_dependencies(&metadata, targets);
+        unused.retain(|dep| !inactive.contains(&dep.name));
+    }
 
     Ok(UnusedOutput {
         unused,
@@ -41,6 +49,25 @@ pub async fn run_unused() -> Result<UnusedOutput> {
     })
 }
 
+/// Names of workspace dependencies whose every declaration is limited to a
+/// platform outside `targets`.
+fn inactive_dependencies(metadata: &Metadata, targets: &TargetFilter) -> HashSet<String> {
+    let mut inactive = HashSet::new();
+    let mut active = HashSet::new();
+    for package in metadata.workspace_packages() {
+        for dep in &package.dependencies {
+            let name = dep.rename.as_ref().unwrap_or(&dep.name).clone();
+            if targets.matches(dep.target.as_ref()) {
+                active.insert(name);
+            } else {
+                inactive.insert(name);
+            }
+        }
+    }
+    inactive.retain(|name| !active.contains(name));
+    inactive
+}
+
 async fn run_machete_json(workspace_root: &Path) -> Result<std::process::Output> {
     run_machete_json_with(workspace_root, |args, root| {
         Box::pin(async move { run_cargo_tool(args, &root, &MACHETE_CONFIG).await })
@@ -356,6 +383,40 @@ mod tests {
     use super::*;
     use std::sync::{Arc, Mutex};
 
+    #[test]
+    fn inactive_dependencies_lists_deps_only_declared_for_other_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nshared = { path = \"shared\" }\n\n[target.'cfg(windows)'.dependencies]\nwinonly = { path = \"winonly\" }\nshared = { path = \"shared\" }\n",
+        );
+        write("src/lib.rs", "");
+        for name in ["shared", "winonly"] {
+            write(
+                &format!("{name}/Cargo.toml"),
+                &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            );
+            write(&format!("{name}/src/lib.rs"), "");
+        }
+        let metadata = MetadataCommand::new()
+            .current_dir(root)
+            .exec()
+            .expect("metadata");
+
+        let linux = TargetFilter::from_args(&["x86_64-unknown-linux-gnu".to_string()]).unwrap();
+        let inactive = inactive_dependencies(&metadata, &linux);
+        assert_eq!(inactive, HashSet::from(["winonly".to_string()]));
+
+        let windows = TargetFilter::from_args(&["x86_64-pc-windows-msvc".to_string()]).unwrap();
+        assert!(inactive_dependencies(&metadata, &windows).is_empty());
+    }
+
     #[test]
     fn parse_machete_output_handles_null() {
         let (unused, possibly_unused) = parse_machete_output("null").expect("parse");
@@ -1,6 +1,11 @@
 //! Shared utilities for analyzer modules.
 
 use serde_json::Value;
+use std::time::Duration;
+
+/// How long a single `cargo check` spawned by an analyzer may run before it is
+/// abandoned, so a stuck build cannot hang the command.
+pub const CARGO_CHECK_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Produces a human-readable description of a JSON value's schema.
 ///
@@ -0,0 +1,223 @@
+//! What each direct dependency of a workspace member costs to build.
+
+use cargo_metadata::{Package, PackageId, TargetKind};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::core::graph::ResolveGraph;
+use crate::core::output::DependencyWeight;
+use crate::core::walk::visit_files;
+
+/// Weighs every direct dependency of `member`. A crate is unique to a
+/// dependency when removing that dependency from `member` would drop it from
+/// the graph reachable from `roots`. Results are ranked by unique crates.
+pub fn dependency_weights(
+    graph: &ResolveGraph<'_>,
+    roots: &[&PackageId],
+    member: &PackageId,
+    sizes: &mut SourceSizes,
+) -> Vec<DependencyWeight> {
+    let reachable = graph.reachable_from_roots(roots, |_, _| false);
+    let mut weights: Vec<DependencyWeight> = graph
+        .edges
+        .get(member)
+        .into_iter()
+        .flatten()
+        .filter_map(|edge| {
+            let package = graph.package(&edge.id)?;
+            let without = graph.reachable_from_roots(roots, |from, candidate| {
+                from == member && candidate.id == edge.id
+            });
+            let unique = reachable.difference(&without).count();
+            // The dependency's own dev-dependencies are never built for `member`.
+            let subtree: Vec<&Package> = graph
+                .reachable_from_roots(&[&edge.id], |_, child| !child.is_normal && !child.is_build)
+                .iter()
+                .filter_map(|id| graph.package(id))
+                .collect();
+
+            Some(DependencyWeight {
+                name: package.name.to_string(),
+                version: package.version.to_string(),
+                kinds: edge.kinds(),
+                unique,
+                shared: subtree.len().saturating_sub(unique),
+                total: subtree.len(),
+                proc_macros: count_with_target(&subtree, &TargetKind::ProcMacro),
+                build_scripts: count_with_target(&subtree, &TargetKind::CustomBuild),
+                source_bytes: subtree.iter().map(|package| sizes.get(package)).sum(),
+            })
+        })
+        .collect();
+
+    weights.sort_by(|left, right| {
+        right
+            .unique
+            .cmp(&left.unique)
+            .then_with(|| right.total.cmp(&left.total))
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    weights
+}
+
+fn count_with_target(packages: &[&Package], kind: &TargetKind) -> usize {
+    packages
+        .iter()
+        .filter(|package| {
+            package
+                .targets
+                .iter()
+                .any(|target| target.kind.contains(kind))
+        })
+        .count()
+}
+
+/// Sizes of unpacked registry sources, measured once per package.
+#[derive(Default)]
+pub struct SourceSizes {
+    bytes: HashMap<PackageId, u64>,
+}
+
+impl SourceSizes {
+    /// Returns the size of the package's sources in the local registry
+    /// cache. Path and git dependencies are not counted.
+    pub fn get(&mut self, package: &Package) -> u64 {
+        let is_registry = package.source.as_ref().is_some_and(|source| {
+            source.repr.starts_with("registry+") || source.repr.starts_with("sparse+")
+        });
+        if !is_registry {
+            return 0;
+        }
+        *self.bytes.entry(package.id.clone()).or_insert_with(|| {
+            package
+                .manifest_path
+                .parent()
+                .map_or(0, |dir| directory_size(dir.as_std_path()))
+        })
+    }
+}
+
+fn directory_size(dir: &Path) -> u64 {
+    let mut total = 0;
+    visit_files(dir, &|_| true, &mut |_, metadata| total += metadata.len());
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::platform::TargetFilter;
+    use cargo_metadata::Metadata;
+    use serde_json::json;
+    use std::fs;
+
+    fn package(name: &str, kind: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": null,
+            "dependencies": [],
+            "targets": [{
+                "name": name,
+                "kind": [kind],
+                "crate_types": [kind],
+                "src_path": format!("/tmp/{name}/src/lib.rs"),
+                "edition": "2021",
+                "doctest": false,
+                "test": false,
+            }],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        })
+    }
+
+    fn node(id: &str, deps: &[&str]) -> serde_json::Value {
+        let node_deps: Vec<serde_json::Value> = deps
+            .iter()
+            .map(|dep| {
+                json!({
+                    "name": dep,
+                    "pkg": format!("{dep} 1.0.0"),
+                    "dep_kinds": [{ "kind": null, "target": null }],
+                })
+            })
+            .collect();
+        json!({
+            "id": format!("{id} 1.0.0"),
+            "deps": node_deps,
+            "dependencies": [],
+            "features": [],
+        })
+    }
+
+    /// app -> serde -> serde_derive (proc-macro) -> syn
+    /// app -> tokio -> syn
+    /// app -> libc (build script)
+    fn metadata() -> Metadata {
+        serde_json::from_value(json!({
+            "packages": [
+                package("app", "lib"),
+                package("serde", "lib"),
+                package("serde_derive", "proc-macro"),
+                package("syn", "lib"),
+                package("tokio", "lib"),
+                package("libc", "custom-build"),
+            ],
+            "workspace_members": ["app 1.0.0"],
+            "resolve": {
+                "root": "app 1.0.0",
+                "nodes": [
+                    node("app", &["serde", "tokio", "libc"]),
+                    node("serde", &["serde_derive"]),
+                    node("serde_derive", &["syn"]),
+                    node("syn", &[]),
+                    node("tokio", &["syn"]),
+                    node("libc", &[]),
+                ],
+            },
+            "target_directory": "/tmp/target",
+            "version": 1,
+            "workspace_root": "/tmp",
+        }))
+        .expect("metadata")
+    }
+
+    #[test]
+    fn dependency_weights_split_unique_and_shared_crates() {
+        let metadata = metadata();
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        let member = &metadata.workspace_members[0];
+
+        let weights = dependency_weights(&graph, &[member], member, &mut SourceSizes::default());
+
+        let names: Vec<&str> = weights.iter().map(|weight| weight.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "tokio", "libc"]);
+
+        let serde = &weights[0];
+        assert_eq!(serde.unique, 2);
+        assert_eq!(serde.shared, 1);
+        assert_eq!(serde.total, 3);
+        assert_eq!(serde.proc_macros, 1);
+        assert_eq!(serde.build_scripts, 0);
+
+        let tokio = &weights[1];
+        assert_eq!(tokio.unique, 1);
+        assert_eq!(tokio.shared, 1);
+
+        assert_eq!(weights[2].build_scripts, 1);
+        // Path sources are not measured.
+        assert_eq!(serde.source_bytes, 0);
+    }
+
+    #[test]
+    fn directory_size_sums_nested_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src/nested")).unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "12345").unwrap();
+        fs::write(temp_dir.path().join("src/nested/lib.rs"), "123").unwrap();
+
+        assert_eq!(directory_size(temp_dir.path()), 8);
+    }
+}
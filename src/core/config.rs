@@ -0,0 +1,174 @@
+//! Project settings read from `[workspace.metadata.upkeep]` and the root
+//! package's `[package.metadata.upkeep]` tables.
+
+use cargo_metadata::Metadata;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct UpkeepConfig {
+    pub licenses: LicensePolicy,
+    pub sources: SourcePolicy,
+}
+
+/// Which licenses dependencies may use.
+///
+/// ```toml
+/// [workspace.metadata.upkeep.licenses]
+/// allow = ["MIT", "Apache-2.0"]
+/// deny = ["GPL-3.0"]
+/// exceptions = [{ crate = "ring", allow = ["OpenSSL"] }]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LicensePolicy {
+    /// When non-empty, only these licenses are accepted.
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub exceptions: Vec<LicenseException>,
+}
+
+/// Extra licenses accepted for a single crate.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LicenseException {
+    #[serde(rename = "crate")]
+    pub name: String,
+    pub allow: Vec<String>,
+}
+
+/// Where dependencies may come from. crates.io is always allowed.
+///
+/// ```toml
+/// [workspace.metadata.upkeep.sources]
+/// registries = ["https://registry.example.com/index/"]
+/// git = ["https://github.com/my-org/"]
+/// allow-branches = false
+/// allow-external-paths = false
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SourcePolicy {
+    /// Index URLs of other registries crates may come from.
+    pub registries: Vec<String>,
+    /// Repository URL prefixes git dependencies may use; when empty, any
+    /// repository is accepted.
+    pub git: Vec<String>,
+    /// Accept git dependencies that follow a branch instead of pinning a
+    /// `tag` or `rev`.
+    pub allow_branches: bool,
+    /// Accept path dependencies outside the workspace root.
+    pub allow_external_paths: bool,
+}
+
+impl SourcePolicy {
+    fn extend(&mut self, other: SourcePolicy) {
+        self.registries.extend(other.registries);
+        self.git.extend(other.git);
+        self.allow_branches |= other.allow_branches;
+        self.allow_external_paths |= other.allow_external_paths;
+    }
+}
+
+impl LicensePolicy {
+    /// Whether any license is restricted; exceptions alone restrict nothing.
+    pub fn is_configured(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty()
+    }
+
+    fn extend(&mut self, other: LicensePolicy) {
+        self.allow.extend(other.allow);
+        self.deny.extend(other.deny);
+        self.exceptions.extend(other.exceptions);
+    }
+}
+
+impl UpkeepConfig {
+    /// Loads the workspace table, then adds the root package's table on top;
+    /// lists from both are combined and either table can enable a flag.
+    pub fn from_metadata(metadata: &Metadata) -> Result<Self> {
+        let mut config = parse_table(&metadata.workspace_metadata, "workspace.metadata.upkeep")?;
+        if let Some(package) = metadata.root_package() {
+            let package_config = parse_table(&package.metadata, "package.metadata.upkeep")?;
+            config.licenses.extend(package_config.licenses);
+            config.sources.extend(package_config.sources);
+        }
+        Ok(config)
+    }
+}
+
+fn parse_table(metadata: &Value, location: &str) -> Result<UpkeepConfig> {
+    match metadata.get("upkeep") {
+        None | Some(Value::Null) => Ok(UpkeepConfig::default()),
+        Some(table) => UpkeepConfig::deserialize(table).map_err(|err| {
+            UpkeepError::context(ErrorCode::Config, format!("invalid [{location}]"), err)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_table_reads_license_policy() {
+        let config = parse_table(
+            &json!({
+                "upkeep": {
+                    "licenses": {
+                        "allow": ["MIT", "Apache-2.0"],
+                        "exceptions": [{ "crate": "ring", "allow": ["OpenSSL"] }],
+                    }
+                }
+            }),
+            "workspace.metadata.upkeep",
+        )
+        .unwrap();
+        assert_eq!(config.licenses.allow, vec!["MIT", "Apache-2.0"]);
+        assert!(config.licenses.deny.is_empty());
+        assert_eq!(config.licenses.exceptions[0].name, "ring");
+
+        assert!(!parse_table(&Value::Null, "workspace.metadata.upkeep")
+            .unwrap()
+            .licenses
+            .is_configured());
+    }
+
+    #[test]
+    fn parse_table_reads_source_policy() {
+        let config = parse_table(
+            &json!({
+                "upkeep": {
+                    "sources": {
+                        "registries": ["https://registry.example.com/index/"],
+                        "allow-branches": true,
+                    }
+                }
+            }),
+            "workspace.metadata.upkeep",
+        )
+        .unwrap();
+        assert_eq!(
+            config.sources.registries,
+            vec!["https://registry.example.com/index/"]
+        );
+        assert!(config.sources.git.is_empty());
+        assert!(config.sources.allow_branches);
+        assert!(!config.sources.allow_external_paths);
+    }
+
+    #[test]
+    fn parse_table_rejects_unknown_keys() {
+        let err = parse_table(
+            &json!({ "upkeep": { "licenses": { "alow": ["MIT"] } } }),
+            "package.metadata.upkeep",
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Config);
+        assert!(err.to_string().contains("package.metadata.upkeep"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Feature selection passed through to `cargo metadata` resolution.
+
+use cargo_metadata::{CargoOpt, Metadata, MetadataCommand, Package};
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+/// The features a build is resolved with, mirroring cargo's `--features`,
+/// `--all-features`, `--no-default-features`, and `-p` flags.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSelection {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    /// Workspace member the selection applies to.
+    pub package: Option<String>,
+}
+
+impl FeatureSelection {
+    /// Returns true when no feature flag was given.
+    pub fn is_default(&self) -> bool {
+        self.features.is_empty() && !self.all_features && !self.no_default_features
+    }
+
+    /// Adds the selection to `command`. Features without a `package/` prefix
+    /// are scoped to [`FeatureSelection::package`] when one is set.
+    pub fn apply(&self, command: &mut MetadataCommand) {
+        if self.all_features {
+            command.features(CargoOpt::AllFeatures);
+        }
+        if self.no_default_features {
+            command.features(CargoOpt::NoDefaultFeatures);
+        }
+        let features = self.scoped_features();
+        if !features.is_empty() {
+            command.features(CargoOpt::SomeFeatures(features));
+        }
+    }
+
+    /// Finds the workspace member named by `-p`, if any.
+    pub fn selected_package<'a>(&self, metadata: &'a Metadata) -> Result<Option<&'a Package>> {
+        let Some(name) = self.package.as_deref() else {
+            return Ok(None);
+        };
+        metadata
+            .workspace_packages()
+            .into_iter()
+            .find(|package| package.name.as_str() == name)
+            .map(Some)
+            .ok_or_else(|| {
+                UpkeepError::message(
+                    ErrorCode::Config,
+                    format!("package `{name}` is not a member of the workspace"),
+                )
+            })
+    }
+
+    fn scoped_features(&self) -> Vec<String> {
+        self.features
+            .iter()
+            .flat_map(|value| value.split([',', ' ']))
+            .filter(|feature| !feature.is_empty())
+            .map(|feature| match &self.package {
+                Some(package) if !feature.contains('/') => format!("{package}/{feature}"),
+                _ => feature.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_features_prefixes_package_and_splits_lists() {
+        let selection = FeatureSelection {
+            features: vec!["a,b".to_string(), "c other/d".to_string()],
+            package: Some("app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            selection.scoped_features(),
+            vec!["app/a", "app/b", "app/c", "other/d"]
+        );
+        assert!(!selection.is_default());
+    }
+
+    #[test]
+    fn scoped_features_keeps_bare_names_without_package() {
+        let selection = FeatureSelection {
+            features: vec!["serde".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(selection.scoped_features(), vec!["serde"]);
+        assert!(FeatureSelection::default().is_default());
+    }
+}
@@ -0,0 +1,340 @@
+//! Dependency graph built from the `cargo metadata` resolve data.
+
+use cargo_metadata::{DepKindInfo, DependencyKind, Metadata, Package, PackageId};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::output::{DependencyType, GraphEdgeKind};
+use crate::core::platform::TargetFilter;
+
+#[derive(Debug, Clone)]
+pub struct Edge {
+    pub id: PackageId,
+    pub is_normal: bool,
+    pub is_dev: bool,
+    pub is_build: bool,
+    /// Every kind the edge is declared with, including its target platform.
+    pub dep_kinds: Vec<DepKindInfo>,
+}
+
+impl Edge {
+    /// Lists each distinct kind/target pair of the edge, treating the unknown
+    /// kind reported by old cargo versions as normal.
+    pub fn kinds(&self) -> Vec<GraphEdgeKind> {
+        let mut kinds: Vec<GraphEdgeKind> = Vec::new();
+        for info in &self.dep_kinds {
+            let kind = GraphEdgeKind {
+                kind: match info.kind {
+                    DependencyKind::Build => DependencyType::Build,
+                    DependencyKind::Development => DependencyType::Dev,
+                    DependencyKind::Normal | DependencyKind::Unknown => DependencyType::Normal,
+                },
+                target: info.target.as_ref().map(ToString::to_string),
+            };
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+}
+
+/// The resolved package graph with package lookups and enabled features.
+pub struct ResolveGraph<'a> {
+    pub packages_by_id: HashMap<PackageId, &'a Package>,
+    pub features_by_id: HashMap<PackageId, Vec<String>>,
+    /// Forward edges from each package to the packages it depends on.
+    pub edges: HashMap<PackageId, Vec<Edge>>,
+}
+
+impl<'a> ResolveGraph<'a> {
+    /// Builds the graph from metadata. Edges that are only dev-dependencies are
+    /// dropped unless `include_dev` is set, and edge kinds limited to platforms
+    /// outside `targets` are dropped along with edges left without any kind.
+    pub fn from_metadata(
+        metadata: &'a Metadata,
+        include_dev: bool,
+        targets: &TargetFilter,
+    ) -> Result<Self> {
+        let resolve = metadata.resolve.as_ref().ok_or_else(|| {
+            UpkeepError::message(ErrorCode::InvalidData, "metadata missing resolve data")
+        })?;
+
+        let packages_by_id = metadata
+            .packages
+            .iter()
+            .map(|package| (package.id.clone(), package))
+            .collect();
+
+        let mut features_by_id = HashMap::new();
+        let mut edges: HashMap<PackageId, Vec<Edge>> = HashMap::new();
+        for node in &resolve.nodes {
+            let features: Vec<String> = node.features.iter().map(ToString::to_string).collect();
+            features_by_id.insert(node.id.clone(), features);
+            for dep in &node.deps {
+                let dep_kinds: Vec<DepKindInfo> = dep
+                    .dep_kinds
+                    .iter()
+                    .filter(|kind| targets.matches(kind.target.as_ref()))
+                    .cloned()
+                    .collect();
+                // Older cargo versions report no kinds at all; keep those edges.
+                if dep_kinds.is_empty() && !dep.dep_kinds.is_empty() {
+                    continue;
+                }
+                if !include_dev && !includes_non_dev(&dep_kinds) {
+                    continue;
+                }
+
+                let is_normal = dep_kinds.iter().any(|kind| {
+                    matches!(kind.kind, DependencyKind::Normal | DependencyKind::Unknown)
+                });
+                let is_dev = dep_kinds
+                    .iter()
+                    .any(|kind| kind.kind == DependencyKind::Development);
+                let is_build = dep_kinds
+                    .iter()
+                    .any(|kind| kind.kind == DependencyKind::Build);
+
+                edges.entry(node.id.clone()).or_default().push(Edge {
+                    id: dep.pkg.clone(),
+                    is_normal,
+                    is_dev,
+                    is_build,
+                    dep_kinds,
+                });
+            }
+        }
+
+        Ok(Self {
+            packages_by_id,
+            features_by_id,
+            edges,
+        })
+    }
+
+    pub fn package(&self, id: &PackageId) -> Option<&'a Package> {
+        self.packages_by_id.get(id).copied()
+    }
+
+    /// Returns the graph with every edge reversed (dependency -> dependent).
+    pub fn inverted(&self) -> HashMap<PackageId, Vec<Edge>> {
+        let mut inverted: HashMap<PackageId, Vec<Edge>> = HashMap::new();
+        for (from, children) in &self.edges {
+            for child in children {
+                inverted.entry(child.id.clone()).or_default().push(Edge {
+                    id: from.clone(),
+                    is_normal: child.is_normal,
+                    is_dev: child.is_dev,
+                    is_build: child.is_build,
+                    dep_kinds: child.dep_kinds.clone(),
+                });
+            }
+        }
+        inverted
+    }
+
+    /// Collects every package reachable from `start`, excluding `start` itself,
+    /// in breadth-first order.
+    pub fn reachable_from(&self, start: &PackageId) -> Vec<&'a Package> {
+        let mut visited: HashSet<&PackageId> = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut reachable = Vec::new();
+
+        while let Some(id) = queue.pop_front() {
+            for edge in self.edges.get(id).into_iter().flatten() {
+                if visited.insert(&edge.id) {
+                    if let Some(package) = self.package(&edge.id) {
+                        reachable.push(package);
+                    }
+                    queue.push_back(&edge.id);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Collects the packages reachable from `roots`, including the roots.
+    /// Dev-dependency edges are only followed from the roots, as cargo never
+    /// builds a dependency's dev-dependencies, and edges for which `skip`
+    /// returns true are not followed at all.
+    pub fn reachable_from_roots<F>(&self, roots: &[&PackageId], skip: F) -> HashSet<PackageId>
+    where
+        F: Fn(&PackageId, &Edge) -> bool,
+    {
+        let mut visited: HashSet<PackageId> = roots.iter().map(|id| (*id).clone()).collect();
+        let mut queue: VecDeque<(&PackageId, bool)> = roots.iter().map(|id| (*id, true)).collect();
+
+        while let Some((id, is_root)) = queue.pop_front() {
+            for edge in self.edges.get(id).into_iter().flatten() {
+                let dev_only = !edge.is_normal && !edge.is_build;
+                if (dev_only && !is_root) || skip(id, edge) {
+                    continue;
+                }
+                if visited.insert(edge.id.clone()) {
+                    queue.push_back((&edge.id, false));
+                }
+            }
+        }
+        visited
+    }
+}
+
+fn includes_non_dev(dep_kinds: &[DepKindInfo]) -> bool {
+    dep_kinds.iter().any(|kind| match kind.kind {
+        DependencyKind::Development => false,
+        DependencyKind::Normal | DependencyKind::Build | DependencyKind::Unknown => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn package(name: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": null,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        })
+    }
+
+    fn node_dep(name: &str, kind: Option<&str>) -> serde_json::Value {
+        json!({
+            "name": name,
+            "pkg": format!("{name} 1.0.0"),
+            "dep_kinds": [{ "kind": kind, "target": null }],
+        })
+    }
+
+    fn metadata() -> Metadata {
+        serde_json::from_value(metadata_value()).expect("metadata")
+    }
+
+    fn metadata_value() -> serde_json::Value {
+        json!({
+            "packages": [package("app"), package("lib"), package("leaf"), package("tester")],
+            "workspace_members": ["app 1.0.0"],
+            "resolve": {
+                "root": "app 1.0.0",
+                "nodes": [
+                    {
+                        "id": "app 1.0.0",
+                        "deps": [node_dep("lib", None), node_dep("tester", Some("dev"))],
+                        "dependencies": ["lib 1.0.0", "tester 1.0.0"],
+                        "features": ["default"],
+                    },
+                    {
+                        "id": "lib 1.0.0",
+                        "deps": [node_dep("leaf", Some("build"))],
+                        "dependencies": ["leaf 1.0.0"],
+                        "features": [],
+                    },
+                    { "id": "leaf 1.0.0", "deps": [], "dependencies": [], "features": [] },
+                    { "id": "tester 1.0.0", "deps": [], "dependencies": [], "features": [] },
+                ],
+            },
+            "target_directory": "/tmp/target",
+            "version": 1,
+            "workspace_root": "/tmp",
+        })
+    }
+
+    fn names(packages: &[&Package]) -> Vec<String> {
+        packages.iter().map(|pkg| pkg.name.to_string()).collect()
+    }
+
+    #[test]
+    fn reachable_from_skips_dev_edges_unless_requested() {
+        let metadata = metadata();
+        let root = metadata.workspace_members[0].clone();
+
+        let graph = ResolveGraph::from_metadata(&metadata, false, &TargetFilter::all()).unwrap();
+        assert_eq!(names(&graph.reachable_from(&root)), vec!["lib", "leaf"]);
+
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        assert_eq!(
+            names(&graph.reachable_from(&root)),
+            vec!["lib", "tester", "leaf"]
+        );
+    }
+
+    #[test]
+    fn reachable_from_roots_follows_dev_edges_from_roots_only() {
+        let metadata = metadata();
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        let roots = [&metadata.workspace_members[0]];
+
+        let mut reachable: Vec<String> = graph
+            .reachable_from_roots(&roots, |_, _| false)
+            .into_iter()
+            .map(|id| id.repr)
+            .collect();
+        reachable.sort();
+        assert_eq!(
+            reachable,
+            vec!["app 1.0.0", "leaf 1.0.0", "lib 1.0.0", "tester 1.0.0"]
+        );
+
+        let without_lib = graph.reachable_from_roots(&roots, |_, edge| edge.id.repr == "lib 1.0.0");
+        assert_eq!(without_lib.len(), 2);
+    }
+
+    #[test]
+    fn inverted_reverses_edges_and_keeps_kinds() {
+        let metadata = metadata();
+        let graph = ResolveGraph::from_metadata(&metadata, true, &TargetFilter::all()).unwrap();
+        let inverted = graph.inverted();
+
+        let leaf_parents = &inverted[&PackageId {
+            repr: "leaf 1.0.0".to_string(),
+        }];
+        assert_eq!(leaf_parents.len(), 1);
+        assert_eq!(leaf_parents[0].id.repr, "lib 1.0.0");
+        assert!(leaf_parents[0].is_build);
+        assert!(!leaf_parents[0].is_normal);
+        assert_eq!(
+            graph.features_by_id[&metadata.workspace_members[0]],
+            vec!["default".to_string()]
+        );
+    }
+
+    #[test]
+    fn from_metadata_drops_edges_for_unselected_targets() {
+        let mut value = metadata_value();
+        value["packages"]
+            .as_array_mut()
+            .unwrap()
+            .push(package("winapi"));
+        let app = &mut value["resolve"]["nodes"][0];
+        app["deps"].as_array_mut().unwrap().push(json!({
+            "name": "winapi",
+            "pkg": "winapi 1.0.0",
+            "dep_kinds": [{ "kind": null, "target": "cfg(windows)" }],
+        }));
+        app["dependencies"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!("winapi 1.0.0"));
+        let metadata: Metadata = serde_json::from_value(value).expect("metadata");
+        let root = metadata.workspace_members[0].clone();
+
+        let graph = ResolveGraph::from_metadata(&metadata, false, &TargetFilter::all()).unwrap();
+        assert_eq!(
+            names(&graph.reachable_from(&root)),
+            vec!["lib", "winapi", "leaf"]
+        );
+
+        let linux = TargetFilter::from_args(&["x86_64-unknown-linux-gnu".to_string()]).unwrap();
+        let graph = ResolveGraph::from_metadata(&metadata, false, &linux).unwrap();
+        assert_eq!(names(&graph.reachable_from(&root)), vec!["lib", "leaf"]);
+    }
+}
@@ -0,0 +1,557 @@
+//! Format-preserving edits to Cargo.toml manifests.
+
+use cargo_metadata::DependencyKind;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, DocumentMut, InlineTable, Item, Value};
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+/// Where a package's `rust-version` is declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustVersionSource {
+    /// `package.rust-version` holds a literal version.
+    Package,
+    /// `package.rust-version.workspace = true` defers to `[workspace.package]`.
+    Workspace,
+}
+
+/// Sets `rust-version` for the package in `manifest_path`.
+///
+/// When the package inherits `rust-version` from the workspace nothing is
+/// written and [`RustVersionSource::Workspace`] is returned so the caller can
+/// update the workspace root manifest instead.
+pub fn set_package_rust_version(manifest_path: &Path, version: &str) -> Result<RustVersionSource> {
+    let mut document = read_manifest(manifest_path)?;
+    let package = document
+        .get_mut("package")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("{} has no [package] table", manifest_path.display()),
+            )
+        })?;
+
+    if package
+        .get("rust-version")
+        .is_some_and(is_workspace_inherited)
+    {
+        return Ok(RustVersionSource::Workspace);
+    }
+
+    package.insert("rust-version", value(version));
+    write_manifest(manifest_path, &document)?;
+    Ok(RustVersionSource::Package)
+}
+
+/// Sets `workspace.package.rust-version` in the workspace root manifest.
+pub fn set_workspace_rust_version(manifest_path: &Path, version: &str) -> Result<()> {
+    let mut document = read_manifest(manifest_path)?;
+    let workspace = document
+        .get_mut("workspace")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("{} has no [workspace] table", manifest_path.display()),
+            )
+        })?;
+
+    let package = workspace
+        .entry("package")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!(
+                    "workspace.package in {} is not a table",
+                    manifest_path.display()
+                ),
+            )
+        })?;
+    package.insert("rust-version", value(version));
+    write_manifest(manifest_path, &document)
+}
+
+/// A dependency table such as `[dev-dependencies]` or
+/// `[target.'cfg(unix)'.dependencies]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DependencyTable {
+    pub kind: DependencyKind,
+    pub target: Option<String>,
+}
+
+impl DependencyTable {
+    fn key(&self) -> &'static str {
+        match self.kind {
+            DependencyKind::Development => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+            _ => "dependencies",
+        }
+    }
+
+    fn get<'a>(&self, document: &'a DocumentMut) -> Option<&'a dyn toml_edit::TableLike> {
+        let parent = match &self.target {
+            Some(target) => document
+                .get("target")?
+                .as_table_like()?
+                .get(target)?
+                .as_table_like()?,
+            None => document.as_table(),
+        };
+        parent.get(self.key())?.as_table_like()
+    }
+
+    fn get_mut<'a>(
+        &self,
+        document: &'a mut DocumentMut,
+    ) -> Option<&'a mut dyn toml_edit::TableLike> {
+        let parent = match &self.target {
+            Some(target) => document
+                .get_mut("target")?
+                .as_table_like_mut()?
+                .get_mut(target)?
+                .as_table_like_mut()?,
+            None => document.as_table_mut(),
+        };
+        parent.get_mut(self.key())?.as_table_like_mut()
+    }
+}
+
+/// A crate to move into `[workspace.dependencies]`.
+#[derive(Debug, Clone)]
+pub struct Hoist {
+    pub name: String,
+    pub requirement: String,
+    /// The alternate registry the crate comes from; `None` for crates.io.
+    pub registry: Option<String>,
+    pub default_features: bool,
+    /// Member manifests and the tables declaring the crate there.
+    pub declarations: Vec<(PathBuf, DependencyTable)>,
+}
+
+/// Dependencies in `manifest_path` declared with `workspace = true`, keyed by
+/// table and dependency name.
+pub fn inherited_dependencies(manifest_path: &Path) -> Result<HashSet<(DependencyTable, String)>> {
+    let document = read_manifest(manifest_path)?;
+    Ok(dependency_entries(&document)
+        .into_iter()
+        .filter(|(_, _, entry)| is_workspace_inherited(entry))
+        .map(|(table, name, _)| (table, name.to_string()))
+        .collect())
+}
+
+/// The `registry` named by each dependency in `manifest_path` that does not
+/// come from crates.io, keyed by table and dependency name.
+pub fn dependency_registries(
+    manifest_path: &Path,
+) -> Result<HashMap<(DependencyTable, String), String>> {
+    let document = read_manifest(manifest_path)?;
+    Ok(dependency_entries(&document)
+        .into_iter()
+        .filter_map(|(table, name, entry)| {
+            let registry = entry.as_table_like()?.get("registry")?.as_str()?;
+            Some(((table, name.to_string()), registry.to_string()))
+        })
+        .collect())
+}
+
+/// Every entry of the dependency tables in `document`, including
+/// platform-specific ones, with the table declaring it.
+fn dependency_entries(document: &DocumentMut) -> Vec<(DependencyTable, &str, &Item)> {
+    let mut tables = vec![None];
+    if let Some(targets) = document.get("target").and_then(Item::as_table_like) {
+        tables.extend(targets.iter().map(|(target, _)| Some(target.to_string())));
+    }
+
+    let mut entries = Vec::new();
+    for target in tables {
+        for kind in [
+            DependencyKind::Normal,
+            DependencyKind::Development,
+            DependencyKind::Build,
+        ] {
+            let table = DependencyTable {
+                kind,
+                target: target.clone(),
+            };
+            let Some(declared) = table.get(document) else {
+                continue;
+            };
+            for (name, entry) in declared.iter() {
+                entries.push((table.clone(), name, entry));
+            }
+        }
+    }
+    entries
+}
+
+/// A string field of `[workspace.package]` in `root_manifest`, such as
+/// `edition`, if the workspace declares one.
+pub fn workspace_package_field(root_manifest: &Path, key: &str) -> Result<Option<String>> {
+    let document = read_manifest(root_manifest)?;
+    Ok(document
+        .get("workspace")
+        .and_then(Item::as_table_like)
+        .and_then(|workspace| workspace.get("package"))
+        .and_then(Item::as_table_like)
+        .and_then(|package| package.get(key))
+        .and_then(Item::as_str)
+        .map(ToString::to_string))
+}
+
+/// The `[features]` table written in `manifest_path`. Unlike `cargo metadata`
+/// it leaves out the features cargo adds implicitly for optional dependencies.
+pub fn declared_features(manifest_path: &Path) -> Result<BTreeMap<String, Vec<String>>> {
+    let document = read_manifest(manifest_path)?;
+    let Some(features) = document.get("features").and_then(Item::as_table_like) else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(features
+        .iter()
+        .map(|(name, entries)| {
+            let entries = entries
+                .as_array()
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .filter_map(|entry| entry.as_str().map(ToString::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            (name.to_string(), entries)
+        })
+        .collect())
+}
+
+/// An entry of `[workspace.dependencies]`.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceDependency {
+    /// The version requirement, empty when the entry has none.
+    pub requirement: String,
+    pub registry: Option<String>,
+}
+
+/// Entries of `[workspace.dependencies]` in `root_manifest`.
+pub fn workspace_dependencies(
+    root_manifest: &Path,
+) -> Result<HashMap<String, WorkspaceDependency>> {
+    let document = read_manifest(root_manifest)?;
+    let Some(entries) = document
+        .get("workspace")
+        .and_then(Item::as_table_like)
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(Item::as_table_like)
+    else {
+        return Ok(HashMap::new());
+    };
+    Ok(entries
+        .iter()
+        .map(|(name, entry)| {
+            let table = entry.as_table_like();
+            let field = |key: &str| {
+                table
+                    .and_then(|table| table.get(key))
+                    .and_then(Item::as_str)
+                    .map(ToString::to_string)
+            };
+            let dependency = WorkspaceDependency {
+                requirement: entry
+                    .as_str()
+                    .map(ToString::to_string)
+                    .or_else(|| field("version"))
+                    .unwrap_or_default(),
+                registry: field("registry"),
+            };
+            (name.to_string(), dependency)
+        })
+        .collect())
+}
+
+/// Adds `hoist` to `[workspace.dependencies]` in `root_manifest` unless it is
+/// already there, and points each member declaration at it. Member
+/// `features` and `optional` are kept; the version and registry move to the
+/// workspace.
+pub fn hoist_dependency(root_manifest: &Path, hoist: &Hoist) -> Result<()> {
+    let mut document = read_manifest(root_manifest)?;
+    let workspace = document
+        .get_mut("workspace")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("{} has no [workspace] table", root_manifest.display()),
+            )
+        })?;
+    let dependencies = workspace
+        .entry("dependencies")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!(
+                    "workspace.dependencies in {} is not a table",
+                    root_manifest.display()
+                ),
+            )
+        })?;
+    if !dependencies.contains_key(&hoist.name) {
+        let entry = if hoist.default_features && hoist.registry.is_none() {
+            value(&hoist.requirement)
+        } else {
+            let mut entry = InlineTable::new();
+            entry.insert("version", Value::from(hoist.requirement.as_str()));
+            if let Some(registry) = &hoist.registry {
+                entry.insert("registry", Value::from(registry.as_str()));
+            }
+            if !hoist.default_features {
+                entry.insert("default-features", Value::from(false));
+            }
+            value(entry)
+        };
+        dependencies.insert(&hoist.name, entry);
+    }
+    write_manifest(root_manifest, &document)?;
+
+    for (manifest_path, table) in &hoist.declarations {
+        // The root manifest can also be a member.
+        let mut document = read_manifest(manifest_path)?;
+        let entries = table.get_mut(&mut document).ok_or_else(|| {
+            UpkeepError::message(
+                ErrorCode::InvalidData,
+                format!("{} has no [{}] table", manifest_path.display(), table.key()),
+            )
+        })?;
+        let Some(entry) = entries
+            .get_mut(&hoist.name)
+            .filter(|entry| !is_workspace_inherited(entry))
+        else {
+            continue;
+        };
+        inherit_entry(entry);
+        write_manifest(manifest_path, &document)?;
+    }
+    Ok(())
+}
+
+/// Rewrites a member's dependency entry to `{ workspace = true, ... }`.
+fn inherit_entry(entry: &mut Item) {
+    const MOVED: [&str; 3] = ["version", "registry", "default-features"];
+    if let Some(table) = entry.as_table_mut() {
+        for key in MOVED {
+            table.remove(key);
+        }
+        table.insert("workspace", value(true));
+        return;
+    }
+    let mut inherited = InlineTable::new();
+    inherited.insert("workspace", Value::from(true));
+    if let Some(existing) = entry.as_inline_table() {
+        for (key, item) in existing.iter() {
+            if !MOVED.contains(&key) {
+                inherited.insert(key, item.clone());
+            }
+        }
+    }
+    inherited.fmt();
+    let mut inherited = Value::InlineTable(inherited);
+    if let Some(existing) = entry.as_value() {
+        // Keeps trailing comments on the line.
+        *inherited.decor_mut() = existing.decor().clone();
+    }
+    *entry = Item::Value(inherited);
+}
+
+fn is_workspace_inherited(item: &Item) -> bool {
+    item.as_table_like()
+        .and_then(|table| table.get("workspace"))
+        .and_then(Item::as_bool)
+        .unwrap_or(false)
+}
+
+fn read_manifest(path: &Path) -> Result<DocumentMut> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Io,
+            format!("failed to read {}", path.display()),
+            err,
+        )
+    })?;
+    contents.parse::<DocumentMut>().map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::InvalidData,
+            format!("failed to parse {}", path.display()),
+            err,
+        )
+    })
+}
+
+fn write_manifest(path: &Path, document: &DocumentMut) -> Result<()> {
+    fs::write(path, document.to_string()).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Io,
+            format!("failed to write {}", path.display()),
+            err,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn set_package_rust_version_preserves_formatting() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        fs::write(
+            &path,
+            "[package]\nname = \"app\" # the app\nversion = \"0.1.0\"\nrust-version = \"1.60\"\n\n[dependencies]\n",
+        )
+        .unwrap();
+
+        let source = set_package_rust_version(&path, "1.74").unwrap();
+
+        assert_eq!(source, RustVersionSource::Package);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[package]\nname = \"app\" # the app\nversion = \"0.1.0\"\nrust-version = \"1.74\"\n\n[dependencies]\n"
+        );
+    }
+
+    #[test]
+    fn set_package_rust_version_defers_to_workspace() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        let original = "[package]\nname = \"app\"\nrust-version.workspace = true\n";
+        fs::write(&path, original).unwrap();
+
+        let source = set_package_rust_version(&path, "1.74").unwrap();
+
+        assert_eq!(source, RustVersionSource::Workspace);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn set_workspace_rust_version_creates_package_table() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        fs::write(&path, "[workspace]\nmembers = [\"app\"]\n").unwrap();
+
+        set_workspace_rust_version(&path, "1.80").unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let document = contents.parse::<DocumentMut>().unwrap();
+        assert_eq!(
+            document["workspace"]["package"]["rust-version"].as_str(),
+            Some("1.80")
+        );
+    }
+
+    #[test]
+    fn hoist_dependency_moves_version_to_workspace() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("Cargo.toml");
+        let member = dir.path().join("app.toml");
+        fs::write(&root, "[workspace]\nmembers = [\"app\"]\n").unwrap();
+        fs::write(
+            &member,
+            "[package]\nname = \"app\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] } # keep\nlog = \"0.4\"\n\n[target.'cfg(unix)'.dev-dependencies]\nserde = \"1.0.100\"\n",
+        )
+        .unwrap();
+        let unix_dev = DependencyTable {
+            kind: DependencyKind::Development,
+            target: Some("cfg(unix)".to_string()),
+        };
+
+        hoist_dependency(
+            &root,
+            &Hoist {
+                name: "serde".to_string(),
+                requirement: "1.0.100".to_string(),
+                registry: None,
+                default_features: true,
+                declarations: vec![
+                    (
+                        member.clone(),
+                        DependencyTable {
+                            kind: DependencyKind::Normal,
+                            target: None,
+                        },
+                    ),
+                    (member.clone(), unix_dev.clone()),
+                ],
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&root).unwrap(),
+            "[workspace]\nmembers = [\"app\"]\n\n[workspace.dependencies]\nserde = \"1.0.100\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&member).unwrap(),
+            "[package]\nname = \"app\"\n\n[dependencies]\nserde = { workspace = true, features = [\"derive\"] } # keep\nlog = \"0.4\"\n\n[target.'cfg(unix)'.dev-dependencies]\nserde = { workspace = true }\n"
+        );
+        let inherited = inherited_dependencies(&member).unwrap();
+        assert!(inherited.contains(&(unix_dev, "serde".to_string())));
+        assert_eq!(inherited.len(), 2);
+    }
+
+    #[test]
+    fn hoist_dependency_keeps_the_registry() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("Cargo.toml");
+        let member = dir.path().join("app.toml");
+        fs::write(&root, "[workspace]\nmembers = [\"app\"]\n").unwrap();
+        fs::write(
+            &member,
+            "[package]\nname = \"app\"\n\n[dependencies]\ninternal = { version = \"0.3\", registry = \"corp\" }\n",
+        )
+        .unwrap();
+        let normal = DependencyTable {
+            kind: DependencyKind::Normal,
+            target: None,
+        };
+        assert_eq!(
+            dependency_registries(&member)
+                .unwrap()
+                .get(&(normal.clone(), "internal".to_string())),
+            Some(&"corp".to_string())
+        );
+
+        hoist_dependency(
+            &root,
+            &Hoist {
+                name: "internal".to_string(),
+                requirement: "0.3".to_string(),
+                registry: Some("corp".to_string()),
+                default_features: true,
+                declarations: vec![(member.clone(), normal)],
+            },
+        )
+        .unwrap();
+
+        let workspace = workspace_dependencies(&root).unwrap();
+        assert_eq!(workspace["internal"].requirement, "0.3");
+        assert_eq!(workspace["internal"].registry.as_deref(), Some("corp"));
+        assert!(fs::read_to_string(&member)
+            .unwrap()
+            .contains("internal = { workspace = true }"));
+    }
+
+    #[test]
+    fn set_workspace_rust_version_requires_workspace() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        fs::write(&path, "[package]\nname = \"app\"\n").unwrap();
+
+        let err = set_workspace_rust_version(&path, "1.80").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidData);
+    }
+}
@@ -1,6 +1,16 @@
 //! Core analysis modules and shared types.
 
 pub mod analyzers;
+pub mod config;
 pub mod error;
+pub mod features;
+pub mod graph;
+pub mod manifest;
 pub mod output;
+pub mod pattern;
+pub mod platform;
+pub mod sbom;
 pub mod scorers;
+pub mod source;
+pub mod spdx;
+pub mod walk;
@@ -31,6 +31,9 @@ pub struct DepsOutput {
     pub packages: Vec<OutdatedPackage>,
     pub skipped: usize,
     pub skipped_packages: Vec<SkippedDependency>,
+    /// Staleness of direct git dependencies, which are also listed as skipped.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub git_dependencies: Vec<GitDependency>,
     pub warnings: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub security: Option<DepsSecurityOutput>,
@@ -98,6 +101,458 @@ pub struct TreeNode {
     pub duplicate: bool,
 }
 
+/// The resolved dependency graph as flat node and edge lists.
+#[derive(Debug, Serialize)]
+pub struct GraphOutput {
+    pub packages: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+    pub license: Option<String>,
+    /// Features enabled on this package in the resolve.
+    pub features: Vec<String>,
+    pub kind: GraphNodeKind,
+    pub duplicate: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kinds: Vec<GraphEdgeKind>,
+    /// Features the dependent enables on the dependency through this edge,
+    /// including `default` unless it opts out of default features.
+    pub features: Vec<String>,
+    /// The name the dependent uses for this dependency when renamed.
+    pub renamed_as: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdgeKind {
+    pub kind: DependencyType,
+    /// The `cfg(...)` or target triple the dependency is limited to.
+    pub target: Option<String>,
+}
+
+/// Every path from a workspace member to a crate.
+#[derive(Debug, Serialize)]
+pub struct WhyOutput {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// Resolved versions of the crate that match the query.
+    pub versions: Vec<String>,
+    pub paths: Vec<WhyPath>,
+    /// Set when more paths exist than were listed.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WhyPath {
+    pub member: String,
+    pub hops: Vec<WhyHop>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WhyHop {
+    pub from: String,
+    pub to: String,
+    pub version: String,
+    pub kinds: Vec<GraphEdgeKind>,
+    pub optional: bool,
+    /// Features on `from` that enabled this optional dependency.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub enabled_by: Vec<String>,
+}
+
+/// Changes between two `Cargo.lock` files.
+#[derive(Debug, Serialize)]
+pub struct LockDiffOutput {
+    pub old: String,
+    pub new: String,
+    pub added: Vec<LockPackage>,
+    pub removed: Vec<LockPackage>,
+    pub upgraded: Vec<LockChange>,
+    pub downgraded: Vec<LockChange>,
+    /// Advisories affecting the new lockfile that did not affect the old one.
+    pub advisories: Vec<Vulnerability>,
+    /// Crates that gained an extra version.
+    pub new_duplicates: Vec<LockDuplicate>,
+    /// Registries and git repositories the old lockfile did not use.
+    pub new_sources: Vec<LockSource>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockPackage {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub update_type: UpdateType,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockDuplicate {
+    pub name: String,
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LockSource {
+    /// `registry`, `git`, or another cargo source kind.
+    pub kind: String,
+    pub url: String,
+    pub packages: Vec<String>,
+}
+
+/// Build cost of each direct dependency of the workspace members.
+#[derive(Debug, Serialize)]
+pub struct WeightOutput {
+    pub members: Vec<WeightMember>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeightMember {
+    pub name: String,
+    pub dependencies: Vec<DependencyWeight>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyWeight {
+    pub name: String,
+    pub version: String,
+    pub kinds: Vec<GraphEdgeKind>,
+    /// Crates that would leave the graph if this dependency were removed.
+    pub unique: usize,
+    /// Crates in the dependency's subtree that are also used elsewhere.
+    pub shared: usize,
+    /// Crates in the dependency's subtree, including itself.
+    pub total: usize,
+    pub proc_macros: usize,
+    pub build_scripts: usize,
+    /// Summed size of the subtree's sources in the local registry cache.
+    pub source_bytes: u64,
+}
+
+/// Crates resolved at more than one version, ranked by build impact.
+#[derive(Debug, Serialize)]
+pub struct DuplicatesOutput {
+    pub duplicates: Vec<DuplicateCrate>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateCrate {
+    pub name: String,
+    /// Resolved versions in ascending order; the last one is the unification target.
+    pub versions: Vec<DuplicateVersion>,
+    /// Crates built only because of the older versions, including themselves.
+    pub impact: usize,
+    pub suggestions: Vec<UnifySuggestion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateVersion {
+    pub version: String,
+    pub parents: Vec<DuplicateParent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateParent {
+    pub name: String,
+    pub version: String,
+    /// The requirement the parent declares on the duplicated crate.
+    pub req: Option<String>,
+    pub workspace_member: bool,
+}
+
+/// A change to a parent of an older version that would move it to the newest.
+#[derive(Debug, Serialize)]
+pub struct UnifySuggestion {
+    pub parent: String,
+    pub parent_version: String,
+    /// Newer release of the parent; `None` for workspace members, whose
+    /// manifest is edited instead.
+    pub upgrade_to: Option<String>,
+    /// Requirement on the duplicated crate after the change; `None` when the
+    /// release no longer depends on it.
+    pub requirement: Option<String>,
+    pub unifies: bool,
+}
+
+/// Public API changes of each checked crate against its release baseline.
+#[derive(Debug, Serialize)]
+pub struct SemverOutput {
+    pub crates: Vec<SemverCrate>,
+    /// Crates that could not be checked, with the reason.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SemverCrate {
+    pub name: String,
+    /// Version of the baseline the current tree was compared against.
+    pub baseline: Option<String>,
+    pub current: String,
+    /// The release level the version in Cargo.toml amounts to.
+    pub bump: ReleaseBump,
+    /// The release level the detected API changes call for.
+    pub required: ReleaseBump,
+    pub sufficient: bool,
+    pub violations: Vec<SemverViolation>,
+}
+
+/// A failed cargo-semver-checks lint.
+#[derive(Debug, Serialize)]
+pub struct SemverViolation {
+    pub lint: String,
+    pub summary: String,
+    /// Items the lint failed for, as reported by the tool.
+    pub locations: Vec<String>,
+}
+
+/// Licenses of the resolved dependency graph checked against the configured policy.
+#[derive(Debug, Serialize)]
+pub struct LicensesOutput {
+    /// Whether an allow or deny policy is configured.
+    pub policy: bool,
+    pub groups: Vec<LicenseGroup>,
+    pub violations: Vec<LicenseViolation>,
+}
+
+/// Crates sharing a license expression, in canonical SPDX form.
+#[derive(Debug, Serialize)]
+pub struct LicenseGroup {
+    pub license: String,
+    pub crates: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LicenseViolation {
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub status: LicenseStatus,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourcesOutput {
+    /// Every dependency in the resolved graph and where it comes from.
+    pub packages: Vec<SourcePackage>,
+    pub violations: Vec<SourceViolation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourcePackage {
+    pub name: String,
+    pub version: String,
+    pub kind: DependencySource,
+    /// Registry index or git repository URL, or the directory of a path crate.
+    pub location: Option<String>,
+    /// `branch=...`, `tag=...` or `rev=...` for git dependencies; none means
+    /// the repository's default branch.
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceViolation {
+    pub name: String,
+    pub version: String,
+    pub kind: DependencySource,
+    pub location: Option<String>,
+    pub reason: String,
+    /// Workspace members that pull the crate in.
+    pub introduced_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PatchesOutput {
+    pub patches: Vec<PatchEntry>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PatchEntry {
+    pub kind: PatchKind,
+    /// The registry or git URL a `[patch]` applies to, or the package spec a
+    /// `[replace]` entry overrides.
+    pub target: String,
+    pub name: String,
+    /// Where the replacement comes from: a path, a git repository or a
+    /// version requirement.
+    pub source: String,
+    /// Version of the replacement crate, from the resolve or Cargo.lock.
+    pub version: Option<String>,
+    /// Cargo ignores patches whose version does not match any requirement.
+    pub used: bool,
+    /// Resolved packages that depend on the patched crate.
+    pub affects: Vec<String>,
+    /// Newest crates.io release of the patched crate.
+    pub upstream: Option<String>,
+    /// Upstream has published a version newer than the patched one.
+    pub superseded: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchKind {
+    Patch,
+    Replace,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InheritOutput {
+    pub candidates: Vec<InheritCandidate>,
+    /// Crates moved into `[workspace.dependencies]` by `--apply`.
+    pub applied: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InheritCandidate {
+    pub name: String,
+    /// Requirement for `[workspace.dependencies]`: the existing entry, or the
+    /// member requirement with the highest minimum version.
+    pub requirement: String,
+    /// The alternate registry the members use; `None` for crates.io.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Already listed in `[workspace.dependencies]`.
+    pub in_workspace: bool,
+    pub declarations: Vec<InheritDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InheritDeclaration {
+    pub member: String,
+    pub dependency_type: DependencyType,
+    pub target: Option<String>,
+    pub requirement: String,
+    pub features: Vec<String>,
+    pub default_features: bool,
+    /// Declared with `workspace = true`.
+    pub inherited: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsistencyOutput {
+    pub findings: Vec<ConsistencyFinding>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsistencyFinding {
+    pub kind: ConsistencyKind,
+    /// The dependency, or `edition` / `rust-version` for package settings.
+    pub name: String,
+    /// The workspace default members are compared against, for package
+    /// settings.
+    pub expected: Option<String>,
+    pub members: Vec<MemberValue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberValue {
+    pub member: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyKind {
+    /// Requirements with no version in common, so each gets its own copy.
+    IncompatibleRequirements,
+    DefaultFeatures,
+    Features,
+    /// Renamed in some members and not in others, or under different names.
+    Rename,
+    Edition,
+    RustVersion,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeaturesOutput {
+    pub members: Vec<MemberFeatures>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberFeatures {
+    pub name: String,
+    pub features: Vec<String>,
+    /// Features no code checks with `cfg(feature = ...)` and that enable no
+    /// dependency, directly or through other features.
+    pub unreferenced: Vec<String>,
+    /// Optional dependencies that no feature in the manifest turns on, so
+    /// only the implicit feature cargo adds for them reaches them.
+    pub ungated_optional: Vec<String>,
+    pub invalid: Vec<InvalidFeatureEntry>,
+    /// Dependencies of a library whose default features are all enabled
+    /// explicitly or forwarded by the library's own features, so
+    /// `default-features = false` would lose nothing.
+    pub default_features: Vec<DefaultFeaturesHint>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub powerset: Vec<FeatureCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvalidFeatureEntry {
+    pub feature: String,
+    pub entry: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DefaultFeaturesHint {
+    pub dependency: String,
+    pub defaults: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureCheck {
+    /// Enabled on top of `--no-default-features`.
+    pub features: Vec<String>,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Dependencies that run code while the workspace builds.
+#[derive(Debug, Serialize)]
+pub struct BuildTimeOutput {
+    pub crates: Vec<BuildTimeCrate>,
+    /// File the inventory was compared against, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuildTimeCrate {
+    pub name: String,
+    pub version: String,
+    pub source: DependencySource,
+    pub build_script: bool,
+    pub proc_macro: bool,
+    /// The `links` key: the native library the build script links.
+    pub links: Option<String>,
+    /// Direct dependencies of the workspace that pull the crate in.
+    pub via: Vec<String>,
+    /// Not listed in the baseline.
+    pub new: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TreeStats {
     pub total_crates: usize,
@@ -137,8 +592,79 @@ pub struct QualityOutput {
 #[derive(Debug, Serialize)]
 pub struct MetricScore {
     pub name: String,
-    pub score: f32,
+    /// The metric score (0-100), or `None` when the metric was not scored.
+    pub score: Option<f32>,
+    /// The effective weight after renormalizing over scored metrics.
     pub weight: f32,
+    pub status: MetricStatus,
+    /// Why the metric is unavailable, when its analyzer failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MsrvOutput {
+    pub members: Vec<MsrvMember>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MsrvMember {
+    pub name: String,
+    /// The `rust-version` declared in the member manifest, if any.
+    pub declared: Option<String>,
+    pub state: MsrvState,
+    /// Dependencies whose `rust-version` is newer than the declared MSRV.
+    pub incompatible: Vec<MsrvDependency>,
+    /// Result of building under the declared toolchain, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<ToolchainCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MsrvDependency {
+    pub name: String,
+    pub version: String,
+    pub rust_version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EffectiveMsrvOutput {
+    pub members: Vec<EffectiveMsrvMember>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EffectiveMsrvMember {
+    pub name: String,
+    pub declared: Option<String>,
+    /// The highest `rust-version` across the member's dependencies.
+    pub effective: Option<String>,
+    /// Dependencies whose `rust-version` sets the effective MSRV.
+    pub forced_by: Vec<MsrvDependency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downgrade: Option<MsrvDowngrade>,
+    /// The manifest updated by `--write`, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub written: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MsrvDowngrade {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    /// The `rust-version` declared by the suggested release.
+    pub rust_version: String,
+    /// The effective MSRV after applying the downgrade.
+    pub resulting_msrv: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolchainCheck {
+    pub toolchain: String,
+    pub status: ToolchainCheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -168,6 +694,53 @@ pub struct OutdatedPackage {
     pub required: String,
     pub update_type: UpdateType,
     pub dependency_type: DependencyType,
+    /// Newest release compatible with the declared `rust-version` of the
+    /// members that use this dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_compatible: Option<String>,
+    /// The `rust-version` required by `latest`, if it declares one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_rust_version: Option<String>,
+    /// True when updating to `latest` would require a newer compiler than the
+    /// declared MSRV.
+    pub raises_msrv: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitDependency {
+    pub name: String,
+    pub alias: Option<String>,
+    pub dependency_type: DependencyType,
+    pub url: String,
+    /// `branch=...`, `tag=...` or `rev=...`; none follows the default branch.
+    pub reference: Option<String>,
+    /// The commit locked in Cargo.lock.
+    pub pinned: Option<String>,
+    pub status: GitStatus,
+    /// Tip of the followed branch, or of the default branch for tag and rev
+    /// pins.
+    pub latest_commit: Option<String>,
+    /// Commits on the followed branch since the pinned one; only counted for
+    /// branch pins that are behind.
+    pub commits_behind: Option<usize>,
+    /// Newest version-like tag in the repository.
+    pub latest_tag: Option<String>,
+    /// Tag pointing at the pinned commit, if any.
+    pub pinned_tag: Option<String>,
+    /// Newest crates.io release of a crate with the same name.
+    pub registry_alternative: Option<String>,
+    /// Why the repository could not be checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitStatus {
+    UpToDate,
+    /// The followed branch moved on, or a newer tag exists.
+    Behind,
+    Unknown,
 }
 
 #[derive(Debug, Serialize)]
@@ -248,24 +821,91 @@ pub enum SkipReason {
     RegistryUnavailable,
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum Grade {
-    A,
-    B,
-    C,
-    D,
-    F,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricStatus {
+    Measured,
+    Unavailable,
+    Skipped,
 }
 
-/// Confidence level for detection results.
-///
-/// Higher confidence indicates more reliable detection:
-/// - `High`: JSON-parsed output from tools with structured output
-/// - `Medium`: Text-parsed output from tools without structured output
-/// - `Low`: Heuristic-based detection or fallback parsing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    WorkspaceMember,
+    ProcMacro,
+    Build,
+    Dev,
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MsrvState {
+    Valid,
+    Missing,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolchainCheckStatus {
+    Passed,
+    Failed,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseStatus {
+    /// No acceptable choice of licenses under the policy.
+    Denied,
+    /// Neither `license` nor `license-file` is set.
+    Unlicensed,
+    /// `license` is not a valid SPDX expression.
+    Invalid,
+    /// Only `license-file` is set, so the policy cannot be checked.
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencySource {
+    CratesIo,
+    /// A registry other than crates.io.
+    Registry,
+    Git,
+    Path,
+}
+
+/// A release level; pre-1.0 crates shift each level one component right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseBump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+/// Confidence level for detection results.
+///
+/// Higher confidence indicates more reliable detection:
+/// - `High`: JSON-parsed output from tools with structured output
+/// - `Medium`: Text-parsed output from tools without structured output
+/// - `Low`: Heuristic-based detection or fallback parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Confidence {
     High,
     Medium,
@@ -415,7 +1055,7 @@ impl fmt::Display for DepsOutput {
                     Some(alias) => format!("{} ({})", alias, package.name),
                     None => package.name.clone(),
                 };
-                writeln!(
+                write!(
                     f,
                     "- {} ({}) current {} latest {} required {}",
                     display_name,
@@ -424,6 +1064,59 @@ impl fmt::Display for DepsOutput {
                     package.latest,
                     package.required
                 )?;
+                if package.raises_msrv {
+                    let rust_version = package.latest_rust_version.as_deref().unwrap_or("unknown");
+                    match package.latest_compatible.as_deref() {
+                        Some(compatible) => write!(
+                            f,
+                            " [raises MSRV to {rust_version}; latest compatible {compatible}]"
+                        )?,
+                        None => write!(f, " [raises MSRV to {rust_version}]")?,
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.git_dependencies.is_empty() {
+            writeln!(f, "Git dependencies:")?;
+            for git in &self.git_dependencies {
+                let display_name = match git.alias.as_deref() {
+                    Some(alias) => format!("{} ({})", alias, git.name),
+                    None => git.name.clone(),
+                };
+                let short = |commit: &str| commit.chars().take(8).collect::<String>();
+                write!(
+                    f,
+                    "- {} ({}) {} pinned {}",
+                    display_name,
+                    git.reference.as_deref().unwrap_or("default branch"),
+                    git.status,
+                    git.pinned
+                        .as_deref()
+                        .map(short)
+                        .unwrap_or_else(|| "?".to_string())
+                )?;
+                if let Some(tag) = &git.pinned_tag {
+                    write!(f, " ({tag})")?;
+                }
+                if git.status == GitStatus::Behind {
+                    if let Some(latest) = &git.latest_commit {
+                        write!(f, ", latest {}", short(latest))?;
+                    }
+                    if let Some(count) = git.commits_behind {
+                        write!(f, " ({count} commits behind)")?;
+                    }
+                }
+                if let Some(tag) = &git.latest_tag {
+                    write!(f, ", newest tag {tag}")?;
+                }
+                if let Some(version) = &git.registry_alternative {
+                    write!(f, ", crates.io {version}")?;
+                }
+                if let Some(error) = &git.error {
+                    write!(f, " [{error}]")?;
+                }
+                writeln!(f)?;
             }
         }
         if !self.skipped_packages.is_empty() {
@@ -451,135 +1144,1035 @@ impl fmt::Display for DepsOutput {
     }
 }
 
-impl fmt::Display for UnusedOutput {
+impl fmt::Display for UnusedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Unused: {}", self.unused.len())?;
+        writeln!(f, "Possibly unused: {}", self.possibly_unused.len())?;
+
+        if self.unused.is_empty() {
+            writeln!(f, "Unused details: none")?;
+        } else {
+            writeln!(f, "Unused details:")?;
+            for dependency in &self.unused {
+                writeln!(
+                    f,
+                    "- {} ({}, confidence {})",
+                    dependency.name, dependency.dependency_type, dependency.confidence
+                )?;
+            }
+        }
+
+        if self.possibly_unused.is_empty() {
+            writeln!(f, "Possibly unused details: none")?;
+        } else {
+            writeln!(f, "Possibly unused details:")?;
+            for dependency in &self.possibly_unused {
+                writeln!(f, "- {dependency}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for UnsafeOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Packages: {}", self.summary.packages)?;
+        writeln!(f, "Total unsafe: {}", self.summary.total_unsafe)?;
+        writeln!(f, "Unsafe functions: {}", self.summary.unsafe_functions)?;
+        writeln!(f, "Unsafe impls: {}", self.summary.unsafe_impls)?;
+        writeln!(f, "Unsafe traits: {}", self.summary.unsafe_traits)?;
+        writeln!(f, "Unsafe blocks: {}", self.summary.unsafe_blocks)?;
+        writeln!(f, "Unsafe expressions: {}", self.summary.unsafe_expressions)?;
+
+        if self.packages.is_empty() {
+            writeln!(f, "Details: none")?;
+            return Ok(());
+        }
+
+        writeln!(f, "Details:")?;
+        for package in &self.packages {
+            writeln!(
+                f,
+                "- {} {}: unsafe {} (fn {}, impls {}, traits {}, blocks {}, exprs {})",
+                package.name,
+                package.version,
+                package.total_unsafe,
+                package.unsafe_functions,
+                package.unsafe_impls,
+                package.unsafe_traits,
+                package.unsafe_blocks,
+                package.unsafe_expressions
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for AuditOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Vulnerabilities: {}", self.summary.total)?;
+        writeln!(f, "Critical: {}", self.summary.critical)?;
+        writeln!(f, "High: {}", self.summary.high)?;
+        writeln!(f, "Moderate: {}", self.summary.moderate)?;
+        writeln!(f, "Low: {}", self.summary.low)?;
+        if self.vulnerabilities.is_empty() {
+            writeln!(f, "Details: none")?;
+            return Ok(());
+        }
+
+        let mut id_width = "id".len();
+        let mut package_width = "package".len();
+        let mut version_width = "version".len();
+        let mut severity_width = "severity".len();
+
+        for vulnerability in &self.vulnerabilities {
+            id_width = id_width.max(vulnerability.id.len());
+            package_width = package_width.max(vulnerability.package.len());
+            version_width = version_width.max(vulnerability.package_version.len());
+            severity_width = severity_width.max(vulnerability.severity.to_string().len());
+        }
+
+        writeln!(f, "Details:")?;
+        writeln!(
+            f,
+            "{:<id_width$}  {:<package_width$}  {:<version_width$}  {:<severity_width$}",
+            "id",
+            "package",
+            "version",
+            "severity",
+            id_width = id_width,
+            package_width = package_width,
+            version_width = version_width,
+            severity_width = severity_width
+        )?;
+        for vulnerability in &self.vulnerabilities {
+            writeln!(
+                f,
+                "{:<id_width$}  {:<package_width$}  {:<version_width$}  {:<severity_width$}",
+                vulnerability.id,
+                vulnerability.package,
+                vulnerability.package_version,
+                vulnerability.severity,
+                id_width = id_width,
+                package_width = package_width,
+                version_width = version_width,
+                severity_width = severity_width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for QualityOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score: {:.1}", self.score)?;
+        writeln!(f, "Grade: {}", self.grade)?;
+        if self.breakdown.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f, "Breakdown:")?;
+        for metric in &self.breakdown {
+            match (metric.status, metric.score) {
+                (MetricStatus::Measured, Some(score)) => writeln!(
+                    f,
+                    "- {}: {:.1} (weight {:.2})",
+                    metric.name, score, metric.weight
+                )?,
+                (MetricStatus::Unavailable, score) => {
+                    let reason = metric.reason.as_deref().unwrap_or("unknown reason");
+                    match score {
+                        Some(score) => writeln!(
+                            f,
+                            "- {}: {:.1} (weight {:.2}, unavailable: {})",
+                            metric.name, score, metric.weight, reason
+                        )?,
+                        None => writeln!(f, "- {}: unavailable ({})", metric.name, reason)?,
+                    }
+                }
+                (status, _) => writeln!(f, "- {}: {}", metric.name, status)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MsrvOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.members.is_empty() {
+            return writeln!(f, "No workspace members found.");
+        }
+
+        for member in &self.members {
+            let declared = member.declared.as_deref().unwrap_or("not declared");
+            writeln!(
+                f,
+                "{}: rust-version {} ({})",
+                member.name, declared, member.state
+            )?;
+            for dep in &member.incompatible {
+                writeln!(
+                    f,
+                    "  - {} {} requires rust {}",
+                    dep.name, dep.version, dep.rust_version
+                )?;
+            }
+            if let Some(check) = &member.toolchain {
+                match &check.message {
+                    Some(message) => writeln!(
+                        f,
+                        "  toolchain {}: {} ({})",
+                        check.toolchain, check.status, message
+                    )?,
+                    None => writeln!(f, "  toolchain {}: {}", check.toolchain, check.status)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for EffectiveMsrvOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.members.is_empty() {
+            writeln!(f, "No workspace members found.")?;
+        }
+
+        for member in &self.members {
+            let declared = member.declared.as_deref().unwrap_or("not declared");
+            match &member.effective {
+                Some(effective) => writeln!(
+                    f,
+                    "{}: effective MSRV {} (declared {})",
+                    member.name, effective, declared
+                )?,
+                None => writeln!(
+                    f,
+                    "{}: no dependency declares a rust-version (declared {})",
+                    member.name, declared
+                )?,
+            }
+            for dep in &member.forced_by {
+                writeln!(f, "  forced by {} {}", dep.name, dep.version)?;
+            }
+            if let Some(downgrade) = &member.downgrade {
+                writeln!(
+                    f,
+                    "  downgrade {} {} -> {} (rust {}) to lower the MSRV to {}: cargo update -p {}@{} --precise {}",
+                    downgrade.name,
+                    downgrade.from,
+                    downgrade.to,
+                    downgrade.rust_version,
+                    downgrade.resulting_msrv,
+                    downgrade.name,
+                    downgrade.from,
+                    downgrade.to
+                )?;
+            }
+            if let Some(path) = &member.written {
+                writeln!(f, "  wrote rust-version to {path}")?;
+            }
+        }
+
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WhyOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let versions = self.versions.join(", ");
+        if self.paths.is_empty() {
+            return writeln!(
+                f,
+                "{} {} is not reachable from any workspace member.",
+                self.crate_name, versions
+            );
+        }
+
+        writeln!(
+            f,
+            "{} {} is required by {} path(s):",
+            self.crate_name,
+            versions,
+            self.paths.len()
+        )?;
+        for path in &self.paths {
+            writeln!(f)?;
+            writeln!(f, "{}", path.member)?;
+            for hop in &path.hops {
+                let kinds: Vec<String> = hop.kinds.iter().map(ToString::to_string).collect();
+                let mut details = kinds.join(", ");
+                if hop.optional {
+                    details.push_str(", optional");
+                }
+                if !hop.enabled_by.is_empty() {
+                    let features: Vec<String> = hop
+                        .enabled_by
+                        .iter()
+                        .map(|feature| format!("{}/{feature}", hop.from))
+                        .collect();
+                    details.push_str(&format!(", enabled by {}", features.join(", ")));
+                }
+                writeln!(f, "  -> {} v{} ({})", hop.to, hop.version, details)?;
+            }
+        }
+        if self.truncated {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "More paths exist; only the first {} are shown.",
+                self.paths.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LockDiffOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Cargo.lock changes from {} to {}", self.old, self.new)?;
+        writeln!(
+            f,
+            "Added: {}  Removed: {}  Upgraded: {}  Downgraded: {}",
+            self.added.len(),
+            self.removed.len(),
+            self.upgraded.len(),
+            self.downgraded.len()
+        )?;
+        for package in &self.added {
+            writeln!(f, "+ {} {}", package.name, package.version)?;
+        }
+        for package in &self.removed {
+            writeln!(f, "- {} {}", package.name, package.version)?;
+        }
+        for change in &self.upgraded {
+            writeln!(
+                f,
+                "^ {} {} -> {} ({})",
+                change.name, change.from, change.to, change.update_type
+            )?;
+        }
+        for change in &self.downgraded {
+            writeln!(
+                f,
+                "v {} {} -> {} ({})",
+                change.name, change.from, change.to, change.update_type
+            )?;
+        }
+        if !self.advisories.is_empty() {
+            writeln!(f, "New advisories:")?;
+            for advisory in &self.advisories {
+                writeln!(
+                    f,
+                    "- {} {} {} ({}): {}",
+                    advisory.id,
+                    advisory.package,
+                    advisory.package_version,
+                    advisory.severity,
+                    advisory.title
+                )?;
+            }
+        }
+        if !self.new_duplicates.is_empty() {
+            writeln!(f, "New duplicate versions:")?;
+            for duplicate in &self.new_duplicates {
+                writeln!(
+                    f,
+                    "- {} ({})",
+                    duplicate.name,
+                    duplicate.versions.join(", ")
+                )?;
+            }
+        }
+        if !self.new_sources.is_empty() {
+            writeln!(f, "New sources:")?;
+            for source in &self.new_sources {
+                writeln!(
+                    f,
+                    "- {} {} ({})",
+                    source.kind,
+                    source.url,
+                    source.packages.join(", ")
+                )?;
+            }
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WeightOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, member) in self.members.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", member.name)?;
+            if member.dependencies.is_empty() {
+                writeln!(f, "  no dependencies")?;
+            }
+            for dependency in &member.dependencies {
+                writeln!(
+                    f,
+                    "  {} v{}: {} unique, {} shared, {} total, {} proc-macro(s), {} build script(s), {}",
+                    dependency.name,
+                    dependency.version,
+                    dependency.unique,
+                    dependency.shared,
+                    dependency.total,
+                    dependency.proc_macros,
+                    dependency.build_scripts,
+                    format_bytes(dependency.source_bytes)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+impl fmt::Display for DuplicatesOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.duplicates.is_empty() {
+            writeln!(f, "No duplicate crate versions.")?;
+        } else {
+            writeln!(f, "Duplicate crates: {}", self.duplicates.len())?;
+        }
+        for duplicate in &self.duplicates {
+            let versions: Vec<&str> = duplicate
+                .versions
+                .iter()
+                .map(|version| version.version.as_str())
+                .collect();
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{} ({}) - {} crate(s) duplicated",
+                duplicate.name,
+                versions.join(", "),
+                duplicate.impact
+            )?;
+            for version in &duplicate.versions {
+                writeln!(f, "  v{} required by:", version.version)?;
+                for parent in &version.parents {
+                    let member = if parent.workspace_member {
+                        " (workspace member)"
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        f,
+                        "    - {} v{} requires {}{member}",
+                        parent.name,
+                        parent.version,
+                        parent.req.as_deref().unwrap_or("?")
+                    )?;
+                }
+            }
+            let target = versions.last().copied().unwrap_or_default();
+            for suggestion in &duplicate.suggestions {
+                write!(f, "  suggestion: ")?;
+                match (&suggestion.upgrade_to, &suggestion.requirement) {
+                    (None, requirement) => writeln!(
+                        f,
+                        "change {}'s requirement on {} to {}",
+                        suggestion.parent,
+                        duplicate.name,
+                        requirement.as_deref().unwrap_or("?")
+                    )?,
+                    (Some(upgrade), None) => writeln!(
+                        f,
+                        "upgrade {} {} -> {} (no longer depends on {})",
+                        suggestion.parent, suggestion.parent_version, upgrade, duplicate.name
+                    )?,
+                    (Some(upgrade), Some(requirement)) if suggestion.unifies => writeln!(
+                        f,
+                        "upgrade {} {} -> {} (requires {} {}, unifies on {})",
+                        suggestion.parent,
+                        suggestion.parent_version,
+                        upgrade,
+                        duplicate.name,
+                        requirement,
+                        target
+                    )?,
+                    (Some(upgrade), Some(requirement)) => writeln!(
+                        f,
+                        "upgrading {} to {} does not help (still requires {} {})",
+                        suggestion.parent, upgrade, duplicate.name, requirement
+                    )?,
+                }
+            }
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LicensesOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let crates: usize = self.groups.iter().map(|group| group.crates.len()).sum();
+        writeln!(
+            f,
+            "Licenses: {} crate(s) under {} license expression(s)",
+            crates,
+            self.groups.len()
+        )?;
+        for group in &self.groups {
+            writeln!(
+                f,
+                "  {} ({}): {}",
+                group.license,
+                group.crates.len(),
+                group.crates.join(", ")
+            )?;
+        }
+        if !self.policy {
+            writeln!(
+                f,
+                "No license policy configured; add [workspace.metadata.upkeep.licenses] to enforce one."
+            )?;
+        }
+        if self.violations.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "Violations: {}", self.violations.len())?;
+        for violation in &self.violations {
+            writeln!(
+                f,
+                "  - {} {} ({}): {} - {}",
+                violation.name,
+                violation.version,
+                violation.license.as_deref().unwrap_or("no license"),
+                violation.status,
+                violation.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SourcesOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut counts: Vec<(DependencySource, usize)> = Vec::new();
+        for package in &self.packages {
+            match counts.iter_mut().find(|(kind, _)| *kind == package.kind) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((package.kind, 1)),
+            }
+        }
+        counts.sort();
+        let summary: Vec<String> = counts
+            .iter()
+            .map(|(kind, count)| format!("{count} {kind}"))
+            .collect();
+        writeln!(
+            f,
+            "Sources: {} crate(s) ({})",
+            self.packages.len(),
+            if summary.is_empty() {
+                "none".to_string()
+            } else {
+                summary.join(", ")
+            }
+        )?;
+        for package in &self.packages {
+            if package.kind == DependencySource::CratesIo {
+                continue;
+            }
+            write!(
+                f,
+                "  {} {} ({}",
+                package.name, package.version, package.kind
+            )?;
+            if let Some(location) = &package.location {
+                write!(f, " {location}")?;
+            }
+            if let Some(reference) = &package.reference {
+                write!(f, " {reference}")?;
+            }
+            writeln!(f, ")")?;
+        }
+        if self.violations.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "Violations: {}", self.violations.len())?;
+        for violation in &self.violations {
+            writeln!(
+                f,
+                "  - {} {} ({}): {} (via {})",
+                violation.name,
+                violation.version,
+                violation.kind,
+                violation.reason,
+                violation.introduced_by.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FeaturesOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for member in &self.members {
+            let clean = member.unreferenced.is_empty()
+                && member.ungated_optional.is_empty()
+                && member.invalid.is_empty()
+                && member.default_features.is_empty();
+            if clean {
+                writeln!(f, "{}: no feature issues", member.name)?;
+            } else {
+                writeln!(f, "{}", member.name)?;
+            }
+            if !member.unreferenced.is_empty() {
+                writeln!(
+                    f,
+                    "  unreferenced features: {}",
+                    member.unreferenced.join(", ")
+                )?;
+            }
+            if !member.ungated_optional.is_empty() {
+                writeln!(
+                    f,
+                    "  optional dependencies without a feature: {}",
+                    member.ungated_optional.join(", ")
+                )?;
+            }
+            for invalid in &member.invalid {
+                writeln!(
+                    f,
+                    "  invalid entry in {}: \"{}\" ({})",
+                    invalid.feature, invalid.entry, invalid.reason
+                )?;
+            }
+            for hint in &member.default_features {
+                writeln!(
+                    f,
+                    "  {} could drop default features: {}",
+                    hint.dependency,
+                    hint.defaults.join(", ")
+                )?;
+            }
+            if !member.powerset.is_empty() {
+                let failed: Vec<&FeatureCheck> = member
+                    .powerset
+                    .iter()
+                    .filter(|check| !check.passed)
+                    .collect();
+                writeln!(
+                    f,
+                    "  powerset: {} combination(s), {} failed",
+                    member.powerset.len(),
+                    failed.len()
+                )?;
+                for check in failed {
+                    let features = if check.features.is_empty() {
+                        "(no features)".to_string()
+                    } else {
+                        check.features.join(",")
+                    };
+                    writeln!(
+                        f,
+                        "    {features}: {}",
+                        check.message.as_deref().unwrap_or("failed")
+                    )?;
+                }
+            }
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BuildTimeOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.crates.is_empty() {
+            return writeln!(f, "No dependencies run code at build time.");
+        }
+        let build_scripts = self
+            .crates
+            .iter()
+            .filter(|krate| krate.build_script)
+            .count();
+        let proc_macros = self.crates.iter().filter(|krate| krate.proc_macro).count();
+        writeln!(
+            f,
+            "Build-time code: {} crate(s), {build_scripts} build script(s), {proc_macros} proc-macro(s)",
+            self.crates.len()
+        )?;
+        for krate in &self.crates {
+            let mut kinds = Vec::new();
+            if krate.build_script {
+                kinds.push("build script".to_string());
+            }
+            if krate.proc_macro {
+                kinds.push("proc-macro".to_string());
+            }
+            if let Some(links) = &krate.links {
+                kinds.push(format!("links {links}"));
+            }
+            write!(
+                f,
+                "  {} {} [{}] ({})",
+                krate.name,
+                krate.version,
+                kinds.join(", "),
+                krate.source
+            )?;
+            if !krate.via.is_empty() {
+                write!(f, " via {}", krate.via.join(", "))?;
+            }
+            if krate.new {
+                write!(f, " NEW")?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(baseline) = &self.baseline {
+            let new: Vec<&str> = self
+                .crates
+                .iter()
+                .filter(|krate| krate.new)
+                .map(|krate| krate.name.as_str())
+                .collect();
+            if new.is_empty() {
+                writeln!(f, "Nothing new since {baseline}.")?;
+            } else {
+                writeln!(f, "New since {baseline}: {}", new.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConsistencyOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.findings.is_empty() {
+            return writeln!(f, "Workspace members are consistent.");
+        }
+        writeln!(f, "Consistency issues: {}", self.findings.len())?;
+        for finding in &self.findings {
+            match &finding.expected {
+                Some(expected) => writeln!(
+                    f,
+                    "  {} differs from the workspace default {expected}",
+                    finding.name
+                )?,
+                None => writeln!(f, "  {}: {}", finding.name, finding.kind)?,
+            }
+            for member in &finding.members {
+                writeln!(f, "    {}: {}", member.member, member.value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for InheritOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.candidates.is_empty() {
+            writeln!(
+                f,
+                "No shared dependencies to move into [workspace.dependencies]."
+            )?;
+        } else {
+            writeln!(
+                f,
+                "Shared dependencies not inherited from the workspace: {}",
+                self.candidates.len()
+            )?;
+        }
+        for candidate in &self.candidates {
+            let origin = if candidate.in_workspace {
+                "in [workspace.dependencies]"
+            } else {
+                "suggested"
+            };
+            write!(f, "  {} {}", candidate.name, candidate.requirement)?;
+            if let Some(registry) = &candidate.registry {
+                write!(f, " from {registry}")?;
+            }
+            writeln!(f, " ({origin})")?;
+            for declaration in &candidate.declarations {
+                write!(f, "    {}: ", declaration.member)?;
+                if declaration.inherited {
+                    write!(f, "workspace = true")?;
+                } else {
+                    write!(f, "{}", declaration.requirement)?;
+                }
+                if declaration.dependency_type != DependencyType::Normal {
+                    write!(f, " [{}]", declaration.dependency_type)?;
+                }
+                if let Some(target) = &declaration.target {
+                    write!(f, " [{target}]")?;
+                }
+                if !declaration.default_features {
+                    write!(f, " no default features")?;
+                }
+                if !declaration.features.is_empty() {
+                    write!(f, " features: {}", declaration.features.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.applied.is_empty() {
+            writeln!(
+                f,
+                "Moved into [workspace.dependencies]: {}",
+                self.applied.join(", ")
+            )?;
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PatchesOutput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Unused: {}", self.unused.len())?;
-        writeln!(f, "Possibly unused: {}", self.possibly_unused.len())?;
-
-        if self.unused.is_empty() {
-            writeln!(f, "Unused details: none")?;
+        if self.patches.is_empty() {
+            writeln!(f, "No [patch] or [replace] entries.")?;
         } else {
-            writeln!(f, "Unused details:")?;
-            for dependency in &self.unused {
+            let unused = self.patches.iter().filter(|patch| !patch.used).count();
+            let superseded = self.patches.iter().filter(|patch| patch.superseded).count();
+            writeln!(
+                f,
+                "Patches: {} ({unused} unused, {superseded} superseded upstream)",
+                self.patches.len()
+            )?;
+        }
+        for patch in &self.patches {
+            let section = match patch.kind {
+                PatchKind::Patch => format!("patch.{}", patch.target),
+                PatchKind::Replace => format!("replace \"{}\"", patch.target),
+            };
+            write!(f, "  [{section}] {}", patch.name)?;
+            if let Some(version) = &patch.version {
+                write!(f, " {version}")?;
+            }
+            write!(f, " from {}", patch.source)?;
+            if !patch.used {
+                write!(f, " (unused)")?;
+            }
+            writeln!(f)?;
+            if !patch.affects.is_empty() {
+                writeln!(f, "    affects: {}", patch.affects.join(", "))?;
+            }
+            if let (true, Some(upstream)) = (patch.superseded, &patch.upstream) {
                 writeln!(
                     f,
-                    "- {} ({}, confidence {})",
-                    dependency.name, dependency.dependency_type, dependency.confidence
+                    "    crates.io has {upstream}; the patch may no longer be needed"
                 )?;
             }
         }
-
-        if self.possibly_unused.is_empty() {
-            writeln!(f, "Possibly unused details: none")?;
-        } else {
-            writeln!(f, "Possibly unused details:")?;
-            for dependency in &self.possibly_unused {
-                writeln!(f, "- {dependency}")?;
-            }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
         }
-
         Ok(())
     }
 }
 
-impl fmt::Display for UnsafeOutput {
+impl fmt::Display for SemverOutput {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Packages: {}", self.summary.packages)?;
-        writeln!(f, "Total unsafe: {}", self.summary.total_unsafe)?;
-        writeln!(f, "Unsafe functions: {}", self.summary.unsafe_functions)?;
-        writeln!(f, "Unsafe impls: {}", self.summary.unsafe_impls)?;
-        writeln!(f, "Unsafe traits: {}", self.summary.unsafe_traits)?;
-        writeln!(f, "Unsafe blocks: {}", self.summary.unsafe_blocks)?;
-        writeln!(f, "Unsafe expressions: {}", self.summary.unsafe_expressions)?;
-
-        if self.packages.is_empty() {
-            writeln!(f, "Details: none")?;
-            return Ok(());
+        if self.crates.is_empty() {
+            return writeln!(f, "No crates were checked.");
         }
-
-        writeln!(f, "Details:")?;
-        for package in &self.packages {
+        for krate in &self.crates {
+            let baseline = krate.baseline.as_deref().unwrap_or("?");
+            let verdict = if krate.sufficient {
+                "ok"
+            } else {
+                "insufficient"
+            };
             writeln!(
                 f,
-                "- {} {}: unsafe {} (fn {}, impls {}, traits {}, blocks {}, exprs {})",
-                package.name,
-                package.version,
-                package.total_unsafe,
-                package.unsafe_functions,
-                package.unsafe_impls,
-                package.unsafe_traits,
-                package.unsafe_blocks,
-                package.unsafe_expressions
+                "{} {} -> {}: bump {}, required {} ({verdict})",
+                krate.name, baseline, krate.current, krate.bump, krate.required
             )?;
+            for violation in &krate.violations {
+                writeln!(f, "  - {}: {}", violation.lint, violation.summary)?;
+                for location in &violation.locations {
+                    writeln!(f, "      {location}")?;
+                }
+            }
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings: {}", self.warnings.join("; "))?;
         }
-
         Ok(())
     }
 }
 
-impl fmt::Display for AuditOutput {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Vulnerabilities: {}", self.summary.total)?;
-        writeln!(f, "Critical: {}", self.summary.critical)?;
-        writeln!(f, "High: {}", self.summary.high)?;
-        writeln!(f, "Moderate: {}", self.summary.moderate)?;
-        writeln!(f, "Low: {}", self.summary.low)?;
-        if self.vulnerabilities.is_empty() {
-            writeln!(f, "Details: none")?;
-            return Ok(());
+impl GraphOutput {
+    /// Renders the graph as a Graphviz DOT digraph.
+    pub fn to_dot(&self) -> String {
+        let index = self.node_index();
+        let mut lines = vec![
+            "digraph dependencies {".to_string(),
+            "    rankdir=LR;".to_string(),
+            "    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];".to_string(),
+        ];
+
+        for (position, node) in self.packages.iter().enumerate() {
+            let border = if node.duplicate {
+                ", color=\"#dc3545\", penwidth=2"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "    n{position} [label=\"{}\", fillcolor=\"{}\"{border}];",
+                escape_dot(&node_label(node)),
+                node.kind.color()
+            ));
         }
 
-        let mut id_width = "id".len();
-        let mut package_width = "package".len();
-        let mut version_width = "version".len();
-        let mut severity_width = "severity".len();
+        for edge in &self.edges {
+            let (Some(from), Some(to)) =
+                (index.get(edge.from.as_str()), index.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            let style = if edge.has_kind(DependencyType::Normal) {
+                ""
+            } else {
+                ", style=dashed"
+            };
+            lines.push(format!(
+                "    n{from} -> n{to} [label=\"{}\"{style}];",
+                escape_dot(&Self::edge_label(edge))
+            ));
+        }
 
-        for vulnerability in &self.vulnerabilities {
-            id_width = id_width.max(vulnerability.id.len());
-            package_width = package_width.max(vulnerability.package.len());
-            version_width = version_width.max(vulnerability.package_version.len());
-            severity_width = severity_width.max(vulnerability.severity.to_string().len());
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Renders the graph as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let index = self.node_index();
+        let mut lines = vec!["flowchart LR".to_string()];
+
+        for (position, node) in self.packages.iter().enumerate() {
+            let class = if node.duplicate {
+                "duplicate".to_string()
+            } else {
+                node.kind.to_string()
+            };
+            lines.push(format!(
+                "    n{position}[\"{}\"]:::{class}",
+                escape_mermaid(&node_label(node))
+            ));
         }
 
-        writeln!(f, "Details:")?;
-        writeln!(
-            f,
-            "{:<id_width$}  {:<package_width$}  {:<version_width$}  {:<severity_width$}",
-            "id",
-            "package",
-            "version",
-            "severity",
-            id_width = id_width,
-            package_width = package_width,
-            version_width = version_width,
-            severity_width = severity_width
-        )?;
-        for vulnerability in &self.vulnerabilities {
-            writeln!(
-                f,
-                "{:<id_width$}  {:<package_width$}  {:<version_width$}  {:<severity_width$}",
-                vulnerability.id,
-                vulnerability.package,
-                vulnerability.package_version,
-                vulnerability.severity,
-                id_width = id_width,
-                package_width = package_width,
-                version_width = version_width,
-                severity_width = severity_width
-            )?;
+        for edge in &self.edges {
+            let (Some(from), Some(to)) =
+                (index.get(edge.from.as_str()), index.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            let arrow = if edge.has_kind(DependencyType::Normal) {
+                "-->"
+            } else {
+                "-.->"
+            };
+            lines.push(format!(
+                "    n{from} {arrow}|\"{}\"| n{to}",
+                escape_mermaid(&Self::edge_label(edge))
+            ));
+        }
+
+        for kind in [
+            GraphNodeKind::WorkspaceMember,
+            GraphNodeKind::ProcMacro,
+            GraphNodeKind::Build,
+            GraphNodeKind::Dev,
+            GraphNodeKind::Normal,
+        ] {
+            lines.push(format!("    classDef {kind} fill:{}", kind.color()));
+        }
+        lines.push(
+            "    classDef duplicate fill:#f8d7da,stroke:#dc3545,stroke-width:2px".to_string(),
+        );
+
+        lines.join("\n")
+    }
+
+    /// Labels an edge with its kinds and the features it enables.
+    fn edge_label(edge: &GraphEdge) -> String {
+        let kinds: Vec<String> = edge.kinds.iter().map(ToString::to_string).collect();
+        if edge.features.is_empty() {
+            kinds.join(", ")
+        } else {
+            format!("{} [{}]", kinds.join(", "), edge.features.join(", "))
         }
-        Ok(())
+    }
+
+    fn node_index(&self) -> HashMap<&str, usize> {
+        self.packages
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.id.as_str(), position))
+            .collect()
     }
 }
 
-impl fmt::Display for QualityOutput {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Score: {:.1}", self.score)?;
-        writeln!(f, "Grade: {}", self.grade)?;
-        Ok(())
+impl GraphEdge {
+    pub fn has_kind(&self, kind: DependencyType) -> bool {
+        self.kinds.iter().any(|edge_kind| edge_kind.kind == kind)
+    }
+}
+
+impl GraphNodeKind {
+    fn color(self) -> &'static str {
+        match self {
+            GraphNodeKind::WorkspaceMember => "#cfe2ff",
+            GraphNodeKind::ProcMacro => "#e2d9f3",
+            GraphNodeKind::Build => "#fff3cd",
+            GraphNodeKind::Dev => "#d1e7dd",
+            GraphNodeKind::Normal => "#ffffff",
+        }
+    }
+}
+
+fn node_label(node: &GraphNode) -> String {
+    if node.version.is_empty() {
+        node.name.clone()
+    } else {
+        format!("{} v{}", node.name, node.version)
     }
 }
 
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
 impl fmt::Display for Severity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
@@ -592,6 +2185,17 @@ impl fmt::Display for Severity {
     }
 }
 
+impl fmt::Display for GitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            GitStatus::UpToDate => "up to date",
+            GitStatus::Behind => "behind",
+            GitStatus::Unknown => "unknown",
+        };
+        write!(f, "{label}")
+    }
+}
+
 impl fmt::Display for UpdateType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
@@ -614,6 +2218,22 @@ impl fmt::Display for DependencyType {
     }
 }
 
+impl fmt::Display for ConsistencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ConsistencyKind::IncompatibleRequirements => {
+                "incompatible requirements (forces duplicate versions)"
+            }
+            ConsistencyKind::DefaultFeatures => "default-features differ",
+            ConsistencyKind::Features => "feature sets differ",
+            ConsistencyKind::Rename => "renamed inconsistently",
+            ConsistencyKind::Edition => "edition differs",
+            ConsistencyKind::RustVersion => "rust-version differs",
+        };
+        write!(f, "{label}")
+    }
+}
+
 impl fmt::Display for SkipReason {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
@@ -628,6 +2248,97 @@ impl fmt::Display for SkipReason {
     }
 }
 
+impl fmt::Display for MetricStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            MetricStatus::Measured => "measured",
+            MetricStatus::Unavailable => "unavailable",
+            MetricStatus::Skipped => "skipped",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for MsrvState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            MsrvState::Valid => "valid",
+            MsrvState::Missing => "missing",
+            MsrvState::Invalid => "invalid",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for ToolchainCheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ToolchainCheckStatus::Passed => "passed",
+            ToolchainCheckStatus::Failed => "failed",
+            ToolchainCheckStatus::Unavailable => "unavailable",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for GraphEdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{} ({target})", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl fmt::Display for GraphNodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            GraphNodeKind::WorkspaceMember => "workspace_member",
+            GraphNodeKind::ProcMacro => "proc_macro",
+            GraphNodeKind::Build => "build",
+            GraphNodeKind::Dev => "dev",
+            GraphNodeKind::Normal => "normal",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for LicenseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            LicenseStatus::Denied => "denied",
+            LicenseStatus::Unlicensed => "unlicensed",
+            LicenseStatus::Invalid => "invalid",
+            LicenseStatus::Custom => "custom",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for DependencySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DependencySource::CratesIo => "crates.io",
+            DependencySource::Registry => "registry",
+            DependencySource::Git => "git",
+            DependencySource::Path => "path",
+        };
+        write!(f, "{label}")
+    }
+}
+
+impl fmt::Display for ReleaseBump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ReleaseBump::None => "none",
+            ReleaseBump::Patch => "patch",
+            ReleaseBump::Minor => "minor",
+            ReleaseBump::Major => "major",
+        };
+        write!(f, "{label}")
+    }
+}
+
 impl fmt::Display for Grade {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
@@ -678,6 +2389,10 @@ mod tests {
             serde_json::to_value(Confidence::Medium).unwrap(),
             Value::String("medium".into())
         );
+        assert_eq!(
+            serde_json::to_value(MetricStatus::Skipped).unwrap(),
+            Value::String("skipped".into())
+        );
     }
 
     #[test]
@@ -729,6 +2444,9 @@ mod tests {
                 required: "^1.0".to_string(),
                 update_type: UpdateType::Patch,
                 dependency_type: DependencyType::Normal,
+                latest_compatible: None,
+                latest_rust_version: None,
+                raises_msrv: false,
             }],
             skipped: 1,
             skipped_packages: vec![SkippedDependency {
@@ -740,6 +2458,7 @@ mod tests {
                 source: None,
                 target: Some("x86_64-unknown-linux-gnu".to_string()),
             }],
+            git_dependencies: Vec::new(),
             warnings: vec!["rustsec unavailable".to_string()],
             security: Some(DepsSecurityOutput {
                 summary: AuditSummary {
@@ -832,8 +2551,10 @@ mod tests {
             grade: Grade::A,
             breakdown: vec![MetricScore {
                 name: "Security".to_string(),
-                score: 90.0,
+                score: Some(90.0),
                 weight: 0.25,
+                status: MetricStatus::Measured,
+                reason: None,
             }],
             recommendations: vec!["Address security advisories.".to_string()],
         };
@@ -978,6 +2699,9 @@ mod tests {
                 required: "^1.0".to_string(),
                 update_type: UpdateType::Patch,
                 dependency_type: DependencyType::Normal,
+                latest_compatible: None,
+                latest_rust_version: None,
+                raises_msrv: false,
             }],
             skipped: 1,
             skipped_packages: vec![SkippedDependency {
@@ -989,6 +2713,7 @@ mod tests {
                 source: None,
                 target: None,
             }],
+            git_dependencies: Vec::new(),
             warnings: vec!["registry unavailable".to_string()],
             security: Some(DepsSecurityOutput {
                 summary: AuditSummary {
@@ -1042,6 +2767,7 @@ mod tests {
             packages: Vec::new(),
             skipped: 0,
             skipped_packages: Vec::new(),
+            git_dependencies: Vec::new(),
             warnings: Vec::new(),
             security: None,
             workspace: false,
@@ -1175,6 +2901,43 @@ mod tests {
         let text = format!("{output}");
         assert!(text.contains("Score: 92.5"));
         assert!(text.contains("Grade: A"));
+        assert!(!text.contains("Breakdown:"));
+    }
+
+    #[test]
+    fn display_quality_output_lists_metric_status() {
+        let output = QualityOutput {
+            score: 80.0,
+            grade: Grade::B,
+            breakdown: vec![
+                MetricScore {
+                    name: "Security".to_string(),
+                    score: Some(80.0),
+                    weight: 1.0,
+                    status: MetricStatus::Measured,
+                    reason: None,
+                },
+                MetricScore {
+                    name: "Clippy".to_string(),
+                    score: None,
+                    weight: 0.0,
+                    status: MetricStatus::Unavailable,
+                    reason: Some("clippy is not installed".to_string()),
+                },
+                MetricScore {
+                    name: "MSRV".to_string(),
+                    score: None,
+                    weight: 0.0,
+                    status: MetricStatus::Skipped,
+                    reason: None,
+                },
+            ],
+            recommendations: Vec::new(),
+        };
+        let text = format!("{output}");
+        assert!(text.contains("- Security: 80.0 (weight 1.00)"));
+        assert!(text.contains("- Clippy: unavailable (clippy is not installed)"));
+        assert!(text.contains("- MSRV: skipped"));
     }
 
     #[test]
@@ -1191,4 +2954,81 @@ mod tests {
         );
         assert_eq!(format!("{}", Grade::F), "F");
     }
+
+    fn sample_graph() -> GraphOutput {
+        GraphOutput {
+            packages: vec![
+                GraphNode {
+                    id: "app 0.1.0".to_string(),
+                    name: "app".to_string(),
+                    version: "0.1.0".to_string(),
+                    source: None,
+                    license: Some("MIT".to_string()),
+                    features: Vec::new(),
+                    kind: GraphNodeKind::WorkspaceMember,
+                    duplicate: false,
+                },
+                GraphNode {
+                    id: "quote 1.0.0".to_string(),
+                    name: "quote\"x".to_string(),
+                    version: "1.0.0".to_string(),
+                    source: Some(
+                        "registry+https://github.com/rust-lang/crates.io-index".to_string(),
+                    ),
+                    license: None,
+                    features: vec!["proc-macro".to_string()],
+                    kind: GraphNodeKind::Build,
+                    duplicate: true,
+                },
+            ],
+            edges: vec![
+                GraphEdge {
+                    from: "app 0.1.0".to_string(),
+                    to: "quote 1.0.0".to_string(),
+                    kinds: vec![GraphEdgeKind {
+                        kind: DependencyType::Build,
+                        target: Some("cfg(unix)".to_string()),
+                    }],
+                    features: vec!["default".to_string(), "span".to_string()],
+                    renamed_as: None,
+                },
+                GraphEdge {
+                    from: "app 0.1.0".to_string(),
+                    to: "missing 1.0.0".to_string(),
+                    kinds: vec![GraphEdgeKind {
+                        kind: DependencyType::Normal,
+                        target: None,
+                    }],
+                    features: Vec::new(),
+                    renamed_as: Some("other".to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn graph_to_dot_escapes_labels_and_skips_unknown_nodes() {
+        let dot = sample_graph().to_dot();
+        assert!(dot.contains("n0 [label=\"app v0.1.0\", fillcolor=\"#cfe2ff\"];"));
+        assert!(dot.contains(
+            "n1 [label=\"quote\\\"x v1.0.0\", fillcolor=\"#fff3cd\", color=\"#dc3545\", penwidth=2];"
+        ));
+        assert!(
+            dot.contains("n0 -> n1 [label=\"build (cfg(unix)) [default, span]\", style=dashed];")
+        );
+        assert!(!dot.contains("missing"));
+        assert!(dot.ends_with('}'));
+    }
+
+    #[test]
+    fn graph_to_mermaid_uses_classes() {
+        let mermaid = sample_graph().to_mermaid();
+        assert!(mermaid.contains("n0[\"app v0.1.0\"]:::workspace_member"));
+        assert!(mermaid.contains("n1[\"quote#quot;x v1.0.0\"]:::duplicate"));
+        assert!(mermaid.contains("n0 -.->|\"build (cfg(unix)) [default, span]\"| n1"));
+        assert_eq!(
+            mermaid.lines().filter(|line| line.contains("-->")).count(),
+            0
+        );
+    }
 }
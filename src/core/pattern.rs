@@ -0,0 +1,88 @@
+//! Crate name patterns: globs such as `tokio-*`, or regexes written `/.../`.
+
+use regex::Regex;
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+#[derive(Debug, Clone)]
+pub struct NamePattern {
+    regex: Regex,
+}
+
+impl NamePattern {
+    /// Parses `pattern`. `*` matches any run of characters and `?` a single
+    /// character; a pattern wrapped in slashes is a regex matched anywhere in
+    /// the name unless anchored.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let expression = match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) => regex.to_string(),
+            None => glob_to_regex(pattern),
+        };
+        let regex = Regex::new(&expression).map_err(|err| {
+            UpkeepError::context(
+                ErrorCode::Config,
+                format!("invalid crate name pattern '{pattern}'"),
+                err,
+            )
+        })?;
+        Ok(Self { regex })
+    }
+
+    pub fn parse_all(patterns: &[String]) -> Result<Vec<Self>> {
+        patterns
+            .iter()
+            .map(|pattern| Self::parse(pattern))
+            .collect()
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut expression = String::from("^");
+    for ch in glob.chars() {
+        match ch {
+            '*' => expression.push_str(".*"),
+            '?' => expression.push('.'),
+            _ => expression.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    expression.push('$');
+    expression
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_whole_names() {
+        let pattern = NamePattern::parse("tokio-*").unwrap();
+        assert!(pattern.matches("tokio-util"));
+        assert!(!pattern.matches("tokio"));
+        assert!(!pattern.matches("my-tokio-util"));
+
+        let exact = NamePattern::parse("serde").unwrap();
+        assert!(exact.matches("serde"));
+        assert!(!exact.matches("serde_json"));
+
+        let single = NamePattern::parse("windows_?86*").unwrap();
+        assert!(single.matches("windows_x86_64_msvc"));
+    }
+
+    #[test]
+    fn slashes_select_regex_patterns() {
+        let pattern = NamePattern::parse("/^windows(_|-)/").unwrap();
+        assert!(pattern.matches("windows-sys"));
+        assert!(pattern.matches("windows_aarch64_gnullvm"));
+        assert!(!pattern.matches("windows"));
+
+        let err = NamePattern::parse("/(/").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Config);
+    }
+}
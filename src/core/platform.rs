@@ -0,0 +1,280 @@
+//! Target-platform selection for filtering `[target.'cfg(...)'.dependencies]`.
+
+use cargo_metadata::cargo_platform::{Cfg, Platform};
+use std::process::Command;
+use std::str::FromStr;
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+/// Selects every platform when passed to `--target`.
+pub const ALL_TARGETS: &str = "all";
+/// Selects the host platform of the installed `rustc` when passed to `--target`.
+pub const HOST_TARGET: &str = "host";
+
+/// A target triple and the `cfg` values it is compiled with.
+#[derive(Debug, Clone)]
+pub struct TargetPlatform {
+    pub triple: String,
+    cfgs: Vec<Cfg>,
+}
+
+impl TargetPlatform {
+    /// Resolves the `cfg` values for `triple`, preferring `rustc --print cfg`
+    /// and falling back to a built-in table when `rustc` is unavailable or does
+    /// not know the triple.
+    pub fn resolve(triple: &str) -> Result<Self> {
+        let lines = rustc_cfg(triple)
+            .or_else(|| builtin_cfg(triple))
+            .ok_or_else(|| {
+                UpkeepError::message(
+                    ErrorCode::Config,
+                    format!("unknown target triple: {triple}"),
+                )
+            })?;
+        Ok(Self {
+            triple: triple.to_string(),
+            cfgs: parse_cfgs(&lines),
+        })
+    }
+
+    pub fn matches(&self, platform: &Platform) -> bool {
+        platform.matches(&self.triple, &self.cfgs)
+    }
+}
+
+/// The set of platforms dependencies are evaluated against. An empty set
+/// selects every platform, which is the default.
+#[derive(Debug, Clone, Default)]
+pub struct TargetFilter {
+    platforms: Vec<TargetPlatform>,
+}
+
+impl TargetFilter {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Builds a filter from `--target` values. `all` (or no values) keeps
+    /// every platform and `host` resolves to the host triple.
+    pub fn from_args(targets: &[String]) -> Result<Self> {
+        if targets.is_empty() || targets.iter().any(|target| target == ALL_TARGETS) {
+            return Ok(Self::all());
+        }
+
+        let mut platforms: Vec<TargetPlatform> = Vec::new();
+        for target in targets {
+            let triple = if target == HOST_TARGET {
+                host_triple()?
+            } else {
+                target.clone()
+            };
+            if platforms.iter().all(|platform| platform.triple != triple) {
+                platforms.push(TargetPlatform::resolve(&triple)?);
+            }
+        }
+        Ok(Self { platforms })
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.platforms.is_empty()
+    }
+
+    /// Returns true when a dependency limited to `platform` is built for any
+    /// selected target. Dependencies without a platform always match.
+    pub fn matches(&self, platform: Option<&Platform>) -> bool {
+        match platform {
+            None => true,
+            Some(_) if self.is_all() => true,
+            Some(platform) => self.platforms.iter().any(|target| target.matches(platform)),
+        }
+    }
+}
+
+fn host_triple() -> Result<String> {
+    let output = Command::new(rustc()).arg("-vV").output().map_err(|err| {
+        UpkeepError::context(ErrorCode::Io, "failed to run rustc to detect host", err)
+    })?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|host| host.trim().to_string())
+        .ok_or_else(|| {
+            UpkeepError::message(ErrorCode::Config, "rustc -vV did not report a host triple")
+        })
+}
+
+fn rustc_cfg(triple: &str) -> Option<Vec<String>> {
+    let output = Command::new(rustc())
+        .args(["--print", "cfg", "--target", triple])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+fn rustc() -> String {
+    std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string())
+}
+
+fn parse_cfgs(lines: &[String]) -> Vec<Cfg> {
+    lines
+        .iter()
+        .filter_map(|line| Cfg::from_str(line.trim()).ok())
+        .collect()
+}
+
+/// Architecture prefixes as `(triple prefix, target_arch, pointer width, endian)`.
+const ARCHES: &[(&str, &str, &str, &str)] = &[
+    ("x86_64", "x86_64", "64", "little"),
+    ("aarch64", "aarch64", "64", "little"),
+    ("i686", "x86", "32", "little"),
+    ("i586", "x86", "32", "little"),
+    ("armv7", "arm", "32", "little"),
+    ("arm", "arm", "32", "little"),
+    ("riscv64", "riscv64", "64", "little"),
+    ("powerpc64le", "powerpc64", "64", "little"),
+    ("s390x", "s390x", "64", "big"),
+    ("wasm32", "wasm32", "32", "little"),
+];
+
+/// Operating system triple components as `(component, target_os, target_family)`.
+const OPERATING_SYSTEMS: &[(&str, &str, &str)] = &[
+    ("linux", "linux", "unix"),
+    ("android", "android", "unix"),
+    ("androideabi", "android", "unix"),
+    ("darwin", "macos", "unix"),
+    ("ios", "ios", "unix"),
+    ("freebsd", "freebsd", "unix"),
+    ("netbsd", "netbsd", "unix"),
+    ("windows", "windows", "windows"),
+    ("wasi", "wasi", "wasm"),
+    ("wasip1", "wasi", "wasm"),
+    ("wasip2", "wasi", "wasm"),
+];
+
+fn is_os_component(part: &str) -> bool {
+    OPERATING_SYSTEMS
+        .iter()
+        .any(|(component, ..)| *component == part)
+}
+
+/// Derives `rustc --print cfg` style lines from a well-formed triple, for
+/// when `rustc` is not available.
+fn builtin_cfg(triple: &str) -> Option<Vec<String>> {
+    let parts: Vec<&str> = triple.split('-').collect();
+    let &(_, arch, width, endian) = ARCHES
+        .iter()
+        .find(|(prefix, ..)| parts[0].starts_with(prefix))?;
+    let (os, family) = parts[1..]
+        .iter()
+        .rev()
+        .find_map(|part| {
+            OPERATING_SYSTEMS
+                .iter()
+                .find(|(component, ..)| component == part)
+                .map(|&(_, os, family)| (os, family))
+        })
+        .or_else(|| (arch == "wasm32" && parts.len() == 3).then_some(("unknown", "wasm")))?;
+    // Android triples omit the vendor, e.g. `aarch64-linux-android`.
+    let vendor = match parts.get(1) {
+        Some(vendor) if parts.len() > 2 && !is_os_component(vendor) => *vendor,
+        _ => "unknown",
+    };
+    let env = match parts.last().copied().unwrap_or_default() {
+        last if last.starts_with("gnu") => "gnu",
+        last if last.starts_with("musl") => "musl",
+        "msvc" => "msvc",
+        _ => "",
+    };
+
+    let mut lines = vec![
+        format!("target_arch=\"{arch}\""),
+        format!("target_os=\"{os}\""),
+        format!("target_env=\"{env}\""),
+        format!("target_family=\"{family}\""),
+        format!("target_vendor=\"{vendor}\""),
+        format!("target_pointer_width=\"{width}\""),
+        format!("target_endian=\"{endian}\""),
+    ];
+    if family != "wasm" {
+        lines.push(family.to_string());
+    }
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(spec: &str) -> Platform {
+        spec.parse().expect("platform")
+    }
+
+    fn builtin(triple: &str) -> TargetPlatform {
+        TargetPlatform {
+            triple: triple.to_string(),
+            cfgs: parse_cfgs(&builtin_cfg(triple).expect("builtin target")),
+        }
+    }
+
+    #[test]
+    fn builtin_table_evaluates_cfg_expressions() {
+        let linux = builtin("x86_64-unknown-linux-gnu");
+        assert!(linux.matches(&platform("cfg(unix)")));
+        assert!(linux.matches(&platform("cfg(target_os = \"linux\")")));
+        assert!(!linux.matches(&platform("cfg(windows)")));
+        assert!(linux.matches(&platform("cfg(not(target_env = \"msvc\"))")));
+
+        let windows = builtin("x86_64-pc-windows-msvc");
+        assert!(windows.matches(&platform("cfg(windows)")));
+        assert!(windows.matches(&platform("x86_64-pc-windows-msvc")));
+        assert!(!windows.matches(&platform("x86_64-pc-windows-gnu")));
+
+        let wasm = builtin("wasm32-unknown-unknown");
+        assert!(wasm.matches(&platform("cfg(target_arch = \"wasm32\")")));
+        assert!(!wasm.matches(&platform("cfg(unix)")));
+
+        let mac = builtin("aarch64-apple-darwin");
+        assert!(mac.matches(&platform("cfg(target_os = \"macos\")")));
+        assert!(mac.matches(&platform("cfg(target_vendor = \"apple\")")));
+        assert!(builtin_cfg("sparc-unknown-none").is_none());
+
+        let android = builtin_cfg("aarch64-linux-android").unwrap();
+        assert!(android.contains(&"target_vendor=\"unknown\"".to_string()));
+        assert!(android.contains(&"target_os=\"android\"".to_string()));
+    }
+
+    #[test]
+    fn filter_matches_any_selected_target() {
+        let filter = TargetFilter {
+            platforms: vec![
+                builtin("x86_64-unknown-linux-gnu"),
+                builtin("aarch64-apple-darwin"),
+            ],
+        };
+        assert!(filter.matches(None));
+        assert!(filter.matches(Some(&platform("cfg(target_os = \"macos\")"))));
+        assert!(!filter.matches(Some(&platform("cfg(windows)"))));
+    }
+
+    #[test]
+    fn from_args_treats_all_as_unfiltered() {
+        let filter = TargetFilter::from_args(&["all".to_string()]).unwrap();
+        assert!(filter.is_all());
+        assert!(filter.matches(Some(&platform("cfg(windows)"))));
+        assert!(TargetFilter::from_args(&[]).unwrap().is_all());
+    }
+
+    #[test]
+    fn from_args_rejects_unknown_triples() {
+        let err = TargetFilter::from_args(&["not-a-real-target".to_string()]).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Config);
+    }
+}
@@ -0,0 +1,444 @@
+//! CycloneDX 1.5 JSON rendering of an [`Sbom`].
+
+use serde::Serialize;
+
+use super::{timestamp, Component, Sbom, SbomVulnerability};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::spdx::LicenseExpr;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: BomMetadata,
+    components: Vec<BomComponent>,
+    dependencies: Vec<BomDependency>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    vulnerabilities: Vec<BomVulnerability>,
+}
+
+#[derive(Serialize)]
+struct BomMetadata {
+    timestamp: String,
+    tools: BomTools,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    component: Option<BomComponent>,
+}
+
+#[derive(Serialize)]
+struct BomTools {
+    components: Vec<BomTool>,
+}
+
+#[derive(Serialize)]
+struct BomTool {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BomComponent {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    scope: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<BomHash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<BomLicense>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_references: Vec<BomReference>,
+}
+
+#[derive(Serialize)]
+struct BomHash {
+    alg: &'static str,
+    content: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BomLicense {
+    License(BomLicenseChoice),
+    Expression(String),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BomLicenseChoice {
+    Id(String),
+    Name(String),
+}
+
+#[derive(Serialize)]
+struct BomReference {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BomDependency {
+    #[serde(rename = "ref")]
+    reference: String,
+    depends_on: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BomVulnerability {
+    id: String,
+    source: BomSource,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    references: Vec<BomVulnerabilityReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ratings: Vec<BomRating>,
+    description: String,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recommendation: Option<String>,
+    published: String,
+    affects: Vec<BomAffect>,
+}
+
+#[derive(Serialize)]
+struct BomSource {
+    name: String,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct BomVulnerabilityReference {
+    id: String,
+    source: BomSource,
+}
+
+#[derive(Serialize)]
+struct BomRating {
+    source: BomSource,
+    score: f64,
+    severity: String,
+    method: &'static str,
+    vector: String,
+}
+
+#[derive(Serialize)]
+struct BomAffect {
+    #[serde(rename = "ref")]
+    reference: String,
+}
+
+/// Renders the document as pretty-printed CycloneDX 1.5 JSON.
+pub fn render(sbom: &Sbom<'_>) -> Result<String> {
+    let bom = Bom {
+        bom_format: "CycloneDX",
+        spec_version: "1.5",
+        version: 1,
+        metadata: BomMetadata {
+            timestamp: timestamp(),
+            tools: BomTools {
+                components: vec![BomTool {
+                    kind: "application",
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            component: sbom.subject.as_ref().map(component),
+        },
+        components: sbom.components.iter().map(component).collect(),
+        dependencies: sbom
+            .dependencies
+            .iter()
+            .map(|(reference, depends_on)| BomDependency {
+                reference: reference.clone(),
+                depends_on: depends_on.iter().map(|dep| dep.bom_ref.clone()).collect(),
+            })
+            .collect(),
+        vulnerabilities: sbom.vulnerabilities.iter().map(vulnerability).collect(),
+    };
+    serde_json::to_string_pretty(&bom).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Json,
+            "failed to serialize CycloneDX document",
+            err,
+        )
+    })
+}
+
+fn component(component: &Component<'_>) -> BomComponent {
+    let package = component.package;
+    let references = [
+        ("vcs", &package.repository),
+        ("website", &package.homepage),
+        ("documentation", &package.documentation),
+    ];
+    BomComponent {
+        kind: if component.is_application() {
+            "application"
+        } else {
+            "library"
+        },
+        bom_ref: component.bom_ref.clone(),
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        description: package
+            .description
+            .as_ref()
+            .map(|description| description.trim().to_string()),
+        // Dev-only crates are part of the build graph but never shipped.
+        scope: if component.dev_only {
+            "excluded"
+        } else {
+            "required"
+        },
+        hashes: component
+            .checksum
+            .iter()
+            .map(|checksum| BomHash {
+                alg: "SHA-256",
+                content: checksum.clone(),
+            })
+            .collect(),
+        licenses: package
+            .license
+            .as_deref()
+            .map(license)
+            .into_iter()
+            .collect(),
+        purl: component.purl.clone(),
+        external_references: references
+            .into_iter()
+            .filter_map(|(kind, url)| {
+                Some(BomReference {
+                    kind,
+                    url: url.clone()?,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// A single SPDX identifier is listed as such, compound expressions as an
+/// expression, and anything unparseable by name.
+fn license(license: &str) -> BomLicense {
+    match LicenseExpr::parse(license) {
+        Ok(LicenseExpr::License(req)) if req.exception.is_none() && !req.or_later => {
+            BomLicense::License(BomLicenseChoice::Id(req.id))
+        }
+        Ok(expression) => BomLicense::Expression(expression.to_string()),
+        Err(_) => BomLicense::License(BomLicenseChoice::Name(license.to_string())),
+    }
+}
+
+fn vulnerability(vulnerability: &SbomVulnerability) -> BomVulnerability {
+    let rustsec = || BomSource {
+        name: "RustSec".to_string(),
+        url: vulnerability.url.clone(),
+    };
+    let ratings = match (
+        &vulnerability.cvss,
+        vulnerability.score,
+        &vulnerability.severity,
+    ) {
+        (Some(vector), Some(score), Some(severity)) => vec![BomRating {
+            source: rustsec(),
+            score,
+            severity: severity.clone(),
+            method: cvss_method(vector),
+            vector: vector.clone(),
+        }],
+        _ => Vec::new(),
+    };
+    BomVulnerability {
+        id: vulnerability.id.clone(),
+        source: rustsec(),
+        references: vulnerability
+            .aliases
+            .iter()
+            .map(|alias| BomVulnerabilityReference {
+                id: alias.clone(),
+                source: alias_source(alias),
+            })
+            .collect(),
+        ratings,
+        description: vulnerability.title.clone(),
+        detail: vulnerability.description.trim().to_string(),
+        recommendation: (!vulnerability.patched.is_empty()).then(|| {
+            format!(
+                "Upgrade to a patched version: {}",
+                vulnerability.patched.join(", ")
+            )
+        }),
+        published: vulnerability.date.clone(),
+        affects: vulnerability
+            .affects
+            .iter()
+            .map(|reference| BomAffect {
+                reference: reference.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn cvss_method(vector: &str) -> &'static str {
+    if vector.starts_with("CVSS:4") {
+        "CVSSv4"
+    } else if vector.starts_with("CVSS:3.0") {
+        "CVSSv3"
+    } else if vector.starts_with("CVSS:3") {
+        "CVSSv31"
+    } else {
+        "other"
+    }
+}
+
+fn alias_source(alias: &str) -> BomSource {
+    let (name, url) = if alias.starts_with("CVE-") {
+        ("NVD", format!("https://nvd.nist.gov/vuln/detail/{alias}"))
+    } else if alias.starts_with("GHSA-") {
+        ("GitHub", format!("https://github.com/advisories/{alias}"))
+    } else {
+        ("OSV", format!("https://osv.dev/vulnerability/{alias}"))
+    };
+    BomSource {
+        name: name.to_string(),
+        url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sbom::SbomDependency;
+    use cargo_metadata::Package;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    fn package(name: &str, license: Option<&str>, bin: bool) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "license": license,
+            "source": "registry+https://github.com/rust-lang/crates.io-index",
+            "dependencies": [],
+            "targets": [{
+                "name": name,
+                "kind": [if bin { "bin" } else { "lib" }],
+                "crate_types": [if bin { "bin" } else { "lib" }],
+                "src_path": format!("/tmp/{name}/src/lib.rs"),
+                "edition": "2021",
+            }],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn component(package: &Package, dev_only: bool) -> Component<'_> {
+        let purl = format!("pkg:cargo/{}@1.0.0", package.name);
+        Component {
+            package,
+            bom_ref: purl.clone(),
+            purl: Some(purl),
+            checksum: Some("ab".repeat(32)),
+            dev_only,
+        }
+    }
+
+    #[test]
+    fn render_builds_cyclonedx_document() {
+        let app = package("app", Some("MIT"), true);
+        let serde = package("serde", Some("MIT OR Apache-2.0"), false);
+        let mockall = package("mockall", Some("MIT/Apache-2.0 WITH LLVM-exception"), false);
+        let custom = package("custom", Some("Custom license"), false);
+        let mut dependencies = BTreeMap::new();
+        dependencies.insert(
+            "pkg:cargo/app@1.0.0".to_string(),
+            vec![SbomDependency {
+                bom_ref: "pkg:cargo/serde@1.0.0".to_string(),
+                normal: true,
+                dev: false,
+                build: false,
+            }],
+        );
+        let sbom = Sbom {
+            name: "app".to_string(),
+            subject: Some(component(&app, false)),
+            roots: vec!["pkg:cargo/app@1.0.0".to_string()],
+            components: vec![
+                component(&custom, false),
+                component(&mockall, true),
+                component(&serde, false),
+            ],
+            dependencies,
+            vulnerabilities: vec![SbomVulnerability {
+                id: "RUSTSEC-2024-0001".to_string(),
+                title: "Unsound API".to_string(),
+                description: "Details.\n".to_string(),
+                date: "2024-01-02".to_string(),
+                url: "https://rustsec.org/advisories/RUSTSEC-2024-0001".to_string(),
+                cvss: Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".to_string()),
+                score: Some(9.8),
+                severity: Some("critical".to_string()),
+                aliases: vec!["CVE-2024-0001".to_string()],
+                patched: vec![">=1.0.1".to_string()],
+                affects: vec!["pkg:cargo/serde@1.0.0".to_string()],
+            }],
+        };
+
+        let bom: Value = serde_json::from_str(&render(&sbom).unwrap()).unwrap();
+
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], "1.5");
+        assert_eq!(bom["metadata"]["component"]["type"], "application");
+        assert_eq!(
+            bom["metadata"]["component"]["bom-ref"],
+            "pkg:cargo/app@1.0.0"
+        );
+
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(
+            components[0]["licenses"],
+            json!([{ "license": { "name": "Custom license" } }])
+        );
+        assert_eq!(components[1]["scope"], "excluded");
+        assert_eq!(
+            components[1]["licenses"],
+            json!([{ "expression": "MIT OR Apache-2.0 WITH LLVM-exception" }])
+        );
+        assert_eq!(components[2]["scope"], "required");
+        assert_eq!(components[2]["type"], "library");
+        assert_eq!(components[2]["hashes"][0]["alg"], "SHA-256");
+        assert_eq!(
+            bom["metadata"]["component"]["licenses"],
+            json!([{ "license": { "id": "MIT" } }])
+        );
+
+        assert_eq!(bom["dependencies"][0]["ref"], "pkg:cargo/app@1.0.0");
+        assert_eq!(
+            bom["dependencies"][0]["dependsOn"],
+            json!(["pkg:cargo/serde@1.0.0"])
+        );
+
+        let vulnerability = &bom["vulnerabilities"][0];
+        assert_eq!(vulnerability["ratings"][0]["method"], "CVSSv31");
+        assert_eq!(vulnerability["ratings"][0]["severity"], "critical");
+        assert_eq!(vulnerability["references"][0]["source"]["name"], "NVD");
+        assert_eq!(vulnerability["detail"], "Details.");
+        assert_eq!(vulnerability["affects"][0]["ref"], "pkg:cargo/serde@1.0.0");
+    }
+}
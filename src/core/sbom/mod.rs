@@ -0,0 +1,446 @@
+//! Software bill of materials for the resolved dependency graph.
+//!
+//! [`Sbom`] collects the components, their relationships, and optionally the
+//! RustSec advisories affecting them; each format module renders it.
+
+pub mod cyclonedx;
+pub mod spdx;
+
+use cargo_metadata::{MetadataCommand, Package, PackageId, TargetKind};
+use rustsec::report::{Report, Settings};
+use rustsec::{Database, Lockfile};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::analyzers::audit::fetch_database;
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::features::FeatureSelection;
+use crate::core::graph::ResolveGraph;
+use crate::core::platform::TargetFilter;
+use crate::core::source::GitSource;
+
+/// Options for [`generate`].
+pub struct SbomOptions {
+    pub include_dev: bool,
+    pub targets: TargetFilter,
+    pub features: FeatureSelection,
+    /// Embed RustSec advisories; fetches the advisory database.
+    pub vulnerabilities: bool,
+}
+
+/// Builds the document for the workspace (or the `-p` package) and renders
+/// it with `render`.
+pub fn generate(options: &SbomOptions, render: fn(&Sbom<'_>) -> Result<String>) -> Result<String> {
+    let mut command = MetadataCommand::new();
+    options.features.apply(&mut command);
+    let metadata = command.exec().map_err(|err| {
+        UpkeepError::context(ErrorCode::Metadata, "failed to load cargo metadata", err)
+    })?;
+    let graph = ResolveGraph::from_metadata(&metadata, options.include_dev, &options.targets)?;
+
+    let lockfile_path = metadata.workspace_root.join("Cargo.lock");
+    let lockfile = Lockfile::load(&lockfile_path).map_err(|err| {
+        UpkeepError::context(
+            ErrorCode::Rustsec,
+            format!("failed to load {lockfile_path}"),
+            err,
+        )
+    })?;
+
+    let selected = options.features.selected_package(&metadata)?;
+    let roots: Vec<&PackageId> = match selected {
+        Some(package) => vec![&package.id],
+        None => metadata.workspace_members.iter().collect(),
+    };
+    let subject = selected.or_else(|| metadata.root_package());
+    let name = match subject {
+        Some(package) => package.name.to_string(),
+        None => metadata
+            .workspace_root
+            .file_name()
+            .unwrap_or("workspace")
+            .to_string(),
+    };
+
+    let mut sbom = Sbom::build(
+        name,
+        &graph,
+        &roots,
+        subject.map(|package| &package.id),
+        &lockfile,
+    );
+    if options.vulnerabilities {
+        let db = fetch_database()?;
+        sbom.add_vulnerabilities(&db, &lockfile);
+    }
+    render(&sbom)
+}
+
+pub struct Sbom<'a> {
+    /// The subject package, or the workspace directory for virtual workspaces.
+    pub name: String,
+    /// The package the document describes, when there is a single one.
+    pub subject: Option<Component<'a>>,
+    /// References of the packages the graph was walked from.
+    pub roots: Vec<String>,
+    /// Every other package in the graph, sorted by name and version.
+    pub components: Vec<Component<'a>>,
+    /// Direct dependencies of each component, by reference.
+    pub dependencies: BTreeMap<String, Vec<SbomDependency>>,
+    pub vulnerabilities: Vec<SbomVulnerability>,
+}
+
+/// A dependency edge and the kinds it is declared with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomDependency {
+    pub bom_ref: String,
+    pub normal: bool,
+    pub dev: bool,
+    pub build: bool,
+}
+
+pub struct Component<'a> {
+    pub package: &'a Package,
+    /// Identifier unique within the document: the purl when there is one.
+    pub bom_ref: String,
+    pub purl: Option<String>,
+    /// SHA-256 of the `.crate` file, from `Cargo.lock`.
+    pub checksum: Option<String>,
+    /// Only reachable through dev-dependencies, so never shipped.
+    pub dev_only: bool,
+}
+
+impl Component<'_> {
+    /// Packages with a binary target and no library are applications.
+    pub fn is_application(&self) -> bool {
+        let has = |kind: &TargetKind| {
+            self.package
+                .targets
+                .iter()
+                .any(|target| target.kind.contains(kind))
+        };
+        has(&TargetKind::Bin) && !has(&TargetKind::Lib)
+    }
+}
+
+/// A RustSec advisory and the components it affects.
+pub struct SbomVulnerability {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// Publication date, `YYYY-MM-DD`.
+    pub date: String,
+    pub url: String,
+    /// CVSS vector, such as `CVSS:3.1/AV:N/...`.
+    pub cvss: Option<String>,
+    pub score: Option<f64>,
+    pub severity: Option<String>,
+    /// CVE and GHSA identifiers of the same issue.
+    pub aliases: Vec<String>,
+    /// Version requirements that contain a fix.
+    pub patched: Vec<String>,
+    pub affects: Vec<String>,
+}
+
+impl<'a> Sbom<'a> {
+    /// Collects every package reachable from `roots`. `subject` becomes the
+    /// described component instead of an ordinary one.
+    pub fn build(
+        name: String,
+        graph: &ResolveGraph<'a>,
+        roots: &[&PackageId],
+        subject: Option<&PackageId>,
+        lockfile: &Lockfile,
+    ) -> Self {
+        let checksums: HashMap<(String, String), String> = lockfile
+            .packages
+            .iter()
+            .filter_map(|package| {
+                let checksum = package.checksum.as_ref()?;
+                Some((
+                    (package.name.to_string(), package.version.to_string()),
+                    checksum.to_string(),
+                ))
+            })
+            .collect();
+
+        let reachable = graph.reachable_from_roots(roots, |_, _| false);
+        let shipped =
+            graph.reachable_from_roots(roots, |_, edge| !edge.is_normal && !edge.is_build);
+        let mut components: Vec<Component<'a>> = reachable
+            .iter()
+            .filter_map(|id| graph.package(id))
+            .map(|package| {
+                let purl = purl(package);
+                Component {
+                    package,
+                    bom_ref: purl.clone().unwrap_or_else(|| package.id.repr.clone()),
+                    purl,
+                    checksum: checksums
+                        .get(&(package.name.to_string(), package.version.to_string()))
+                        .cloned(),
+                    dev_only: !shipped.contains(&package.id),
+                }
+            })
+            .collect();
+        components.sort_by(|left, right| {
+            left.package
+                .name
+                .cmp(&right.package.name)
+                .then_with(|| left.package.version.cmp(&right.package.version))
+        });
+
+        let refs: HashMap<&PackageId, &str> = components
+            .iter()
+            .map(|component| (&component.package.id, component.bom_ref.as_str()))
+            .collect();
+        let mut dependencies = BTreeMap::new();
+        for component in &components {
+            let mut depends_on: Vec<SbomDependency> = Vec::new();
+            for edge in graph.edges.get(&component.package.id).into_iter().flatten() {
+                let Some(bom_ref) = refs.get(&edge.id) else {
+                    continue;
+                };
+                match depends_on.iter_mut().find(|dep| dep.bom_ref == *bom_ref) {
+                    Some(dep) => {
+                        dep.normal |= edge.is_normal;
+                        dep.dev |= edge.is_dev;
+                        dep.build |= edge.is_build;
+                    }
+                    None => depends_on.push(SbomDependency {
+                        bom_ref: bom_ref.to_string(),
+                        normal: edge.is_normal,
+                        dev: edge.is_dev,
+                        build: edge.is_build,
+                    }),
+                }
+            }
+            depends_on.sort_by(|left, right| left.bom_ref.cmp(&right.bom_ref));
+            dependencies.insert(component.bom_ref.clone(), depends_on);
+        }
+        let roots = roots
+            .iter()
+            .filter_map(|id| refs.get(id))
+            .map(ToString::to_string)
+            .collect();
+
+        let subject = subject.and_then(|id| {
+            let index = components
+                .iter()
+                .position(|component| &component.package.id == id)?;
+            Some(components.remove(index))
+        });
+
+        Sbom {
+            name,
+            subject,
+            roots,
+            components,
+            dependencies,
+            vulnerabilities: Vec::new(),
+        }
+    }
+
+    /// Adds the advisories from `db` that affect a component of the document.
+    pub fn add_vulnerabilities(&mut self, db: &Database, lockfile: &Lockfile) {
+        let refs: HashMap<(String, String), &str> = self
+            .subject
+            .iter()
+            .chain(&self.components)
+            .map(|component| {
+                (
+                    (
+                        component.package.name.to_string(),
+                        component.package.version.to_string(),
+                    ),
+                    component.bom_ref.as_str(),
+                )
+            })
+            .collect();
+
+        let report = Report::generate(db, lockfile, &Settings::default());
+        let mut vulnerabilities: BTreeMap<String, SbomVulnerability> = BTreeMap::new();
+        for entry in &report.vulnerabilities.list {
+            let key = (
+                entry.package.name.to_string(),
+                entry.package.version.to_string(),
+            );
+            let Some(bom_ref) = refs.get(&key) else {
+                continue;
+            };
+            let advisory = &entry.advisory;
+            let id = advisory.id.to_string();
+            vulnerabilities
+                .entry(id.clone())
+                .or_insert_with(|| SbomVulnerability {
+                    url: format!("https://rustsec.org/advisories/{id}"),
+                    id,
+                    title: advisory.title.clone(),
+                    description: advisory.description.clone(),
+                    date: advisory.date.as_str().to_string(),
+                    cvss: advisory.cvss.as_ref().map(ToString::to_string),
+                    score: advisory.cvss.as_ref().map(|cvss| cvss.score()),
+                    severity: advisory
+                        .cvss
+                        .as_ref()
+                        .map(|cvss| cvss.severity().as_str().to_string()),
+                    aliases: advisory.aliases.iter().map(ToString::to_string).collect(),
+                    patched: entry
+                        .versions
+                        .patched()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    affects: Vec::new(),
+                })
+                .affects
+                .push(bom_ref.to_string());
+        }
+        self.vulnerabilities = vulnerabilities.into_values().collect();
+    }
+}
+
+const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// The package URL of a registry or git package. Path packages have none.
+pub fn purl(package: &Package) -> Option<String> {
+    let source = package.source.as_ref()?;
+    let base = format!("pkg:cargo/{}@{}", package.name, package.version);
+    let repr = source.repr.as_str();
+    if source.is_crates_io() {
+        Some(base)
+    } else if let Some(url) = repr
+        .strip_prefix("registry+")
+        .or_else(|| repr.strip_prefix("sparse+"))
+    {
+        (url != CRATES_IO_INDEX)
+            .then(|| format!("{base}?repository_url={}", percent_encode(url)))
+            .or(Some(base))
+    } else {
+        let vcs_url = git_location(repr)?;
+        Some(format!("{base}?vcs_url={}", percent_encode(&vcs_url)))
+    }
+}
+
+/// The `git+<repository>@<commit>` location of a git source, without the
+/// branch or tag query.
+pub fn git_location(source: &str) -> Option<String> {
+    let git = GitSource::parse(source)?;
+    Some(match git.commit {
+        Some(commit) => format!("git+{}@{commit}", git.url),
+        None => format!("git+{}", git.url),
+    })
+}
+
+/// Percent-encodes a purl qualifier value.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// The document creation time in RFC 3339, honoring `SOURCE_DATE_EPOCH` so
+/// reproducible builds produce identical documents.
+pub fn timestamp() -> String {
+    let seconds = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs())
+        });
+    format_timestamp(seconds)
+}
+
+fn format_timestamp(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let rest = seconds % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian date, after
+/// Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn package(name: &str, source: Option<&str>) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    #[test]
+    fn purl_encodes_the_package_source() {
+        assert_eq!(
+            purl(&package(
+                "serde",
+                Some("registry+https://github.com/rust-lang/crates.io-index")
+            ))
+            .as_deref(),
+            Some("pkg:cargo/serde@1.0.0")
+        );
+        assert_eq!(
+            purl(&package(
+                "internal",
+                Some("sparse+https://registry.example.com/index/")
+            ))
+            .as_deref(),
+            Some("pkg:cargo/internal@1.0.0?repository_url=https%3A//registry.example.com/index/")
+        );
+        assert_eq!(
+            purl(&package(
+                "forked",
+                Some("git+https://github.com/example/forked?branch=main#0123abcd")
+            ))
+            .as_deref(),
+            Some("pkg:cargo/forked@1.0.0?vcs_url=git%2Bhttps%3A//github.com/example/forked%400123abcd")
+        );
+        assert!(purl(&package("local", None)).is_none());
+    }
+
+    #[test]
+    fn format_timestamp_renders_utc_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+}
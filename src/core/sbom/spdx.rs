@@ -0,0 +1,497 @@
+//! SPDX 2.3 rendering of an [`Sbom`], as JSON or tag-value.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use super::{git_location, timestamp, Component, Sbom};
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+use crate::core::spdx::LicenseExpr;
+
+const NOASSERTION: &str = "NOASSERTION";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxDocument {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    document_describes: Vec<String>,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<Checksum>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    license_concluded: &'static str,
+    license_declared: String,
+    copyright_text: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef>,
+    primary_package_purpose: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksum {
+    algorithm: &'static str,
+    checksum_value: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+/// Renders the document as pretty-printed SPDX 2.3 JSON.
+pub fn render_json(sbom: &Sbom<'_>) -> Result<String> {
+    serde_json::to_string_pretty(&document(sbom)).map_err(|err| {
+        UpkeepError::context(ErrorCode::Json, "failed to serialize SPDX document", err)
+    })
+}
+
+/// Renders the document in the SPDX 2.3 tag-value format.
+pub fn render_tag_value(sbom: &Sbom<'_>) -> Result<String> {
+    let document = document(sbom);
+    let mut out = String::new();
+    let mut tag = |name: &str, value: &str| {
+        let _ = writeln!(out, "{name}: {}", tag_value(value));
+    };
+    tag("SPDXVersion", document.spdx_version);
+    tag("DataLicense", document.data_license);
+    tag("SPDXID", document.spdx_id);
+    tag("DocumentName", &document.name);
+    tag("DocumentNamespace", &document.document_namespace);
+    for creator in &document.creation_info.creators {
+        tag("Creator", creator);
+    }
+    tag("Created", &document.creation_info.created);
+
+    for package in &document.packages {
+        out.push('\n');
+        let mut tag = |name: &str, value: &str| {
+            let _ = writeln!(out, "{name}: {}", tag_value(value));
+        };
+        tag("PackageName", &package.name);
+        tag("SPDXID", &package.spdx_id);
+        tag("PackageVersion", &package.version_info);
+        tag("PackageDownloadLocation", &package.download_location);
+        tag("FilesAnalyzed", "false");
+        for checksum in &package.checksums {
+            tag(
+                "PackageChecksum",
+                &format!("{}: {}", checksum.algorithm, checksum.checksum_value),
+            );
+        }
+        if let Some(homepage) = &package.homepage {
+            tag("PackageHomePage", homepage);
+        }
+        if let Some(summary) = &package.summary {
+            tag("PackageSummary", summary);
+        }
+        tag("PackageLicenseConcluded", package.license_concluded);
+        tag("PackageLicenseDeclared", &package.license_declared);
+        tag("PackageCopyrightText", package.copyright_text);
+        for reference in &package.external_refs {
+            tag(
+                "ExternalRef",
+                &format!(
+                    "{} {} {}",
+                    reference.reference_category,
+                    reference.reference_type,
+                    reference.reference_locator
+                ),
+            );
+        }
+        tag("PrimaryPackagePurpose", package.primary_package_purpose);
+    }
+
+    out.push('\n');
+    for relationship in &document.relationships {
+        let _ = writeln!(
+            out,
+            "Relationship: {} {} {}",
+            relationship.spdx_element_id,
+            relationship.relationship_type,
+            relationship.related_spdx_element
+        );
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Multi-line values are wrapped in `<text>` as the format requires.
+fn tag_value(value: &str) -> String {
+    if value.contains('\n') {
+        format!("<text>{value}</text>")
+    } else {
+        value.to_string()
+    }
+}
+
+fn document(sbom: &Sbom<'_>) -> SpdxDocument {
+    let ids = spdx_ids(sbom);
+    let id = |bom_ref: &str| ids.get(bom_ref).cloned().unwrap_or_default();
+
+    let mut packages: Vec<SpdxPackage> = Vec::new();
+    for component in sbom.subject.iter().chain(&sbom.components) {
+        packages.push(package(component, id(&component.bom_ref)));
+    }
+
+    let describes: Vec<String> = match &sbom.subject {
+        Some(subject) => vec![id(&subject.bom_ref)],
+        None => sbom.roots.iter().map(|root| id(root)).collect(),
+    };
+    let mut relationships: Vec<Relationship> = describes
+        .iter()
+        .map(|described| Relationship {
+            spdx_element_id: DOCUMENT_ID.to_string(),
+            relationship_type: "DESCRIBES",
+            related_spdx_element: described.clone(),
+        })
+        .collect();
+    for (from, dependencies) in &sbom.dependencies {
+        for dependency in dependencies {
+            let (from, to) = (id(from), id(&dependency.bom_ref));
+            // `A DEPENDS_ON B`, but `B DEV_DEPENDENCY_OF A`.
+            if dependency.normal {
+                relationships.push(Relationship {
+                    spdx_element_id: from.clone(),
+                    relationship_type: "DEPENDS_ON",
+                    related_spdx_element: to.clone(),
+                });
+            }
+            for (declared, relationship_type) in [
+                (dependency.dev, "DEV_DEPENDENCY_OF"),
+                (dependency.build, "BUILD_DEPENDENCY_OF"),
+            ] {
+                if declared {
+                    relationships.push(Relationship {
+                        spdx_element_id: to.clone(),
+                        relationship_type,
+                        related_spdx_element: from.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let created = timestamp();
+    let version = sbom
+        .subject
+        .as_ref()
+        .map(|subject| subject.package.version.to_string());
+    SpdxDocument {
+        spdx_version: "SPDX-2.3",
+        data_license: "CC0-1.0",
+        spdx_id: DOCUMENT_ID,
+        document_namespace: namespace(&sbom.name, version.as_deref(), &created),
+        name: sbom.name.clone(),
+        creation_info: CreationInfo {
+            created,
+            creators: vec![format!(
+                "Tool: {}-{}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )],
+        },
+        document_describes: describes,
+        packages,
+        relationships,
+    }
+}
+
+/// A URI unique to this document. Without a UUID source, the creation time
+/// tells documents for the same package apart.
+fn namespace(name: &str, version: Option<&str>, created: &str) -> String {
+    let stamp: String = created.chars().filter(char::is_ascii_digit).collect();
+    match version {
+        Some(version) => format!("https://spdx.org/spdxdocs/{name}-{version}-{stamp}"),
+        None => format!("https://spdx.org/spdxdocs/{name}-{stamp}"),
+    }
+}
+
+/// Assigns each component an `SPDXRef-` identifier from its name and version,
+/// numbering any that collide (the same crate from two sources).
+fn spdx_ids(sbom: &Sbom<'_>) -> HashMap<String, String> {
+    let mut taken = HashSet::new();
+    let mut ids = HashMap::new();
+    for component in sbom.subject.iter().chain(&sbom.components) {
+        let base: String = format!(
+            "SPDXRef-Package-{}-{}",
+            component.package.name, component.package.version
+        )
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(id.clone()) {
+            id = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        ids.insert(component.bom_ref.clone(), id);
+    }
+    ids
+}
+
+fn package(component: &Component<'_>, spdx_id: String) -> SpdxPackage {
+    let package = component.package;
+    SpdxPackage {
+        name: package.name.to_string(),
+        spdx_id,
+        version_info: package.version.to_string(),
+        download_location: download_location(component),
+        files_analyzed: false,
+        checksums: component
+            .checksum
+            .iter()
+            .map(|checksum| Checksum {
+                algorithm: "SHA256",
+                checksum_value: checksum.clone(),
+            })
+            .collect(),
+        homepage: package.homepage.clone(),
+        summary: package
+            .description
+            .as_ref()
+            .map(|description| description.trim().to_string()),
+        license_concluded: NOASSERTION,
+        // Expressions that do not parse cannot be declared as written.
+        license_declared: package
+            .license
+            .as_deref()
+            .and_then(|license| LicenseExpr::parse(license).ok())
+            .map_or_else(|| NOASSERTION.to_string(), |expr| expr.to_string()),
+        copyright_text: NOASSERTION,
+        external_refs: component
+            .purl
+            .iter()
+            .map(|purl| ExternalRef {
+                reference_category: "PACKAGE-MANAGER",
+                reference_type: "purl",
+                reference_locator: purl.clone(),
+            })
+            .collect(),
+        primary_package_purpose: if component.is_application() {
+            "APPLICATION"
+        } else {
+            "LIBRARY"
+        },
+    }
+}
+
+/// Where the crate can be fetched: the crates.io download URL, the pinned git
+/// commit, or `NOASSERTION` for path crates and other registries.
+fn download_location(component: &Component<'_>) -> String {
+    let package = component.package;
+    let Some(source) = &package.source else {
+        return NOASSERTION.to_string();
+    };
+    if source.is_crates_io() {
+        return format!(
+            "https://crates.io/api/v1/crates/{}/{}/download",
+            package.name, package.version
+        );
+    }
+    git_location(&source.repr).unwrap_or_else(|| NOASSERTION.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sbom::SbomDependency;
+    use cargo_metadata::Package;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    fn package(name: &str, license: Option<&str>, source: Option<&str>) -> Package {
+        serde_json::from_value(json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "license": license,
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "edition": "2021",
+        }))
+        .expect("package")
+    }
+
+    fn component(package: &Package) -> Component<'_> {
+        let bom_ref = format!("{} 1.0.0", package.name);
+        Component {
+            package,
+            bom_ref,
+            purl: None,
+            checksum: None,
+            dev_only: false,
+        }
+    }
+
+    fn dependency(bom_ref: &str, normal: bool, dev: bool, build: bool) -> SbomDependency {
+        SbomDependency {
+            bom_ref: bom_ref.to_string(),
+            normal,
+            dev,
+            build,
+        }
+    }
+
+    fn relationships(document: &Value) -> Vec<String> {
+        document["relationships"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|relationship| {
+                format!(
+                    "{} {} {}",
+                    relationship["spdxElementId"].as_str().unwrap(),
+                    relationship["relationshipType"].as_str().unwrap(),
+                    relationship["relatedSpdxElement"].as_str().unwrap()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn render_json_maps_dependency_kinds_to_relationships() {
+        let app = package("app", Some("MIT/Apache-2.0"), None);
+        let serde = package(
+            "serde",
+            Some("MIT OR Apache-2.0"),
+            Some("registry+https://github.com/rust-lang/crates.io-index"),
+        );
+        let cc = package("cc", Some("Custom license"), None);
+        let mockall = package(
+            "mockall",
+            None,
+            Some("git+https://github.com/example/mockall?branch=main#abc123"),
+        );
+        let mut dependencies = BTreeMap::new();
+        dependencies.insert(
+            "app 1.0.0".to_string(),
+            vec![
+                dependency("cc 1.0.0", false, false, true),
+                dependency("mockall 1.0.0", false, true, false),
+                dependency("serde 1.0.0", true, true, false),
+            ],
+        );
+        let sbom = Sbom {
+            name: "app".to_string(),
+            subject: Some(component(&app)),
+            roots: vec!["app 1.0.0".to_string()],
+            components: vec![component(&cc), component(&mockall), component(&serde)],
+            dependencies,
+            vulnerabilities: Vec::new(),
+        };
+
+        let document: Value = serde_json::from_str(&render_json(&sbom).unwrap()).unwrap();
+
+        assert_eq!(document["spdxVersion"], "SPDX-2.3");
+        assert_eq!(document["SPDXID"], "SPDXRef-DOCUMENT");
+        assert_eq!(
+            document["documentDescribes"],
+            json!(["SPDXRef-Package-app-1.0.0"])
+        );
+        assert_eq!(
+            relationships(&document),
+            vec![
+                "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-app-1.0.0",
+                "SPDXRef-Package-cc-1.0.0 BUILD_DEPENDENCY_OF SPDXRef-Package-app-1.0.0",
+                "SPDXRef-Package-mockall-1.0.0 DEV_DEPENDENCY_OF SPDXRef-Package-app-1.0.0",
+                "SPDXRef-Package-app-1.0.0 DEPENDS_ON SPDXRef-Package-serde-1.0.0",
+                "SPDXRef-Package-serde-1.0.0 DEV_DEPENDENCY_OF SPDXRef-Package-app-1.0.0",
+            ]
+        );
+
+        let packages = document["packages"].as_array().unwrap();
+        assert_eq!(packages[0]["licenseDeclared"], "MIT OR Apache-2.0");
+        assert_eq!(packages[0]["downloadLocation"], NOASSERTION);
+        assert_eq!(packages[1]["licenseDeclared"], NOASSERTION);
+        assert_eq!(
+            packages[2]["downloadLocation"],
+            "git+https://github.com/example/mockall@abc123"
+        );
+        assert_eq!(
+            packages[3]["downloadLocation"],
+            "https://crates.io/api/v1/crates/serde/1.0.0/download"
+        );
+    }
+
+    #[test]
+    fn render_tag_value_lists_packages_and_relationships() {
+        let core = package("core-lib", Some("MIT"), None);
+        let cli = package("cli", Some("MIT"), None);
+        let mut dependencies = BTreeMap::new();
+        dependencies.insert(
+            "cli 1.0.0".to_string(),
+            vec![dependency("core-lib 1.0.0", true, false, false)],
+        );
+        let sbom = Sbom {
+            name: "workspace".to_string(),
+            subject: None,
+            roots: vec!["cli 1.0.0".to_string(), "core-lib 1.0.0".to_string()],
+            components: vec![component(&cli), component(&core)],
+            dependencies,
+            vulnerabilities: Vec::new(),
+        };
+
+        let document = render_tag_value(&sbom).unwrap();
+
+        assert!(document.starts_with("SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\n"));
+        assert!(document.contains("\nDocumentName: workspace\n"));
+        assert!(document.contains(
+            "\nPackageName: core-lib\nSPDXID: SPDXRef-Package-core-lib-1.0.0\nPackageVersion: 1.0.0\n"
+        ));
+        assert!(document.contains("\nPackageLicenseDeclared: MIT\n"));
+        assert!(document
+            .contains("Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-core-lib-1.0.0"));
+        assert!(document.ends_with(
+            "Relationship: SPDXRef-Package-cli-1.0.0 DEPENDS_ON SPDXRef-Package-core-lib-1.0.0"
+        ));
+    }
+}
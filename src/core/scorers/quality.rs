@@ -1,8 +1,9 @@
-use crate::core::output::{Grade, MetricScore, QualityOutput};
+use crate::core::output::{Grade, MetricScore, MetricStatus, QualityOutput};
 
 // === Metric Weights ===
 // These weights determine how much each metric contributes to the overall quality score.
-// They must sum to 1.0 (100%).
+// The default metrics must sum to 1.0 (100%); the opt-in semver and license
+// metrics add their weight on top and are renormalized with the rest.
 
 pub const WEIGHT_DEPENDENCY_FRESHNESS: f32 = 0.20;
 pub const WEIGHT_SECURITY: f32 = 0.25;
@@ -10,6 +11,8 @@ pub const WEIGHT_UNUSED_DEPS: f32 = 0.15;
 pub const WEIGHT_UNSAFE_CODE: f32 = 0.15;
 pub const WEIGHT_CLIPPY: f32 = 0.15;
 pub const WEIGHT_MSRV: f32 = 0.10;
+pub const WEIGHT_SEMVER: f32 = 0.10;
+pub const WEIGHT_LICENSES: f32 = 0.10;
 
 // === Security Penalty Multipliers ===
 // These values define how much each severity level reduces the security score.
@@ -48,7 +51,6 @@ pub struct ClippySummary {
 pub enum MsrvStatus {
     Valid,
     Missing,
-    #[allow(dead_code)] // Will be used when MSRV validation is implemented
     Invalid,
 }
 
@@ -60,67 +62,108 @@ pub struct UnsafeSummary {
     pub total_unsafe: usize,
 }
 
+pub struct LicenseSummary {
+    /// Dependencies that break the license policy or lack a valid license.
+    pub violations: usize,
+}
+
+pub struct SemverSummary {
+    /// Breaking API changes not covered by the version bump in Cargo.toml.
+    pub unreleased_breaking: usize,
+}
+
+/// Input for a single quality metric.
+pub enum MetricInput<T> {
+    /// The metric was computed from analyzer output.
+    Measured(T),
+    /// The analyzer failed (missing tool, network error, ...); holds the reason.
+    Unavailable(String),
+    /// The metric was excluded from this run (for example via `--skip`).
+    Skipped,
+}
+
+/// How metrics whose analyzer failed contribute to the overall score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnavailablePolicy {
+    /// Leave the metric out and renormalize the remaining weights.
+    #[default]
+    Exclude,
+    /// Score the metric as zero with its full weight.
+    Zero,
+    /// Fail the quality run.
+    Fail,
+}
+
 pub struct QualityInputs {
-    pub dependency_freshness: DependencyFreshness,
-    pub security: SecuritySummary,
-    pub unused: Option<UnusedSummary>,
-    pub unsafe_code: Option<UnsafeSummary>,
-    pub clippy: Option<ClippySummary>,
-    pub msrv: MsrvStatus,
+    pub dependency_freshness: MetricInput<DependencyFreshness>,
+    pub security: MetricInput<SecuritySummary>,
+    pub unused: MetricInput<UnusedSummary>,
+    pub unsafe_code: MetricInput<UnsafeSummary>,
+    pub clippy: MetricInput<ClippySummary>,
+    pub msrv: MetricInput<MsrvStatus>,
+    pub semver: MetricInput<SemverSummary>,
+    pub licenses: MetricInput<LicenseSummary>,
 }
 
-pub fn score_quality(inputs: &QualityInputs) -> QualityOutput {
-    let freshness_score = dependency_freshness_score(&inputs.dependency_freshness);
-    let security_score = security_score(&inputs.security);
-    let unused_score = inputs
-        .unused
-        .as_ref()
-        .map(unused_deps_score)
-        .unwrap_or(100.0);
-    let unsafe_score = inputs
-        .unsafe_code
-        .as_ref()
-        .map(unsafe_code_score)
-        .unwrap_or(100.0);
-    let clippy_score = inputs.clippy.as_ref().map(clippy_score).unwrap_or(100.0);
-    let msrv_score = msrv_score(&inputs.msrv);
-
-    let breakdown = vec![
-        MetricScore {
-            name: "Dependency freshness".to_string(),
-            score: freshness_score,
-            weight: WEIGHT_DEPENDENCY_FRESHNESS,
-        },
-        MetricScore {
-            name: "Security".to_string(),
-            score: security_score,
-            weight: WEIGHT_SECURITY,
-        },
-        MetricScore {
-            name: "Unused dependencies".to_string(),
-            score: unused_score,
-            weight: WEIGHT_UNUSED_DEPS,
-        },
-        MetricScore {
-            name: "Unsafe code".to_string(),
-            score: unsafe_score,
-            weight: WEIGHT_UNSAFE_CODE,
-        },
-        MetricScore {
-            name: "Clippy".to_string(),
-            score: clippy_score,
-            weight: WEIGHT_CLIPPY,
-        },
-        MetricScore {
-            name: "MSRV".to_string(),
-            score: msrv_score,
-            weight: WEIGHT_MSRV,
-        },
+pub fn score_quality(inputs: &QualityInputs, policy: UnavailablePolicy) -> QualityOutput {
+    let mut breakdown = vec![
+        metric(
+            "Dependency freshness",
+            WEIGHT_DEPENDENCY_FRESHNESS,
+            &inputs.dependency_freshness,
+            policy,
+            dependency_freshness_score,
+        ),
+        metric(
+            "Security",
+            WEIGHT_SECURITY,
+            &inputs.security,
+            policy,
+            security_score,
+        ),
+        metric(
+            "Unused dependencies",
+            WEIGHT_UNUSED_DEPS,
+            &inputs.unused,
+            policy,
+            unused_deps_score,
+        ),
+        metric(
+            "Unsafe code",
+            WEIGHT_UNSAFE_CODE,
+            &inputs.unsafe_code,
+            policy,
+            unsafe_code_score,
+        ),
+        metric(
+            "Clippy",
+            WEIGHT_CLIPPY,
+            &inputs.clippy,
+            policy,
+            clippy_score,
+        ),
+        metric("MSRV", WEIGHT_MSRV, &inputs.msrv, policy, msrv_score),
+        metric(
+            "Semver",
+            WEIGHT_SEMVER,
+            &inputs.semver,
+            policy,
+            semver_score,
+        ),
+        metric(
+            "Licenses",
+            WEIGHT_LICENSES,
+            &inputs.licenses,
+            policy,
+            licenses_score,
+        ),
     ];
 
+    renormalize_weights(&mut breakdown);
+
     let score = breakdown
         .iter()
-        .map(|metric| metric.score * metric.weight)
+        .filter_map(|metric| metric.score.map(|score| score * metric.weight))
         .sum::<f32>();
 
     let grade = grade_for(score);
@@ -134,6 +177,60 @@ pub fn score_quality(inputs: &QualityInputs) -> QualityOutput {
     }
 }
 
+fn metric<T>(
+    name: &str,
+    weight: f32,
+    input: &MetricInput<T>,
+    policy: UnavailablePolicy,
+    scorer: impl Fn(&T) -> f32,
+) -> MetricScore {
+    let (score, weight, status, reason) = match input {
+        MetricInput::Measured(value) => (Some(scorer(value)), weight, MetricStatus::Measured, None),
+        MetricInput::Unavailable(reason) => {
+            let score = match policy {
+                UnavailablePolicy::Zero => Some(0.0),
+                // `Fail` is enforced by the caller; score like `Exclude` so the
+                // breakdown stays meaningful if it is rendered anyway.
+                UnavailablePolicy::Exclude | UnavailablePolicy::Fail => None,
+            };
+            let weight = if score.is_some() { weight } else { 0.0 };
+            (
+                score,
+                weight,
+                MetricStatus::Unavailable,
+                Some(reason.clone()),
+            )
+        }
+        MetricInput::Skipped => (None, 0.0, MetricStatus::Skipped, None),
+    };
+
+    MetricScore {
+        name: name.to_string(),
+        score,
+        weight,
+        status,
+        reason,
+    }
+}
+
+/// Rescales the weights of scored metrics so they sum to 1.0.
+///
+/// Skipped and excluded unavailable metrics keep a weight of zero, so running a
+/// subset of metrics still produces a score on the usual 0-100 scale.
+fn renormalize_weights(metrics: &mut [MetricScore]) {
+    let total: f32 = metrics
+        .iter()
+        .filter(|metric| metric.score.is_some())
+        .map(|metric| metric.weight)
+        .sum();
+    if total <= 0.0 {
+        return;
+    }
+    for metric in metrics.iter_mut().filter(|metric| metric.score.is_some()) {
+        metric.weight /= total;
+    }
+}
+
 fn dependency_freshness_score(input: &DependencyFreshness) -> f32 {
     if input.total == 0 {
         return 100.0;
@@ -184,6 +281,18 @@ fn msrv_score(status: &MsrvStatus) -> f32 {
     }
 }
 
+fn semver_score(summary: &SemverSummary) -> f32 {
+    // Each breaking change the version bump does not cover costs 25 points
+    let penalty = (summary.unreleased_breaking as u64).saturating_mul(25);
+    100u64.saturating_sub(penalty) as f32
+}
+
+fn licenses_score(summary: &LicenseSummary) -> f32 {
+    // Each non-compliant dependency costs 10 points
+    let penalty = (summary.violations as u64).saturating_mul(10);
+    100u64.saturating_sub(penalty) as f32
+}
+
 fn grade_for(score: f32) -> Grade {
     if score >= 90.0 {
         Grade::A
@@ -202,24 +311,40 @@ fn recommendations_for(metrics: &[MetricScore]) -> Vec<String> {
     let mut items = Vec::new();
 
     for metric in metrics {
+        if metric.status != MetricStatus::Measured {
+            continue;
+        }
+        let Some(score) = metric.score else {
+            continue;
+        };
         let message = match metric.name.as_str() {
-            "Dependency freshness" if metric.score < 90.0 => {
+            "Dependency freshness" if score < 90.0 => {
                 Some("Update outdated dependencies.".to_string())
             }
-            "Security" if metric.score < 90.0 => Some("Address security advisories.".to_string()),
-            "Unused dependencies" if metric.score < 100.0 => {
+            "Security" if score < 90.0 => Some("Address security advisories.".to_string()),
+            "Unused dependencies" if score < 100.0 => {
                 Some("Remove unused dependencies.".to_string())
             }
-            "Unsafe code" if metric.score < 100.0 => Some("Reduce unsafe code usage.".to_string()),
-            "Clippy" if metric.score < 90.0 => Some("Fix clippy warnings and errors.".to_string()),
-            "MSRV" if metric.score < 100.0 => {
-                Some("Declare a valid MSRV in Cargo.toml.".to_string())
+            "Unsafe code" if score < 100.0 => Some("Reduce unsafe code usage.".to_string()),
+            "Clippy" if score < 90.0 => Some("Fix clippy warnings and errors.".to_string()),
+            "MSRV" if score <= msrv_score(&MsrvStatus::Invalid) => Some(
+                "Raise rust-version or downgrade the dependencies that need a newer Rust."
+                    .to_string(),
+            ),
+            "MSRV" if score < 100.0 => {
+                Some("Declare an MSRV (rust-version) in Cargo.toml.".to_string())
+            }
+            "Semver" if score < 100.0 => {
+                Some("Bump the version to cover breaking API changes.".to_string())
+            }
+            "Licenses" if score < 100.0 => {
+                Some("Resolve dependency license violations.".to_string())
             }
             _ => None,
         };
 
         if let Some(message) = message {
-            let impact = (100.0 - metric.score) * metric.weight;
+            let impact = (100.0 - score) * metric.weight;
             items.push((impact, message));
         }
     }
@@ -307,6 +432,160 @@ mod tests {
         assert_close(msrv_score(&MsrvStatus::Invalid), 0.0);
     }
 
+    #[test]
+    fn msrv_recommendation_depends_on_status() {
+        let metric = |status| MetricScore {
+            name: "MSRV".to_string(),
+            score: Some(msrv_score(&status)),
+            weight: WEIGHT_MSRV,
+            status: MetricStatus::Measured,
+            reason: None,
+        };
+        assert_eq!(
+            recommendations_for(&[metric(MsrvStatus::Invalid)]),
+            vec!["Raise rust-version or downgrade the dependencies that need a newer Rust."]
+        );
+        assert_eq!(
+            recommendations_for(&[metric(MsrvStatus::Missing)]),
+            vec!["Declare an MSRV (rust-version) in Cargo.toml."]
+        );
+        assert!(recommendations_for(&[metric(MsrvStatus::Valid)]).is_empty());
+    }
+
+    #[test]
+    fn semver_score_applies_penalties() {
+        assert_close(
+            semver_score(&SemverSummary {
+                unreleased_breaking: 0,
+            }),
+            100.0,
+        );
+        assert_close(
+            semver_score(&SemverSummary {
+                unreleased_breaking: 3,
+            }),
+            25.0,
+        );
+        assert_close(
+            semver_score(&SemverSummary {
+                unreleased_breaking: 10,
+            }),
+            0.0,
+        );
+    }
+
+    #[test]
+    fn licenses_score_applies_penalties() {
+        assert_close(licenses_score(&LicenseSummary { violations: 0 }), 100.0);
+        assert_close(licenses_score(&LicenseSummary { violations: 3 }), 70.0);
+        assert_close(licenses_score(&LicenseSummary { violations: 12 }), 0.0);
+    }
+
+    #[test]
+    fn score_quality_renormalizes_opt_in_semver_metric() {
+        let mut inputs = all_measured();
+        inputs.semver = MetricInput::Measured(SemverSummary {
+            unreleased_breaking: 2,
+        });
+
+        let output = score_quality(&inputs, UnavailablePolicy::Exclude);
+        let semver = &output.breakdown[6];
+        assert_eq!(semver.status, MetricStatus::Measured);
+        assert_close(semver.weight, WEIGHT_SEMVER / 1.10);
+        // Freshness scores 50 and semver 50, out of a total base weight of 1.10.
+        assert_close(
+            output.score,
+            100.0 - 50.0 * (0.20 / 1.10) - 50.0 * (0.10 / 1.10),
+        );
+        assert!(output
+            .recommendations
+            .contains(&"Bump the version to cover breaking API changes.".to_string()));
+    }
+
+    fn all_measured() -> QualityInputs {
+        QualityInputs {
+            dependency_freshness: MetricInput::Measured(DependencyFreshness {
+                total: 10,
+                outdated: 5,
+            }),
+            security: MetricInput::Measured(SecuritySummary {
+                critical: 0,
+                high: 0,
+                moderate: 0,
+                low: 0,
+            }),
+            unused: MetricInput::Measured(UnusedSummary { unused_count: 0 }),
+            unsafe_code: MetricInput::Measured(UnsafeSummary { total_unsafe: 0 }),
+            clippy: MetricInput::Measured(ClippySummary {
+                warnings: 0,
+                errors: 0,
+            }),
+            msrv: MetricInput::Measured(MsrvStatus::Valid),
+            semver: MetricInput::Skipped,
+            licenses: MetricInput::Skipped,
+        }
+    }
+
+    #[test]
+    fn score_quality_uses_base_weights_when_all_measured() {
+        let output = score_quality(&all_measured(), UnavailablePolicy::Exclude);
+        // Freshness scores 50 with weight 0.20; everything else is perfect.
+        assert_close(output.score, 90.0);
+        let total_weight: f32 = output.breakdown.iter().map(|metric| metric.weight).sum();
+        assert_close(total_weight, 1.0);
+    }
+
+    #[test]
+    fn score_quality_renormalizes_over_measured_metrics() {
+        let mut inputs = all_measured();
+        inputs.security = MetricInput::Skipped;
+        inputs.clippy = MetricInput::Skipped;
+
+        let output = score_quality(&inputs, UnavailablePolicy::Exclude);
+        let security = &output.breakdown[1];
+        assert_eq!(security.status, MetricStatus::Skipped);
+        assert!(security.score.is_none());
+        assert_close(security.weight, 0.0);
+
+        // Remaining base weights: 0.20 + 0.15 + 0.15 + 0.10 = 0.60
+        let freshness = &output.breakdown[0];
+        assert_close(freshness.weight, WEIGHT_DEPENDENCY_FRESHNESS / 0.60);
+        assert_close(output.score, 100.0 - 50.0 * (0.20 / 0.60));
+        let total_weight: f32 = output.breakdown.iter().map(|metric| metric.weight).sum();
+        assert_close(total_weight, 1.0);
+    }
+
+    #[test]
+    fn score_quality_excludes_unavailable_metrics_by_default() {
+        let mut inputs = all_measured();
+        inputs.clippy = MetricInput::Unavailable("clippy is not installed".to_string());
+
+        let output = score_quality(&inputs, UnavailablePolicy::Exclude);
+        let clippy = &output.breakdown[4];
+        assert_eq!(clippy.status, MetricStatus::Unavailable);
+        assert!(clippy.score.is_none());
+        assert_close(clippy.weight, 0.0);
+        assert_eq!(clippy.reason.as_deref(), Some("clippy is not installed"));
+        // Freshness (50) now weighs 0.20 / 0.85 instead of 0.20.
+        assert_close(output.score, 100.0 - 50.0 * (0.20 / 0.85));
+        assert!(!output
+            .recommendations
+            .contains(&"Fix clippy warnings and errors.".to_string()));
+    }
+
+    #[test]
+    fn score_quality_scores_unavailable_as_zero_when_requested() {
+        let mut inputs = all_measured();
+        inputs.clippy = MetricInput::Unavailable("clippy is not installed".to_string());
+
+        let output = score_quality(&inputs, UnavailablePolicy::Zero);
+        let clippy = &output.breakdown[4];
+        assert_eq!(clippy.status, MetricStatus::Unavailable);
+        assert_eq!(clippy.score, Some(0.0));
+        assert_close(clippy.weight, WEIGHT_CLIPPY);
+        assert_close(output.score, 90.0 - 100.0 * WEIGHT_CLIPPY);
+    }
+
     #[test]
     fn grade_for_respects_boundaries() {
         assert!(matches!(grade_for(92.0), Grade::A));
@@ -321,40 +600,55 @@ mod tests {
         let metrics = vec![
             MetricScore {
                 name: "Dependency freshness".to_string(),
-                score: 60.0,
+                score: Some(60.0),
                 weight: WEIGHT_DEPENDENCY_FRESHNESS,
+                status: MetricStatus::Measured,
+                reason: None,
             },
             MetricScore {
                 name: "Security".to_string(),
-                score: 70.0,
+                score: Some(70.0),
                 weight: WEIGHT_SECURITY,
+                status: MetricStatus::Measured,
+                reason: None,
             },
             MetricScore {
                 name: "Clippy".to_string(),
-                score: 80.0,
+                score: Some(80.0),
                 weight: WEIGHT_CLIPPY,
+                status: MetricStatus::Measured,
+                reason: None,
             },
             MetricScore {
                 name: "Unused dependencies".to_string(),
-                score: 90.0,
+                score: Some(90.0),
                 weight: WEIGHT_UNUSED_DEPS,
+                status: MetricStatus::Measured,
+                reason: None,
             },
             MetricScore {
                 name: "Unsafe code".to_string(),
-                score: 95.0,
+                score: Some(95.0),
                 weight: WEIGHT_UNSAFE_CODE,
+                status: MetricStatus::Measured,
+                reason: None,
             },
             MetricScore {
                 name: "MSRV".to_string(),
-                score: 50.0,
+                score: Some(50.0),
                 weight: WEIGHT_MSRV,
+                status: MetricStatus::Measured,
+                reason: None,
             },
         ];
 
         let recommendations = recommendations_for(&metrics);
         assert_eq!(recommendations[0], "Update outdated dependencies.");
         assert_eq!(recommendations[1], "Address security advisories.");
-        assert_eq!(recommendations[2], "Declare a valid MSRV in Cargo.toml.");
+        assert_eq!(
+            recommendations[2],
+            "Declare an MSRV (rust-version) in Cargo.toml."
+        );
         assert_eq!(recommendations[3], "Fix clippy warnings and errors.");
         assert_eq!(recommendations[4], "Remove unused dependencies.");
         assert_eq!(recommendations[5], "Reduce unsafe code usage.");
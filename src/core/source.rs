@@ -0,0 +1,68 @@
+//! Parsing of the package source strings cargo records for git dependencies.
+
+/// A git source as cargo writes it: `git+<url>?branch=main#<commit>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSource {
+    pub url: String,
+    /// `branch=...`, `tag=...` or `rev=...`; none follows the default branch.
+    pub reference: Option<String>,
+    /// The locked commit; only present on resolved packages.
+    pub commit: Option<String>,
+}
+
+impl GitSource {
+    /// Parses a `git+` source, returning `None` for any other kind.
+    pub fn parse(source: &str) -> Option<Self> {
+        let url = source.strip_prefix("git+")?;
+        let (url, commit) = match url.split_once('#') {
+            Some((url, commit)) => (url, Some(commit.to_string())),
+            None => (url, None),
+        };
+        let (url, reference) = match url.split_once('?') {
+            Some((url, query)) => (url, Some(query.to_string())),
+            None => (url, None),
+        };
+        Some(Self {
+            url: url.to_string(),
+            reference,
+            commit,
+        })
+    }
+
+    /// The branch named by a `branch=...` reference.
+    pub fn branch(&self) -> Option<&str> {
+        self.reference.as_deref()?.strip_prefix("branch=")
+    }
+
+    /// Whether the source tracks a branch, named or default, rather than a
+    /// tag or rev.
+    pub fn follows_branch(&self) -> bool {
+        self.reference.is_none() || self.branch().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_source_parses_reference_and_commit() {
+        assert_eq!(
+            GitSource::parse("git+https://github.com/o/r?branch=main#abc123"),
+            Some(GitSource {
+                url: "https://github.com/o/r".to_string(),
+                reference: Some("branch=main".to_string()),
+                commit: Some("abc123".to_string()),
+            })
+        );
+        assert_eq!(
+            GitSource::parse("git+https://github.com/o/r#abc123").map(|s| s.reference),
+            Some(None)
+        );
+        assert_eq!(
+            GitSource::parse("git+https://github.com/o/r?tag=v1").and_then(|s| s.commit),
+            None
+        );
+        assert!(GitSource::parse("registry+https://example.com").is_none());
+    }
+}
@@ -0,0 +1,278 @@
+//! SPDX license expressions as found in the `license` field of Cargo.toml.
+
+use std::fmt;
+
+use crate::core::error::{ErrorCode, Result, UpkeepError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpr {
+    License(LicenseReq),
+    And(Vec<LicenseExpr>),
+    Or(Vec<LicenseExpr>),
+}
+
+/// A single license, such as `GPL-2.0+` or `Apache-2.0 WITH LLVM-exception`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseReq {
+    pub id: String,
+    /// Set for `id+`, "this version or any later one".
+    pub or_later: bool,
+    pub exception: Option<String>,
+}
+
+impl LicenseExpr {
+    /// Parses an expression. Operators may be written in any case, and the
+    /// legacy `MIT/Apache-2.0` form still used by older crates reads as `OR`.
+    pub fn parse(expression: &str) -> Result<Self> {
+        let tokens = tokenize(expression);
+        if tokens.is_empty() {
+            return Err(invalid(expression, "empty expression"));
+        }
+        let mut parser = Parser {
+            expression,
+            tokens,
+            position: 0,
+        };
+        let parsed = parser.or_expr()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(parsed),
+            Some(token) => Err(invalid(expression, &format!("unexpected `{token}`"))),
+        }
+    }
+
+    /// Returns true when some choice of licenses permitted by the expression
+    /// consists only of licenses accepted by `accepts`.
+    pub fn is_satisfied_by(&self, accepts: &impl Fn(&LicenseReq) -> bool) -> bool {
+        match self {
+            LicenseExpr::License(license) => accepts(license),
+            LicenseExpr::And(terms) => terms.iter().all(|term| term.is_satisfied_by(accepts)),
+            LicenseExpr::Or(terms) => terms.iter().any(|term| term.is_satisfied_by(accepts)),
+        }
+    }
+
+    /// Every license mentioned by the expression, in order.
+    pub fn licenses(&self) -> Vec<&LicenseReq> {
+        match self {
+            LicenseExpr::License(license) => vec![license],
+            LicenseExpr::And(terms) | LicenseExpr::Or(terms) => {
+                terms.iter().flat_map(LicenseExpr::licenses).collect()
+            }
+        }
+    }
+}
+
+fn invalid(expression: &str, reason: &str) -> UpkeepError {
+    UpkeepError::message(
+        ErrorCode::InvalidData,
+        format!("invalid SPDX expression '{expression}': {reason}"),
+    )
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for ch in expression.chars() {
+        match ch {
+            '(' | ')' | '/' => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                tokens.push(ch.to_string());
+            }
+            _ if ch.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek_operator(&self, operator: &str) -> bool {
+        self.tokens
+            .get(self.position)
+            .is_some_and(|token| token.eq_ignore_ascii_case(operator))
+    }
+
+    fn or_expr(&mut self) -> Result<LicenseExpr> {
+        let mut terms = vec![self.and_expr()?];
+        while self.peek_operator("OR") || self.peek_operator("/") {
+            self.position += 1;
+            terms.push(self.and_expr()?);
+        }
+        Ok(collapse(terms, LicenseExpr::Or))
+    }
+
+    fn and_expr(&mut self) -> Result<LicenseExpr> {
+        let mut terms = vec![self.primary()?];
+        while self.peek_operator("AND") {
+            self.position += 1;
+            terms.push(self.primary()?);
+        }
+        Ok(collapse(terms, LicenseExpr::And))
+    }
+
+    fn primary(&mut self) -> Result<LicenseExpr> {
+        let Some(token) = self.tokens.get(self.position).cloned() else {
+            return Err(invalid(self.expression, "expected a license"));
+        };
+        self.position += 1;
+        if token == "(" {
+            let inner = self.or_expr()?;
+            if !self.peek_operator(")") {
+                return Err(invalid(self.expression, "missing `)`"));
+            }
+            self.position += 1;
+            return Ok(inner);
+        }
+        let is_operator = ["AND", "OR", "WITH", ")", "/"]
+            .iter()
+            .any(|operator| token.eq_ignore_ascii_case(operator));
+        if is_operator || !token.chars().all(is_id_char) {
+            return Err(invalid(
+                self.expression,
+                &format!("expected a license, found `{token}`"),
+            ));
+        }
+
+        let (id, or_later) = match token.strip_suffix('+') {
+            Some(id) if !id.is_empty() => (id.to_string(), true),
+            _ => (token, false),
+        };
+        let exception = if self.peek_operator("WITH") {
+            self.position += 1;
+            match self.tokens.get(self.position) {
+                Some(exception) if exception.chars().all(is_id_char) => {
+                    self.position += 1;
+                    Some(exception.clone())
+                }
+                _ => return Err(invalid(self.expression, "expected an exception after WITH")),
+            }
+        } else {
+            None
+        };
+        Ok(LicenseExpr::License(LicenseReq {
+            id,
+            or_later,
+            exception,
+        }))
+    }
+}
+
+fn is_id_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '-' | '.' | ':' | '+')
+}
+
+fn collapse(
+    mut terms: Vec<LicenseExpr>,
+    combine: fn(Vec<LicenseExpr>) -> LicenseExpr,
+) -> LicenseExpr {
+    if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        combine(terms)
+    }
+}
+
+impl fmt::Display for LicenseReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        if self.or_later {
+            write!(f, "+")?;
+        }
+        if let Some(exception) = &self.exception {
+            write!(f, " WITH {exception}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the expression in canonical form, parenthesizing nested terms.
+impl fmt::Display for LicenseExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (terms, operator) = match self {
+            LicenseExpr::License(license) => return write!(f, "{license}"),
+            LicenseExpr::And(terms) => (terms, " AND "),
+            LicenseExpr::Or(terms) => (terms, " OR "),
+        };
+        for (index, term) in terms.iter().enumerate() {
+            if index > 0 {
+                write!(f, "{operator}")?;
+            }
+            match term {
+                LicenseExpr::License(_) => write!(f, "{term}")?,
+                _ => write!(f, "({term})")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(expression: &LicenseExpr) -> Vec<String> {
+        expression
+            .licenses()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn parses_operators_and_precedence() {
+        let expression = LicenseExpr::parse("MIT OR Apache-2.0 AND BSD-3-Clause").unwrap();
+        assert_eq!(
+            expression.to_string(),
+            "MIT OR (Apache-2.0 AND BSD-3-Clause)"
+        );
+
+        let expression =
+            LicenseExpr::parse("(MIT or Apache-2.0 WITH LLVM-exception) AND GPL-2.0+").unwrap();
+        assert_eq!(
+            ids(&expression),
+            vec!["MIT", "Apache-2.0 WITH LLVM-exception", "GPL-2.0+"]
+        );
+        assert!(matches!(expression, LicenseExpr::And(_)));
+
+        let legacy = LicenseExpr::parse("MIT/Apache-2.0").unwrap();
+        assert_eq!(legacy.to_string(), "MIT OR Apache-2.0");
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expression in [
+            "",
+            "MIT OR",
+            "(MIT",
+            "MIT Apache-2.0",
+            "MIT WITH",
+            "AND MIT",
+        ] {
+            let err = LicenseExpr::parse(expression).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::InvalidData, "{expression}");
+        }
+    }
+
+    #[test]
+    fn is_satisfied_by_picks_any_acceptable_choice() {
+        let accepts = |license: &LicenseReq| license.id != "GPL-3.0";
+        let dual = LicenseExpr::parse("MIT OR GPL-3.0").unwrap();
+        assert!(dual.is_satisfied_by(&accepts));
+        let both = LicenseExpr::parse("MIT AND GPL-3.0").unwrap();
+        assert!(!both.is_satisfied_by(&accepts));
+    }
+}
@@ -0,0 +1,62 @@
+//! Recursive directory traversal shared by the analyzers that read files
+//! from disk.
+
+use std::fs::{self, Metadata};
+use std::path::Path;
+
+/// Calls `visit` with every regular file below `dir`, descending into the
+/// subdirectories for which `descend` returns true.
+///
+/// Symlinks are neither followed nor visited, so a link cycle cannot recurse
+/// forever. Unreadable entries are skipped.
+pub fn visit_files(
+    dir: &Path,
+    descend: &impl Fn(&Path) -> bool,
+    visit: &mut impl FnMut(&Path, &Metadata),
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if descend(&path) {
+                visit_files(&path, descend, visit);
+            }
+        } else if metadata.is_file() {
+            visit(&path, &metadata);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn visit_files_skips_excluded_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join("skip")).unwrap();
+        fs::write(root.join("a.rs"), "a").unwrap();
+        fs::write(root.join("src/nested/b.rs"), "b").unwrap();
+        fs::write(root.join("skip/c.rs"), "c").unwrap();
+
+        let skipped = root.join("skip");
+        let mut files: Vec<PathBuf> = Vec::new();
+        visit_files(root, &|path| path != skipped, &mut |path, _| {
+            files.push(path.strip_prefix(root).unwrap().to_path_buf());
+        });
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec![PathBuf::from("a.rs"), PathBuf::from("src/nested/b.rs")]
+        );
+    }
+}
@@ -18,12 +18,34 @@ async fn main() -> ExitCode {
     let command = match cli.command {
         cli::Command::Upkeep(command) => command,
         cli::Command::Detect => cli::UpkeepCommand::Detect,
-        cli::Command::Audit => cli::UpkeepCommand::Audit,
-        cli::Command::Deps { security } => cli::UpkeepCommand::Deps { security },
-        cli::Command::Quality => cli::UpkeepCommand::Quality,
-        cli::Command::Unused => cli::UpkeepCommand::Unused,
+        cli::Command::Audit(args) => cli::UpkeepCommand::Audit(args),
+        cli::Command::Deps {
+            security,
+            git,
+            target,
+        } => cli::UpkeepCommand::Deps {
+            security,
+            git,
+            target,
+        },
+        cli::Command::Quality(args) => cli::UpkeepCommand::Quality(args),
+        cli::Command::Unused(args) => cli::UpkeepCommand::Unused(args),
         cli::Command::UnsafeCode => cli::UpkeepCommand::UnsafeCode,
         cli::Command::Tree(args) => cli::UpkeepCommand::Tree(args),
+        cli::Command::Msrv(args) => cli::UpkeepCommand::Msrv(args),
+        cli::Command::Why(args) => cli::UpkeepCommand::Why(args),
+        cli::Command::Duplicates(args) => cli::UpkeepCommand::Duplicates(args),
+        cli::Command::Weight(args) => cli::UpkeepCommand::Weight(args),
+        cli::Command::Lockdiff(args) => cli::UpkeepCommand::Lockdiff(args),
+        cli::Command::Semver(args) => cli::UpkeepCommand::Semver(args),
+        cli::Command::Licenses(args) => cli::UpkeepCommand::Licenses(args),
+        cli::Command::Sbom(args) => cli::UpkeepCommand::Sbom(args),
+        cli::Command::Sources(args) => cli::UpkeepCommand::Sources(args),
+        cli::Command::Patches(args) => cli::UpkeepCommand::Patches(args),
+        cli::Command::Inherit(args) => cli::UpkeepCommand::Inherit(args),
+        cli::Command::Consistency => cli::UpkeepCommand::Consistency,
+        cli::Command::Features(args) => cli::UpkeepCommand::Features(args),
+        cli::Command::BuildTime(args) => cli::UpkeepCommand::BuildTime(args),
     };
 
     match cli::commands::handle(command, cli.json).await {
@@ -0,0 +1,98 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_crate, write_file};
+use serde_json::Value;
+use std::path::Path;
+
+/// `app` depends on `wrapper`, which pulls in the proc-macro `macros` and
+/// `sys`, whose build script links a native library. The dependencies live
+/// outside the workspace directory so they do not become members.
+fn create_workspace(root: &Path) {
+    write_crate(
+        root,
+        "deps/macros",
+        "[package]\nname = \"macros\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[lib]\nproc-macro = true\n",
+    );
+    write_crate(
+        root,
+        "deps/sys",
+        "[package]\nname = \"sys\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlinks = \"native\"\n",
+    );
+    write_file(&root.join("deps/sys/build.rs"), "fn main() {}\n");
+    write_crate(
+        root,
+        "deps/wrapper",
+        "[package]\nname = \"wrapper\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nmacros = { path = \"../macros\" }\nsys = { path = \"../sys\" }\n",
+    );
+    write_crate(
+        root,
+        "app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nwrapper = { path = \"../deps/wrapper\" }\n",
+    );
+}
+
+#[test]
+fn build_time_lists_build_scripts_and_proc_macros() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    create_workspace(temp_dir.path());
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(temp_dir.path().join("app"))
+        .args(["upkeep", "build-time", "--json"])
+        .output()
+        .expect("run build-time");
+    assert!(
+        output.status.success(),
+        "build-time failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let crates = output["crates"].as_array().expect("crates");
+    let summary: Vec<(&str, bool, bool)> = crates
+        .iter()
+        .map(|krate| {
+            (
+                krate["name"].as_str().expect("name"),
+                krate["build_script"].as_bool().expect("build_script"),
+                krate["proc_macro"].as_bool().expect("proc_macro"),
+            )
+        })
+        .collect();
+    assert_eq!(summary, vec![("macros", false, true), ("sys", true, false)]);
+    assert_eq!(crates[1]["links"], "native");
+    assert_eq!(crates[1]["via"], serde_json::json!(["wrapper"]));
+}
+
+#[test]
+fn build_time_fails_on_crates_missing_from_the_baseline() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    create_workspace(temp_dir.path());
+    let baseline = temp_dir.path().join("baseline.json");
+    write_file(&baseline, r#"{"crates":[{"name":"macros"}]}"#);
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(temp_dir.path().join("app"))
+        .args([
+            "upkeep",
+            "build-time",
+            "--json",
+            "--fail-on-new",
+            "--baseline",
+        ])
+        .arg(&baseline)
+        .output()
+        .expect("run build-time");
+    assert!(!output.status.success());
+    let report: Value = serde_json::from_slice(&output.stdout).expect("json output");
+    let new: Vec<&str> = report["crates"]
+        .as_array()
+        .expect("crates")
+        .iter()
+        .filter(|krate| krate["new"].as_bool().expect("new"))
+        .map(|krate| krate["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(new, vec!["sys"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not in the baseline: sys"));
+}
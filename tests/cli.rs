@@ -77,6 +77,20 @@ fn cli_subcommands_have_help() {
         "unused",
         "unsafe-code",
         "tree",
+        "msrv",
+        "why",
+        "duplicates",
+        "weight",
+        "lockdiff",
+        "semver",
+        "licenses",
+        "sbom",
+        "sources",
+        "patches",
+        "inherit",
+        "consistency",
+        "features",
+        "build-time",
     ];
 
     for subcommand in subcommands {
@@ -0,0 +1,50 @@
+//! Fixture helpers shared by the integration tests.
+
+// Each test binary compiles this module but only uses some of the helpers.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Writes `contents` to `path`, creating parent directories as needed.
+pub fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create dir");
+    }
+    fs::write(path, contents).expect("write file");
+}
+
+/// A minimal `[package]` manifest for `name` at `version`.
+pub fn package_manifest(name: &str, version: &str) -> String {
+    format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\nedition = \"2021\"\n")
+}
+
+/// Writes a library crate with `manifest` to `root/dir`.
+pub fn write_crate(root: &Path, dir: &str, manifest: &str) {
+    write_file(&root.join(dir).join("Cargo.toml"), manifest);
+    write_file(&root.join(dir).join("src/lib.rs"), "pub fn f() {}\n");
+}
+
+/// Writes workspace member `name` at version 0.1.0 to `root/name`, with
+/// `tables` such as `[dependencies]` appended to its manifest.
+pub fn write_member(root: &Path, name: &str, tables: &str) {
+    let manifest = format!("{}\n{tables}", package_manifest(name, "0.1.0"));
+    write_crate(root, name, &manifest);
+}
+
+/// Runs git in `dir` with a fixed identity and returns its trimmed stdout.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .expect("run git");
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_crate, write_file};
+use serde_json::Value;
+
+#[test]
+fn consistency_reports_member_differences() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    write_file(
+        &root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"cli\"]\nresolver = \"2\"\n\n[workspace.package]\nedition = \"2021\"\n",
+    );
+    write_crate(
+        root,
+        "app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition.workspace = true\n\n[dependencies]\nrand = \"0.7\"\nserde = { version = \"1\", default-features = false }\n",
+    );
+    write_crate(
+        root,
+        "cli",
+        "[package]\nname = \"cli\"\nversion = \"0.1.0\"\nedition = \"2018\"\n\n[dependencies]\nrand = \"0.8\"\nserde = \"1\"\n",
+    );
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args(["upkeep", "consistency", "--json"])
+        .output()
+        .expect("run consistency");
+    assert!(
+        output.status.success(),
+        "consistency failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let findings: Vec<(&str, &str)> = output["findings"]
+        .as_array()
+        .expect("findings")
+        .iter()
+        .map(|finding| {
+            (
+                finding["kind"].as_str().expect("kind"),
+                finding["name"].as_str().expect("name"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        findings,
+        vec![
+            ("incompatible_requirements", "rand"),
+            ("default_features", "serde"),
+            ("edition", "edition"),
+        ]
+    );
+    assert_eq!(
+        output["findings"][2]["members"],
+        serde_json::json!([{ "member": "cli", "value": "2018" }])
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{git, write_crate, write_file};
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// A crate `app` depending on the `main` branch of a local repository, locked
+/// at the tagged first commit before a second commit lands on `main`.
+fn create_git_workspace(temp_dir: &Path) -> (String, String) {
+    let repo = temp_dir.join("upstream");
+    write_crate(
+        temp_dir,
+        "upstream",
+        "[package]\nname = \"upkeep-git-fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "--quiet", "-m", "initial"]);
+    git(&repo, &["tag", "v0.1.0"]);
+    let pinned = git(&repo, &["rev-parse", "HEAD"]);
+
+    let app = temp_dir.join("app");
+    write_crate(
+        temp_dir,
+        "app",
+        &format!(
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nupkeep-git-fixture = {{ git = \"file://{}\", branch = \"main\" }}\n",
+            repo.display()
+        ),
+    );
+    let status = Command::new("cargo")
+        .current_dir(&app)
+        .env("CARGO_HOME", temp_dir.join("cargo-home"))
+        .arg("generate-lockfile")
+        .status()
+        .expect("run cargo generate-lockfile");
+    assert!(status.success(), "generate-lockfile failed");
+
+    write_file(&repo.join("src/lib.rs"), "pub fn f() {}\npub fn g() {}\n");
+    git(&repo, &["commit", "--quiet", "-am", "second"]);
+    let latest = git(&repo, &["rev-parse", "HEAD"]);
+
+    (pinned, latest)
+}
+
+#[test]
+fn deps_reports_git_dependencies_behind_their_branch() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let (pinned, latest) = create_git_workspace(temp_dir.path());
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(temp_dir.path().join("app"))
+        .env("CARGO_HOME", temp_dir.path().join("cargo-home"))
+        .args(["upkeep", "deps", "--json", "--git"])
+        .output()
+        .expect("run deps");
+    assert!(
+        output.status.success(),
+        "deps failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let git_dependencies = output["git_dependencies"]
+        .as_array()
+        .expect("git dependencies");
+    assert_eq!(git_dependencies.len(), 1);
+    let dependency = &git_dependencies[0];
+    assert_eq!(dependency["name"], "upkeep-git-fixture");
+    assert_eq!(dependency["reference"], "branch=main");
+    assert_eq!(dependency["status"], "behind");
+    assert_eq!(dependency["pinned"], pinned.as_str());
+    assert_eq!(dependency["latest_commit"], latest.as_str());
+    assert_eq!(dependency["commits_behind"], 1);
+    assert_eq!(dependency["pinned_tag"], "v0.1.0");
+    assert_eq!(dependency["latest_tag"], "v0.1.0");
+}
+
+#[test]
+fn deps_skips_git_remotes_without_the_flag() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    create_git_workspace(temp_dir.path());
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(temp_dir.path().join("app"))
+        .env("CARGO_HOME", temp_dir.path().join("cargo-home"))
+        .args(["upkeep", "deps", "--json"])
+        .output()
+        .expect("run deps");
+    assert!(output.status.success());
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    assert!(output.get("git_dependencies").is_none());
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_crate;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+fn create_duplicates_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\", \"crates/tool\"]\nexclude = [\"external/old\", \"external/dup_v1\", \"external/dup_v2\", \"external/helper\"]\n",
+    )
+    .expect("write workspace");
+    write_crate(
+        root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nold = { path = \"../../external/old\" }\ndup = { path = \"../../external/dup_v2\" }\n",
+    );
+    write_crate(
+        root,
+        "crates/tool",
+        "[package]\nname = \"tool\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ndup = { path = \"../../external/dup_v1\", version = \"0.1\" }\n",
+    );
+    write_crate(
+        root,
+        "external/old",
+        "[package]\nname = \"old\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ndup = { path = \"../dup_v1\" }\n",
+    );
+    write_crate(
+        root,
+        "external/dup_v1",
+        "[package]\nname = \"dup\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nhelper = { path = \"../helper\" }\n",
+    );
+    write_crate(
+        root,
+        "external/dup_v2",
+        "[package]\nname = \"dup\"\nversion = \"0.2.0\"\nedition = \"2021\"\n",
+    );
+    write_crate(
+        root,
+        "external/helper",
+        "[package]\nname = \"helper\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+fn run_duplicates_json(root: &Path, args: &[&str]) -> Value {
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(root)
+        .args(["upkeep", "duplicates", "--json"])
+        .args(args)
+        .output()
+        .expect("run duplicates");
+    assert!(
+        output.status.success(),
+        "duplicates failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn duplicates_lists_parents_requirements_and_impact() {
+    let workspace = create_duplicates_workspace();
+    let output = run_duplicates_json(workspace.path(), &[]);
+
+    let duplicates = output["duplicates"].as_array().expect("duplicates");
+    assert_eq!(duplicates.len(), 1);
+    let dup = &duplicates[0];
+    assert_eq!(dup["name"], "dup");
+    assert_eq!(dup["versions"][0]["version"], "0.1.0");
+    assert_eq!(dup["versions"][1]["version"], "0.2.0");
+    // dup 0.1.0 and the helper it pulls in.
+    assert_eq!(dup["impact"], 2);
+
+    let parents = dup["versions"][0]["parents"].as_array().expect("parents");
+    let names: Vec<&str> = parents
+        .iter()
+        .map(|parent| parent["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["old", "tool"]);
+    assert_eq!(parents[1]["req"], "^0.1");
+    assert_eq!(parents[1]["workspace_member"], true);
+
+    // Path parents have no registry releases; only the member can be edited.
+    let suggestions = dup["suggestions"].as_array().expect("suggestions");
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["parent"], "tool");
+    assert_eq!(suggestions[0]["requirement"], "0.2.0");
+    assert_eq!(suggestions[0]["unifies"], true);
+}
+
+#[test]
+fn duplicates_respects_package_scope() {
+    let workspace = create_duplicates_workspace();
+    let output = run_duplicates_json(workspace.path(), &["-p", "tool", "--offline"]);
+
+    assert!(output["duplicates"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_file, write_member};
+use serde_json::Value;
+use std::path::Path;
+
+/// `app` checks `std` in code, declares an `unused` feature and pulls in
+/// `helper` without a feature of its own, plus any `extra` feature lines.
+fn create_workspace(root: &Path, extra: &str) {
+    write_file(
+        &root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"helper\"]\nresolver = \"2\"\n",
+    );
+    write_member(
+        root,
+        "app",
+        &format!("[dependencies]\nhelper = {{ path = \"../helper\", optional = true }}\n\n[features]\ndefault = [\"std\"]\nstd = []\nunused = []\n{extra}"),
+    );
+    write_file(
+        &root.join("app/src/lib.rs"),
+        "#[cfg(feature = \"std\")]\npub fn f() {}\n",
+    );
+    write_member(root, "helper", "");
+}
+
+#[test]
+fn features_reports_hygiene_and_powerset_results() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    create_workspace(root, "");
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args([
+            "upkeep",
+            "features",
+            "-p",
+            "app",
+            "--powerset",
+            "--depth",
+            "1",
+            "--json",
+        ])
+        .output()
+        .expect("run features");
+    assert!(
+        output.status.success(),
+        "features failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let members = output["members"].as_array().expect("members");
+    assert_eq!(members.len(), 1);
+    let combinations = members[0]["powerset"].as_array().expect("powerset").len();
+    assert!(
+        stderr.contains(&format!(
+            "Checking {combinations} feature combinations across 1 member"
+        )),
+        "{stderr}"
+    );
+    let app = &members[0];
+    assert_eq!(app["name"], "app");
+    assert_eq!(app["unreferenced"], serde_json::json!(["unused"]));
+    assert_eq!(app["ungated_optional"], serde_json::json!(["helper"]));
+    assert_eq!(app["invalid"], serde_json::json!([]));
+
+    let checks: Vec<(String, bool)> = app["powerset"]
+        .as_array()
+        .expect("powerset")
+        .iter()
+        .map(|check| {
+            let features: Vec<&str> = check["features"]
+                .as_array()
+                .expect("features")
+                .iter()
+                .map(|feature| feature.as_str().expect("feature"))
+                .collect();
+            (
+                features.join(","),
+                check["passed"].as_bool().expect("passed"),
+            )
+        })
+        .collect();
+    assert!(checks.contains(&(String::new(), true)));
+    assert!(checks.contains(&("std".to_string(), true)));
+    assert!(checks.contains(&("unused".to_string(), true)));
+}
+
+#[test]
+fn features_reports_entries_cargo_cannot_resolve() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    create_workspace(root, "fast = [\"helper?/fast\"]\n");
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args(["upkeep", "features", "--json"])
+        .output()
+        .expect("run features");
+    assert!(
+        output.status.success(),
+        "features failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let app = &output["members"][0];
+    assert_eq!(app["name"], "app");
+    assert_eq!(app["invalid"][0]["feature"], "fast");
+    assert_eq!(app["invalid"][0]["entry"], "helper?/fast");
+    assert_eq!(
+        app["invalid"][0]["reason"],
+        "helper 0.1.0 has no feature fast"
+    );
+    assert_eq!(output["warnings"].as_array().expect("warnings").len(), 1);
+}
+
+#[test]
+fn features_treats_an_explicit_dep_feature_as_a_gate() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    create_workspace(root, "helper = [\"dep:helper\"]\n");
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args(["upkeep", "features", "-p", "app", "--json"])
+        .output()
+        .expect("run features");
+    assert!(
+        output.status.success(),
+        "features failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let app = &output["members"][0];
+    assert_eq!(app["ungated_optional"], serde_json::json!([]));
+    assert!(app["features"]
+        .as_array()
+        .expect("features")
+        .contains(&serde_json::json!("helper")));
+}
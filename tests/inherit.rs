@@ -0,0 +1,198 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_file, write_member};
+use serde_json::Value;
+use std::fs;
+
+fn create_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"cli\"]\nresolver = \"2\"\n\n[workspace.dependencies]\nlog = \"0.4\"\n",
+    )
+    .expect("write workspace");
+    write_member(
+        root,
+        "app",
+        "[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\nlog.workspace = true\nanyhow = \"1\"\n",
+    );
+    write_member(
+        root,
+        "cli",
+        "[dependencies]\nserde = \"1.0.200\"\nlog = \"0.4.20\"\n",
+    );
+    temp_dir
+}
+
+fn run_inherit(workspace: &tempfile::TempDir, apply: bool) -> Value {
+    let mut args = vec!["upkeep", "inherit", "--json"];
+    if apply {
+        args.push("--apply");
+    }
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args(args)
+        .output()
+        .expect("run inherit");
+    assert!(
+        output.status.success(),
+        "inherit failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn inherit_reports_shared_dependencies() {
+    let workspace = create_workspace();
+    let output = run_inherit(&workspace, false);
+
+    let candidates = output["candidates"].as_array().expect("candidates");
+    let names: Vec<&str> = candidates
+        .iter()
+        .map(|candidate| candidate["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(names, vec!["log", "serde"]);
+    assert_eq!(candidates[0]["requirement"], "0.4");
+    assert_eq!(candidates[0]["in_workspace"], true);
+    assert_eq!(candidates[1]["requirement"], "1.0.200");
+    assert_eq!(
+        candidates[1]["declarations"][0]["features"],
+        serde_json::json!(["derive"])
+    );
+    assert!(output["applied"].as_array().expect("applied").is_empty());
+}
+
+#[test]
+fn inherit_apply_hoists_into_workspace_dependencies() {
+    let workspace = create_workspace();
+    let output = run_inherit(&workspace, true);
+    assert_eq!(output["applied"], serde_json::json!(["log", "serde"]));
+
+    let root = fs::read_to_string(workspace.path().join("Cargo.toml")).expect("read root");
+    assert!(
+        root.contains("log = \"0.4\"\nserde = \"1.0.200\"\n"),
+        "{root}"
+    );
+    let app = fs::read_to_string(workspace.path().join("app/Cargo.toml")).expect("read app");
+    assert!(
+        app.contains("serde = { workspace = true, features = [\"derive\"] }\nlog.workspace = true\nanyhow = \"1\"\n"),
+        "{app}"
+    );
+    let cli = fs::read_to_string(workspace.path().join("cli/Cargo.toml")).expect("read cli");
+    assert!(
+        cli.contains("serde = { workspace = true }\nlog = { workspace = true }\n"),
+        "{cli}"
+    );
+
+    let rerun = run_inherit(&workspace, false);
+    assert!(rerun["candidates"]
+        .as_array()
+        .expect("candidates")
+        .is_empty());
+}
+
+#[test]
+fn inherit_apply_skips_incompatible_requirements() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    let workspace = "[workspace]\nmembers = [\"app\", \"cli\"]\nresolver = \"2\"\n\n[workspace.dependencies]\nlog = \"0.3\"\n";
+    fs::write(root.join("Cargo.toml"), workspace).expect("write workspace");
+    let app = "[dependencies]\nrand = \"0.7\"\nlog = \"0.4\"\n";
+    let cli = "[dependencies]\nrand = \"0.8\"\nlog = \"0.4.20\"\n";
+    write_member(root, "app", app);
+    write_member(root, "cli", cli);
+
+    let output = run_inherit(&temp_dir, true);
+
+    assert!(output["applied"].as_array().expect("applied").is_empty());
+    let warnings = output["warnings"].as_array().expect("warnings");
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.as_str().unwrap().starts_with("rand: ")));
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.as_str().unwrap().starts_with("log: ")));
+    assert_eq!(
+        fs::read_to_string(root.join("Cargo.toml")).expect("read root"),
+        workspace
+    );
+    assert!(fs::read_to_string(root.join("app/Cargo.toml"))
+        .expect("read app")
+        .ends_with(app));
+    assert!(fs::read_to_string(root.join("cli/Cargo.toml"))
+        .expect("read cli")
+        .ends_with(cli));
+}
+
+fn create_registry_workspace(members: &[(&str, &str)]) -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    let names: Vec<String> = members
+        .iter()
+        .map(|(name, _)| format!("\"{name}\""))
+        .collect();
+    fs::write(
+        root.join("Cargo.toml"),
+        format!(
+            "[workspace]\nmembers = [{}]\nresolver = \"2\"\n",
+            names.join(", ")
+        ),
+    )
+    .expect("write workspace");
+    write_file(
+        &root.join(".cargo/config.toml"),
+        "[registries.corp]\nindex = \"sparse+https://registry.example.com/index/\"\n",
+    );
+    for (name, dependencies) in members {
+        write_member(root, name, dependencies);
+    }
+    temp_dir
+}
+
+#[test]
+fn inherit_apply_keeps_the_alternate_registry() {
+    let corp = "[dependencies]\ninternal = { version = \"0.3\", registry = \"corp\" }\n";
+    let workspace = create_registry_workspace(&[("app", corp), ("cli", corp)]);
+
+    let output = run_inherit(&workspace, true);
+
+    assert_eq!(output["applied"], serde_json::json!(["internal"]));
+    assert_eq!(output["candidates"][0]["registry"], "corp");
+    let root = fs::read_to_string(workspace.path().join("Cargo.toml")).expect("read root");
+    assert!(
+        root.contains("internal = { version = \"0.3\", registry = \"corp\" }"),
+        "{root}"
+    );
+    let app = fs::read_to_string(workspace.path().join("app/Cargo.toml")).expect("read app");
+    assert!(app.contains("internal = { workspace = true }"), "{app}");
+}
+
+#[test]
+fn inherit_apply_skips_crates_from_different_registries() {
+    let corp = "[dependencies]\ninternal = { version = \"0.3\", registry = \"corp\" }\n";
+    let public = "[dependencies]\ninternal = \"0.3\"\n";
+    let workspace = create_registry_workspace(&[
+        ("app", corp),
+        ("cli", corp),
+        ("tool", public),
+        ("web", public),
+    ]);
+    let before = fs::read_to_string(workspace.path().join("Cargo.toml")).expect("read root");
+
+    let output = run_inherit(&workspace, true);
+
+    assert!(output["applied"].as_array().expect("applied").is_empty());
+    assert_eq!(
+        output["warnings"],
+        serde_json::json!(["internal: members use different registries, not moved automatically"])
+    );
+    assert_eq!(
+        fs::read_to_string(workspace.path().join("Cargo.toml")).expect("read root"),
+        before
+    );
+    let app = fs::read_to_string(workspace.path().join("app/Cargo.toml")).expect("read app");
+    assert!(app.ends_with(corp));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_crate;
+use serde_json::Value;
+use std::fs;
+
+fn create_licenses_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\"]\nexclude = [\"external\"]\n\n[workspace.metadata.upkeep.licenses]\nallow = [\"MIT\", \"Apache-2.0\"]\ndeny = [\"GPL-3.0-only\"]\nexceptions = [{ crate = \"vendored\", allow = [\"ISC\"] }]\n",
+    )
+    .expect("write workspace");
+    write_crate(
+        root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\npermissive = { path = \"../../external/permissive\" }\ncopyleft = { path = \"../../external/copyleft\" }\nvendored = { path = \"../../external/vendored\" }\nbare = { path = \"../../external/bare\" }\n",
+    );
+    write_crate(
+        root,
+        "external/permissive",
+        "[package]\nname = \"permissive\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"MIT OR Apache-2.0\"\n",
+    );
+    write_crate(
+        root,
+        "external/copyleft",
+        "[package]\nname = \"copyleft\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"GPL-3.0-only\"\n",
+    );
+    write_crate(
+        root,
+        "external/vendored",
+        "[package]\nname = \"vendored\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"ISC\"\n",
+    );
+    write_crate(
+        root,
+        "external/bare",
+        "[package]\nname = \"bare\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+#[test]
+fn licenses_applies_workspace_policy() {
+    let workspace = create_licenses_workspace();
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args(["upkeep", "licenses", "--json"])
+        .output()
+        .expect("run licenses");
+    assert!(
+        output.status.success(),
+        "licenses failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    assert_eq!(output["policy"], true);
+    let groups: Vec<&str> = output["groups"]
+        .as_array()
+        .expect("groups")
+        .iter()
+        .map(|group| group["license"].as_str().expect("license"))
+        .collect();
+    assert_eq!(
+        groups,
+        vec!["GPL-3.0-only", "ISC", "MIT OR Apache-2.0", "unlicensed"]
+    );
+
+    let violations: Vec<(&str, &str)> = output["violations"]
+        .as_array()
+        .expect("violations")
+        .iter()
+        .map(|violation| {
+            (
+                violation["name"].as_str().expect("name"),
+                violation["status"].as_str().expect("status"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        violations,
+        vec![("bare", "unlicensed"), ("copyleft", "denied")]
+    );
+}
+
+#[test]
+fn licenses_rejects_invalid_policy() {
+    let workspace = create_licenses_workspace();
+    fs::write(
+        workspace.path().join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\"]\nexclude = [\"external\"]\n\n[workspace.metadata.upkeep.licenses]\nallowed = [\"MIT\"]\n",
+    )
+    .expect("write workspace");
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args(["upkeep", "licenses"])
+        .output()
+        .expect("run licenses");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("workspace.metadata.upkeep"));
+}
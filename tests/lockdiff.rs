@@ -0,0 +1,91 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::git;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+const OLD_LOCK: &str = r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["serde"]
+
+[[package]]
+name = "serde"
+version = "1.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+const NEW_LOCK: &str = r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["itoa", "serde"]
+
+[[package]]
+name = "itoa"
+version = "1.0.11"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+fn run_lockdiff(root: &Path, args: &[&str]) -> Value {
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(root)
+        .args(["upkeep", "lockdiff", "--json", "--offline"])
+        .args(args)
+        .output()
+        .expect("run lockdiff");
+    assert!(
+        output.status.success(),
+        "lockdiff failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn lockdiff_compares_lockfile_paths() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    fs::write(temp_dir.path().join("old.lock"), OLD_LOCK).expect("write old");
+    fs::write(temp_dir.path().join("new.lock"), NEW_LOCK).expect("write new");
+
+    let output = run_lockdiff(temp_dir.path(), &["old.lock", "new.lock"]);
+
+    assert_eq!(output["added"][0]["name"], "itoa");
+    assert_eq!(output["upgraded"][0]["name"], "serde");
+    assert_eq!(output["upgraded"][0]["from"], "1.0.100");
+    assert_eq!(output["upgraded"][0]["to"], "1.0.200");
+    assert_eq!(output["upgraded"][0]["update_type"], "patch");
+    assert!(output["removed"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn lockdiff_reads_git_revisions() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    git(root, &["init", "--quiet"]);
+    fs::write(root.join("Cargo.lock"), OLD_LOCK).expect("write old");
+    git(root, &["add", "Cargo.lock"]);
+    git(root, &["commit", "--quiet", "-m", "old"]);
+    fs::write(root.join("Cargo.lock"), NEW_LOCK).expect("write new");
+
+    let output = run_lockdiff(root, &["HEAD", "Cargo.lock"]);
+    assert_eq!(output["old"], "HEAD");
+    assert_eq!(output["added"][0]["name"], "itoa");
+
+    // The reverse direction is a removal and a downgrade.
+    git(root, &["commit", "--quiet", "-am", "new"]);
+    let output = run_lockdiff(root, &["HEAD", "HEAD~1"]);
+    assert_eq!(output["removed"][0]["name"], "itoa");
+    assert_eq!(output["downgraded"][0]["to"], "1.0.100");
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_crate, write_file};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+fn create_msrv_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    write_file(
+        &root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\", \"crates/shared\"]\nexclude = [\"external/new_dep\", \"external/old_dep\", \"external/dev_dep\"]\n\n[workspace.package]\nrust-version = \"1.60\"\n",
+    );
+    write_crate(
+        root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.65\"\n\n[dependencies]\nold_dep = { path = \"../../external/old_dep\" }\nnew_dep = { path = \"../../external/new_dep\" }\n\n[dev-dependencies]\ndev_dep = { path = \"../../external/dev_dep\" }\n",
+    );
+    write_crate(
+        root,
+        "crates/shared",
+        "[package]\nname = \"shared\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version.workspace = true\n\n[dependencies]\nold_dep = { path = \"../../external/old_dep\" }\n",
+    );
+    write_crate(
+        root,
+        "external/old_dep",
+        "[package]\nname = \"old_dep\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.63\"\n",
+    );
+    write_crate(
+        root,
+        "external/new_dep",
+        "[package]\nname = \"new_dep\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.74\"\n",
+    );
+    write_crate(
+        root,
+        "external/dev_dep",
+        "[package]\nname = \"dev_dep\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.90\"\n",
+    );
+
+    temp_dir
+}
+
+fn run_msrv(root: &Path, extra_args: &[&str]) -> Value {
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(root)
+        .args(["upkeep", "msrv", "--json"])
+        .args(extra_args)
+        .output()
+        .expect("run msrv");
+    assert!(
+        output.status.success(),
+        "msrv failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+fn member<'a>(output: &'a Value, name: &str) -> &'a Value {
+    output["members"]
+        .as_array()
+        .expect("members")
+        .iter()
+        .find(|member| member["name"] == name)
+        .expect("member present")
+}
+
+#[test]
+fn msrv_reports_effective_version_and_forcing_crate() {
+    let workspace = create_msrv_workspace();
+    let output = run_msrv(workspace.path(), &[]);
+
+    let app = member(&output, "app");
+    assert_eq!(app["declared"], "1.65");
+    assert_eq!(app["effective"], "1.74");
+    assert_eq!(app["forced_by"][0]["name"], "new_dep");
+    assert_eq!(app["forced_by"].as_array().unwrap().len(), 1);
+
+    let shared = member(&output, "shared");
+    assert_eq!(shared["declared"], "1.60");
+    assert_eq!(shared["effective"], "1.63");
+}
+
+#[test]
+fn msrv_write_updates_package_and_workspace_manifests() {
+    let workspace = create_msrv_workspace();
+    let root = workspace.path();
+    let output = run_msrv(root, &["--write"]);
+
+    assert!(member(&output, "app")["written"]
+        .as_str()
+        .unwrap()
+        .ends_with("app/Cargo.toml"));
+    let app_manifest = fs::read_to_string(root.join("crates/app/Cargo.toml")).unwrap();
+    assert!(app_manifest.contains("rust-version = \"1.74\""));
+
+    let shared_manifest = fs::read_to_string(root.join("crates/shared/Cargo.toml")).unwrap();
+    assert!(shared_manifest.contains("rust-version.workspace = true"));
+    let root_manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(root_manifest.contains("rust-version = \"1.63\""));
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{git, package_manifest, write_crate};
+use serde_json::Value;
+use std::path::Path;
+
+/// A crate depending on `upstream` from a local git repository, with that
+/// source patched by a vendored copy and a patch for a crate nobody uses.
+fn create_patched_workspace(temp_dir: &Path) {
+    let repo = temp_dir.join("upstream");
+    write_crate(temp_dir, "upstream", &package_manifest("upstream", "0.1.0"));
+    git(&repo, &["init", "--quiet"]);
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "--quiet", "-m", "initial"]);
+
+    let app = temp_dir.join("app");
+    write_crate(
+        temp_dir,
+        "app",
+        &format!(
+            "{}\n[dependencies]\nupstream = {{ git = \"file://{repo}\" }}\n\n[patch.\"file://{repo}\"]\nupstream = {{ path = \"vendor/upstream\" }}\nstale = {{ path = \"vendor/stale\" }}\n",
+            package_manifest("app", "0.1.0"),
+            repo = repo.display()
+        ),
+    );
+    write_crate(
+        &app,
+        "vendor/upstream",
+        &package_manifest("upstream", "0.1.1"),
+    );
+    write_crate(&app, "vendor/stale", &package_manifest("stale", "1.0.0"));
+}
+
+#[test]
+fn patches_reports_used_and_unused_entries() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    create_patched_workspace(temp_dir.path());
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(temp_dir.path().join("app"))
+        .env("CARGO_HOME", temp_dir.path().join("cargo-home"))
+        .args(["upkeep", "patches", "--offline", "--json"])
+        .output()
+        .expect("run patches");
+    assert!(
+        output.status.success(),
+        "patches failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let patches = output["patches"].as_array().expect("patches");
+    let summary: Vec<(&str, bool)> = patches
+        .iter()
+        .map(|patch| {
+            (
+                patch["name"].as_str().expect("name"),
+                patch["used"].as_bool().expect("used"),
+            )
+        })
+        .collect();
+    assert_eq!(summary, vec![("upstream", true), ("stale", false)]);
+    assert_eq!(patches[0]["kind"], "patch");
+    assert_eq!(patches[0]["version"], "0.1.1");
+    assert_eq!(patches[0]["affects"], serde_json::json!(["app 0.1.0"]));
+    assert_eq!(patches[1]["version"], "1.0.0");
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_crate;
+use serde_json::Value;
+use std::fs;
+
+fn create_sbom_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    write_crate(
+        root,
+        ".",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"MIT\"\n\n[dependencies]\nhelper = { path = \"helper\" }\n\n[dev-dependencies]\nfixture = { path = \"fixture\" }\n",
+    );
+    write_crate(
+        root,
+        "helper",
+        "[package]\nname = \"helper\"\nversion = \"0.2.0\"\nedition = \"2021\"\nlicense = \"MIT OR Apache-2.0\"\n",
+    );
+    write_crate(
+        root,
+        "fixture",
+        "[package]\nname = \"fixture\"\nversion = \"0.3.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+fn component<'a>(components: &'a [Value], name: &str) -> &'a Value {
+    components
+        .iter()
+        .find(|component| component["name"] == name)
+        .unwrap_or_else(|| panic!("component {name}"))
+}
+
+#[test]
+fn sbom_writes_cyclonedx_document() {
+    let workspace = create_sbom_workspace();
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .env("SOURCE_DATE_EPOCH", "0")
+        .args(["upkeep", "sbom", "--format", "cyclonedx-json"])
+        .output()
+        .expect("run sbom");
+    assert!(
+        output.status.success(),
+        "sbom failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let bom: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["specVersion"], "1.5");
+    assert_eq!(bom["metadata"]["timestamp"], "1970-01-01T00:00:00Z");
+    assert_eq!(bom["metadata"]["component"]["name"], "app");
+
+    let components = bom["components"].as_array().expect("components");
+    assert_eq!(components.len(), 2);
+    let helper = component(components, "helper");
+    assert_eq!(helper["scope"], "required");
+    assert_eq!(helper["licenses"][0]["expression"], "MIT OR Apache-2.0");
+    // Path crates have no purl.
+    assert!(helper.get("purl").is_none());
+    assert_eq!(component(components, "fixture")["scope"], "excluded");
+
+    let app_ref = bom["metadata"]["component"]["bom-ref"].clone();
+    let app = bom["dependencies"]
+        .as_array()
+        .expect("dependencies")
+        .iter()
+        .find(|dependency| dependency["ref"] == app_ref)
+        .expect("app dependencies");
+    assert_eq!(app["dependsOn"].as_array().expect("dependsOn").len(), 2);
+}
+
+#[test]
+fn sbom_writes_to_output_file_without_dev_dependencies() {
+    let workspace = create_sbom_workspace();
+    cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args(["upkeep", "sbom", "--no-dev", "-o", "bom.json"])
+        .assert()
+        .success();
+
+    let bom: Value = serde_json::from_str(
+        &fs::read_to_string(workspace.path().join("bom.json")).expect("read bom"),
+    )
+    .expect("json document");
+    let names: Vec<&str> = bom["components"]
+        .as_array()
+        .expect("components")
+        .iter()
+        .map(|component| component["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(names, vec!["helper"]);
+}
+
+#[test]
+fn sbom_writes_spdx_relationships() {
+    let workspace = create_sbom_workspace();
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args(["upkeep", "sbom", "--format", "spdx"])
+        .output()
+        .expect("run sbom");
+    assert!(
+        output.status.success(),
+        "sbom failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let document: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    assert_eq!(document["spdxVersion"], "SPDX-2.3");
+    assert_eq!(document["name"], "app");
+    let relationships: Vec<String> = document["relationships"]
+        .as_array()
+        .expect("relationships")
+        .iter()
+        .map(|relationship| {
+            format!(
+                "{} {} {}",
+                relationship["spdxElementId"].as_str().expect("element"),
+                relationship["relationshipType"].as_str().expect("type"),
+                relationship["relatedSpdxElement"]
+                    .as_str()
+                    .expect("related")
+            )
+        })
+        .collect();
+    assert_eq!(
+        relationships,
+        vec![
+            "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-fixture-0.3.0 DEV_DEPENDENCY_OF SPDXRef-Package-app-0.1.0",
+            "SPDXRef-Package-app-0.1.0 DEPENDS_ON SPDXRef-Package-helper-0.2.0",
+        ]
+    );
+}
+
+#[test]
+fn sbom_rejects_vulnerabilities_for_spdx() {
+    let workspace = create_sbom_workspace();
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path())
+        .args([
+            "upkeep",
+            "sbom",
+            "--format",
+            "spdx-tag-value",
+            "--vulnerabilities",
+        ])
+        .output()
+        .expect("run sbom");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--vulnerabilities"));
+}
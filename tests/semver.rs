@@ -0,0 +1,24 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use std::fs;
+
+#[test]
+fn semver_requires_a_publishable_library() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).expect("create src");
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .expect("write manifest");
+    fs::write(root.join("src/main.rs"), "fn main() {}\n").expect("write main");
+
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args(["upkeep", "semver"])
+        .output()
+        .expect("run semver");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no publishable library crates"));
+}
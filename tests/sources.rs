@@ -0,0 +1,95 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_crate;
+use serde_json::Value;
+use std::fs;
+
+/// A workspace in `repo/` whose members depend on a crate inside the
+/// workspace and one next to it.
+fn create_sources_workspace(policy: &str) -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path().join("repo");
+
+    fs::create_dir_all(&root).expect("create repo");
+    fs::write(
+        root.join("Cargo.toml"),
+        format!(
+            "[workspace]\nmembers = [\"crates/app\", \"crates/cli\"]\nexclude = [\"vendor\"]\n{policy}"
+        ),
+    )
+    .expect("write workspace");
+    write_crate(
+        &root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nvendored = { path = \"../../vendor/vendored\" }\nsibling = { path = \"../../../sibling\" }\n",
+    );
+    write_crate(
+        &root,
+        "crates/cli",
+        "[package]\nname = \"cli\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\napp = { path = \"../app\" }\n",
+    );
+    write_crate(
+        &root,
+        "vendor/vendored",
+        "[package]\nname = \"vendored\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write_crate(
+        temp_dir.path(),
+        "sibling",
+        "[package]\nname = \"sibling\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+fn run_sources(workspace: &tempfile::TempDir) -> Value {
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(workspace.path().join("repo"))
+        .args(["upkeep", "sources", "--json"])
+        .output()
+        .expect("run sources");
+    assert!(
+        output.status.success(),
+        "sources failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn sources_flags_paths_outside_the_workspace() {
+    let workspace = create_sources_workspace("");
+    let output = run_sources(&workspace);
+
+    let packages: Vec<(&str, &str)> = output["packages"]
+        .as_array()
+        .expect("packages")
+        .iter()
+        .map(|package| {
+            (
+                package["name"].as_str().expect("name"),
+                package["kind"].as_str().expect("kind"),
+            )
+        })
+        .collect();
+    assert_eq!(packages, vec![("sibling", "path"), ("vendored", "path")]);
+
+    let violations = output["violations"].as_array().expect("violations");
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0]["name"], "sibling");
+    assert_eq!(violations[0]["introduced_by"], serde_json::json!(["app"]));
+}
+
+#[test]
+fn sources_honors_allow_external_paths() {
+    let workspace = create_sources_workspace(
+        "\n[workspace.metadata.upkeep.sources]\nallow-external-paths = true\n",
+    );
+    let output = run_sources(&workspace);
+
+    assert!(output["violations"]
+        .as_array()
+        .expect("violations")
+        .is_empty());
+}
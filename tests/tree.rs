@@ -1,16 +1,11 @@
+mod common;
+
 use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_file;
 use serde_json::Value;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 
-fn write_file(path: &Path, contents: &str) {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).expect("create dir");
-    }
-    fs::write(path, contents).expect("write file");
-}
-
 fn create_tree_workspace() -> tempfile::TempDir {
     let temp_dir = tempfile::tempdir().expect("temp dir");
     let root = temp_dir.path();
@@ -227,7 +222,7 @@ fn tree_features_flag_populates_features() {
         .map(|features| features.is_empty())
         .unwrap_or(false));
 
-    let with_features = run_tree(root, &["--features"]);
+    let with_features = run_tree(root, &["--show-features"]);
     let dep_a = find_node(&with_features["root"], "dep_a").expect("dep_a node");
     let features: HashSet<String> = dep_a
         .get("features")
@@ -242,6 +237,28 @@ fn tree_features_flag_populates_features() {
     assert!(features.contains("extra"));
 }
 
+#[test]
+fn tree_features_flag_selects_features_instead_of_annotating() {
+    let temp_dir = create_tree_workspace();
+    let root = temp_dir.path();
+
+    // `--features` used to be the annotation switch; it now takes a list of
+    // features to activate, so the bare flag is rejected.
+    let output = cargo_bin_cmd!("cargo-upkeep")
+        .current_dir(root)
+        .args(["tree", "--json", "--features"])
+        .output()
+        .expect("run tree");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features <FEATURES>"));
+
+    let output = run_tree(root, &["-p", "dep_a", "--features", "extra"]);
+    let features = output["root"]["features"]
+        .as_array()
+        .expect("features array");
+    assert!(features.is_empty());
+}
+
 #[test]
 fn tree_marks_dev_and_build_dependencies() {
     let temp_dir = create_tree_workspace();
@@ -299,3 +316,326 @@ fn tree_stats_reports_correct_counts() {
         "total_crates should be at least direct_deps"
     );
 }
+
+fn run_tree_text(root: &Path, args: &[&str]) -> String {
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(root)
+        .arg("tree")
+        .args(args)
+        .output()
+        .expect("run tree");
+
+    assert!(
+        output.status.success(),
+        "tree command failed.\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("stdout")
+}
+
+fn dot_node_id<'a>(dot: &'a str, label: &str) -> &'a str {
+    dot.lines()
+        .find(|line| line.contains(&format!("label=\"{label}\"")) && !line.contains("->"))
+        .and_then(|line| line.split_whitespace().next())
+        .expect("node present")
+}
+
+#[test]
+fn tree_dot_format_labels_kinds_and_duplicates() {
+    let temp_dir = create_tree_workspace();
+    let dot = run_tree_text(temp_dir.path(), &["--format", "dot"]);
+
+    assert!(dot.starts_with("digraph dependencies {"));
+    let app = dot_node_id(&dot, "app v0.1.0");
+    let dep_a = dot_node_id(&dot, "dep_a v0.1.0");
+    let build_only = dot_node_id(&dot, "build_only v0.1.0");
+    let dev_only = dot_node_id(&dot, "dev_only v0.1.0");
+
+    assert!(dot.contains(&format!(
+        "{dep_a} -> {build_only} [label=\"build [default]\", style=dashed]"
+    )));
+    assert!(dot.contains(&format!(
+        "{app} -> {dev_only} [label=\"dev [default]\", style=dashed]"
+    )));
+    // `extra` is enabled by dep_a's own default, not by the edge from app.
+    assert!(dot.contains(&format!("{app} -> {dep_a} [label=\"normal [default]\"]")));
+    assert!(dot
+        .lines()
+        .filter(|line| line.contains("label=\"dup v0."))
+        .all(|line| line.contains("color=\"#dc3545\"")));
+    assert!(dot.lines().any(
+        |line| line.contains("label=\"app v0.1.0\"") && line.contains("fillcolor=\"#cfe2ff\"")
+    ));
+}
+
+#[test]
+fn tree_mermaid_format_emits_flowchart() {
+    let temp_dir = create_tree_workspace();
+    let mermaid = run_tree_text(temp_dir.path(), &["--format", "mermaid", "--no-dev"]);
+
+    assert!(mermaid.starts_with("flowchart LR"));
+    assert!(mermaid.contains("[\"build_only v0.1.0\"]:::build"));
+    assert!(mermaid.contains("[\"dup v0.2.0\"]:::duplicate"));
+    assert!(mermaid.contains("-.->|\"build [default]\"|"));
+    assert!(!mermaid.contains("dev_only"));
+    assert!(mermaid.contains("classDef workspace_member"));
+}
+
+fn create_renamed_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    write_file(
+        &root.join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"MIT\"\n\n[dependencies]\nalias = { path = \"external/leaf\", package = \"leaf\", features = [\"fast\"] }\n\n[target.'cfg(unix)'.build-dependencies]\nalias = { path = \"external/leaf\", package = \"leaf\" }\n",
+    );
+    write_file(&root.join("src/lib.rs"), "pub fn app() {}\n");
+    write_file(
+        &root.join("external/leaf/Cargo.toml"),
+        "[package]\nname = \"leaf\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"Apache-2.0\"\n\n[features]\nfast = []\n",
+    );
+    write_file(&root.join("external/leaf/src/lib.rs"), "pub fn leaf() {}\n");
+
+    temp_dir
+}
+
+#[test]
+fn tree_graph_json_lists_packages_and_edges_once() {
+    let temp_dir = create_tree_workspace();
+    let output = run_tree_text(temp_dir.path(), &["--format", "graph-json"]);
+    let graph: Value = serde_json::from_str(&output).expect("graph json");
+
+    let packages = graph["packages"].as_array().expect("packages");
+    let mut ids: Vec<&str> = packages
+        .iter()
+        .map(|package| package["id"].as_str().unwrap())
+        .collect();
+    let total = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), total);
+    assert_eq!(
+        packages
+            .iter()
+            .filter(|package| package["name"] == "dup")
+            .count(),
+        2
+    );
+
+    let edges = graph["edges"].as_array().expect("edges");
+    let build_edge = edges
+        .iter()
+        .find(|edge| edge["to"].as_str().unwrap().contains("build_only"))
+        .expect("build edge");
+    assert_eq!(build_edge["kinds"][0]["kind"], "build");
+    assert!(build_edge["kinds"][0]["target"].is_null());
+}
+
+#[test]
+fn tree_graph_json_reports_targets_renames_and_licenses() {
+    let temp_dir = create_renamed_workspace();
+    let output = run_tree_text(temp_dir.path(), &["--format", "graph-json"]);
+    let graph: Value = serde_json::from_str(&output).expect("graph json");
+
+    let leaf = graph["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|package| package["name"] == "leaf")
+        .expect("leaf package");
+    assert_eq!(leaf["license"], "Apache-2.0");
+    assert_eq!(leaf["features"], serde_json::json!(["fast"]));
+
+    let edges = graph["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["renamed_as"], "alias");
+    assert_eq!(edges[0]["features"], serde_json::json!(["default", "fast"]));
+    let kinds = edges[0]["kinds"].as_array().unwrap();
+    assert!(kinds
+        .iter()
+        .any(|kind| kind["kind"] == "normal" && kind["target"].is_null()));
+    assert!(kinds
+        .iter()
+        .any(|kind| kind["kind"] == "build" && kind["target"] == "cfg(unix)"));
+}
+
+#[test]
+fn tree_target_filters_platform_specific_dependencies() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+    write_file(
+        &root.join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[target.'cfg(windows)'.dependencies]\nwin_only = { path = \"external/win_only\" }\n\n[target.'cfg(target_os = \"linux\")'.dependencies]\nlinux_only = { path = \"external/linux_only\" }\n",
+    );
+    write_file(&root.join("src/lib.rs"), "pub fn app() {}\n");
+    for name in ["win_only", "linux_only"] {
+        write_file(
+            &root.join(format!("external/{name}/Cargo.toml")),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+        );
+        write_file(
+            &root.join(format!("external/{name}/src/lib.rs")),
+            "pub fn f() {}\n",
+        );
+    }
+
+    let output = run_tree(root, &[]);
+    assert!(find_node(&output["root"], "win_only").is_some());
+    assert!(find_node(&output["root"], "linux_only").is_some());
+
+    let output = run_tree(root, &["--target", "x86_64-unknown-linux-gnu"]);
+    assert!(find_node(&output["root"], "win_only").is_none());
+    assert!(find_node(&output["root"], "linux_only").is_some());
+
+    let output = run_tree(
+        root,
+        &[
+            "--target",
+            "x86_64-unknown-linux-gnu",
+            "--target",
+            "x86_64-pc-windows-msvc",
+        ],
+    );
+    assert!(find_node(&output["root"], "win_only").is_some());
+    assert!(find_node(&output["root"], "linux_only").is_some());
+}
+
+fn create_feature_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    write_file(
+        &root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\", \"crates/tool\"]\nexclude = [\"external/fancy\", \"external/extra\"]\n",
+    );
+    write_file(
+        &root.join("crates/app/Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nfancy = { path = \"../../external/fancy\", optional = true }\nextra = { path = \"../../external/extra\", optional = true }\n\n[features]\ndefault = [\"fancy\"]\n",
+    );
+    write_file(&root.join("crates/app/src/lib.rs"), "pub fn app() {}\n");
+    write_file(
+        &root.join("crates/tool/Cargo.toml"),
+        "[package]\nname = \"tool\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write_file(&root.join("crates/tool/src/lib.rs"), "pub fn tool() {}\n");
+    for name in ["fancy", "extra"] {
+        write_file(
+            &root.join(format!("external/{name}/Cargo.toml")),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+        );
+        write_file(
+            &root.join(format!("external/{name}/src/lib.rs")),
+            "pub fn f() {}\n",
+        );
+    }
+
+    temp_dir
+}
+
+#[test]
+fn tree_feature_flags_select_the_resolved_graph() {
+    let temp_dir = create_feature_workspace();
+    let root = temp_dir.path();
+
+    let output = run_tree(root, &[]);
+    assert!(find_node(&output["root"], "fancy").is_some());
+    assert!(find_node(&output["root"], "extra").is_some());
+
+    let output = run_tree(root, &["-p", "app"]);
+    assert_eq!(output["root"]["name"], "app");
+    assert!(find_node(&output["root"], "fancy").is_some());
+
+    let output = run_tree(root, &["-p", "app", "--no-default-features"]);
+    assert!(find_node(&output["root"], "fancy").is_none());
+    assert!(find_node(&output["root"], "extra").is_none());
+
+    let output = run_tree(
+        root,
+        &["-p", "app", "--no-default-features", "--features", "extra"],
+    );
+    assert!(find_node(&output["root"], "fancy").is_none());
+    assert!(find_node(&output["root"], "extra").is_some());
+}
+
+#[test]
+fn tree_rejects_unknown_package() {
+    let temp_dir = create_feature_workspace();
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(temp_dir.path())
+        .args(["tree", "-p", "missing"])
+        .output()
+        .expect("run tree");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("package `missing`"));
+}
+
+#[test]
+fn tree_prune_hides_matching_subtrees_and_updates_stats() {
+    let temp_dir = create_tree_workspace();
+    let root = temp_dir.path();
+
+    let output = run_tree(root, &["--prune", "dup", "--prune", "/^build_/"]);
+    assert!(find_node(&output["root"], "dup").is_none());
+    assert!(find_node(&output["root"], "build_only").is_none());
+    assert!(find_node(&output["root"], "leaf").is_some());
+    assert_eq!(output["stats"]["duplicate_crates"], 0);
+
+    // Workspace members are never pruned.
+    let output = run_tree(root, &["--prune", "mid"]);
+    assert!(find_node(&output["root"], "mid").is_some());
+}
+
+#[test]
+fn tree_exclude_source_keeps_workspace_members() {
+    let temp_dir = create_tree_workspace();
+    let root = temp_dir.path();
+
+    let output = run_tree(root, &["--exclude-source", "path"]);
+    let mut nodes = Vec::new();
+    collect_nodes(&output["root"], &mut nodes);
+    let names: HashSet<&str> = nodes
+        .iter()
+        .filter_map(|node| node["name"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        HashSet::from(["(workspace)", "app", "dep_a", "dep_b", "mid"])
+    );
+    assert_eq!(output["stats"]["total_crates"], 4);
+}
+
+#[test]
+fn tree_only_direct_lists_member_dependencies() {
+    let temp_dir = create_tree_workspace();
+    let root = temp_dir.path();
+
+    let output = run_tree(root, &["--only-direct"]);
+    let mid = find_node(&output["root"], "mid").expect("mid");
+    assert_eq!(mid["dependencies"][0]["name"], "leaf");
+
+    let mut nodes = Vec::new();
+    collect_nodes(&output["root"], &mut nodes);
+    let members = ["(workspace)", "app", "dep_a", "dep_b", "mid"];
+    for node in nodes {
+        let name = node["name"].as_str().unwrap();
+        if !members.contains(&name) {
+            assert!(
+                node["dependencies"].as_array().unwrap().is_empty(),
+                "{name} should not list dependencies"
+            );
+        }
+    }
+}
+
+#[test]
+fn tree_invert_accepts_glob_patterns() {
+    let temp_dir = create_tree_workspace();
+    let root = temp_dir.path();
+
+    let output = run_tree(root, &["--invert", "build_*"]);
+    assert_eq!(output["root"]["name"], "build_only");
+    assert!(find_node(&output["root"], "dep_a").is_some());
+}
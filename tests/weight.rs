@@ -0,0 +1,77 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::write_crate;
+use serde_json::Value;
+use std::fs;
+
+fn create_weight_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\"]\nexclude = [\"external/heavy\", \"external/light\", \"external/inner\", \"external/common\"]\n",
+    )
+    .expect("write workspace");
+    write_crate(
+        root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nheavy = { path = \"../../external/heavy\" }\nlight = { path = \"../../external/light\" }\n",
+    );
+    write_crate(
+        root,
+        "external/heavy",
+        "[package]\nname = \"heavy\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ninner = { path = \"../inner\" }\ncommon = { path = \"../common\" }\n",
+    );
+    fs::write(root.join("external/heavy/build.rs"), "fn main() {}\n").expect("write build script");
+    write_crate(
+        root,
+        "external/light",
+        "[package]\nname = \"light\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ncommon = { path = \"../common\" }\n",
+    );
+    write_crate(
+        root,
+        "external/inner",
+        "[package]\nname = \"inner\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write_crate(
+        root,
+        "external/common",
+        "[package]\nname = \"common\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+#[test]
+fn weight_splits_unique_and_shared_crates() {
+    let workspace = create_weight_workspace();
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    let output = cmd
+        .current_dir(workspace.path())
+        .args(["upkeep", "weight", "--json"])
+        .output()
+        .expect("run weight");
+    assert!(
+        output.status.success(),
+        "weight failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output: Value = serde_json::from_slice(&output.stdout).expect("json output");
+
+    let member = &output["members"][0];
+    assert_eq!(member["name"], "app");
+    let heavy = &member["dependencies"][0];
+    assert_eq!(heavy["name"], "heavy");
+    assert_eq!(heavy["unique"], 2);
+    assert_eq!(heavy["shared"], 1);
+    assert_eq!(heavy["total"], 3);
+    assert_eq!(heavy["build_scripts"], 1);
+    assert_eq!(heavy["source_bytes"], 0);
+
+    let light = &member["dependencies"][1];
+    assert_eq!(light["name"], "light");
+    assert_eq!(light["unique"], 1);
+    assert_eq!(light["shared"], 1);
+}
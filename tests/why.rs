@@ -0,0 +1,101 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use common::{write_crate, write_file};
+use serde_json::Value;
+use std::path::Path;
+
+fn create_why_workspace() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let root = temp_dir.path();
+
+    write_file(
+        &root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/app\"]\nexclude = [\"external/mid\", \"external/leaf\", \"external/tls\"]\n",
+    );
+    write_crate(
+        root,
+        "crates/app",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nmid = { path = \"../../external/mid\" }\nleaf = { path = \"../../external/leaf\" }\ntls = { path = \"../../external/tls\", optional = true }\n\n[features]\ndefault = [\"secure\"]\nsecure = [\"dep:tls\"]\n",
+    );
+    write_crate(
+        root,
+        "external/mid",
+        "[package]\nname = \"mid\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[build-dependencies]\nleaf = { path = \"../leaf\" }\n",
+    );
+    write_crate(
+        root,
+        "external/leaf",
+        "[package]\nname = \"leaf\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write_crate(
+        root,
+        "external/tls",
+        "[package]\nname = \"tls\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+
+    temp_dir
+}
+
+fn run_why(root: &Path, args: &[&str]) -> std::process::Output {
+    let mut cmd = cargo_bin_cmd!("cargo-upkeep");
+    cmd.current_dir(root)
+        .args(["upkeep", "why", "--json"])
+        .args(args)
+        .output()
+        .expect("run why")
+}
+
+fn run_why_json(root: &Path, args: &[&str]) -> Value {
+    let output = run_why(root, args);
+    assert!(
+        output.status.success(),
+        "why failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[test]
+fn why_lists_every_path_with_kinds() {
+    let workspace = create_why_workspace();
+    let output = run_why_json(workspace.path(), &["leaf"]);
+
+    assert_eq!(output["crate"], "leaf");
+    assert_eq!(output["versions"][0], "0.1.0");
+    let paths = output["paths"].as_array().expect("paths");
+    assert_eq!(paths.len(), 2);
+
+    let via_mid = paths
+        .iter()
+        .find(|path| path["hops"].as_array().unwrap().len() == 2)
+        .expect("path through mid");
+    assert_eq!(via_mid["member"], "app");
+    assert_eq!(via_mid["hops"][0]["to"], "mid");
+    assert_eq!(via_mid["hops"][1]["from"], "mid");
+    assert_eq!(via_mid["hops"][1]["kinds"][0]["kind"], "build");
+}
+
+#[test]
+fn why_names_the_feature_that_enabled_an_optional_dependency() {
+    let workspace = create_why_workspace();
+    let output = run_why_json(workspace.path(), &["tls@0.1"]);
+
+    let hop = &output["paths"][0]["hops"][0];
+    assert_eq!(hop["from"], "app");
+    assert_eq!(hop["optional"], true);
+    assert_eq!(hop["enabled_by"][0], "secure");
+
+    // Without the feature the optional crate is not resolved at all.
+    let output = run_why(workspace.path(), &["tls", "--no-default-features"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn why_fails_for_unknown_crate() {
+    let workspace = create_why_workspace();
+    let output = run_why(workspace.path(), &["missing"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing"));
+}